@@ -1,20 +1,179 @@
-use clap::{crate_description, crate_name, crate_version, value_t_or_exit, App, Arg, SubCommand};
-use insurance_contract::state::{InsuranceContractData, INSURANCE_CONTRACT_DATA_LEN};
+use chrono::{TimeZone, Utc};
+use clap::{
+    crate_description, crate_name, crate_version, value_t_or_exit, App, Arg, ArgMatches, SubCommand,
+};
+use insurance_contract::{
+    error::InsuranceContractError,
+    events::decode_events,
+    find_config_address, find_insurance_product_address, find_policy_token_address,
+    find_vault_address,
+    state::{
+        ConfigData, ConfigSettings, DocumentUri, InsuranceContractData, InsuranceContractStatus,
+        InsuranceProductData, Peril, PolicyTerms, ProductTerms, BASIS_POINTS, MAX_ALLOWED_MINTS,
+        MAX_DOCUMENT_URI_LEN, MAX_PERILS, NO_PERIL,
+    },
+};
 use solana_clap_utils::{
     fee_payer::fee_payer_arg,
-    input_validators::{is_url_or_moniker, is_valid_pubkey, normalize_to_url_if_moniker},
+    input_parsers::{pubkey_of, unix_timestamp_from_rfc3339_datetime},
+    input_validators::{
+        is_amount, is_keypair, is_parsable, is_rfc3339_datetime, is_url_or_moniker,
+        is_valid_pubkey, normalize_to_url_if_moniker,
+    },
+};
+use solana_client::{
+    client_error::ClientError, rpc_client::RpcClient, rpc_request::TokenAccountsFilter,
 };
-use solana_client::rpc_client::RpcClient;
-use solana_program::borsh::try_from_slice_unchecked;
 use solana_sdk::{
-    instruction::Instruction,
+    clock::{UnixTimestamp, SECONDS_PER_DAY},
+    decode_error::DecodeError,
+    hash::hash,
+    instruction::InstructionError,
+    native_token::{lamports_to_sol, sol_to_lamports},
+    program_pack::Pack,
     pubkey::Pubkey,
     signature::{read_keypair_file, Keypair, Signer},
     system_instruction,
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
+};
+use solana_transaction_status::UiTransactionEncoding;
+use spl_token::{
+    amount_to_ui_amount,
+    state::{Account as TokenAccount, Mint},
+    ui_amount_to_amount,
 };
 
 // Helper functions
+fn coverage_period_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("coverage_period")
+        .long("period-days")
+        .validator(is_parsable::<u32>)
+        .value_name("DAYS")
+        .takes_value(true)
+        .required(true)
+        .help("Days the coverage of contracts issued from the product lasts")
+}
+
+/// Arguments of the terms shared by InsuranceContracts and insurance products
+fn terms_args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("sum_insured")
+            .long("sum-insured")
+            .validator(is_amount)
+            .value_name("AMOUNT")
+            .takes_value(true)
+            .required(true)
+            .help("Sum insured, in premium mint tokens"),
+        Arg::with_name("premium_amount")
+            .long("premium")
+            .validator(is_amount)
+            .value_name("AMOUNT")
+            .takes_value(true)
+            .required(true)
+            .help("Premium amount, in premium mint tokens"),
+        Arg::with_name("premium_mint")
+            .long("mint")
+            .validator(is_valid_pubkey)
+            .value_name("PUBKEY")
+            .takes_value(true)
+            .required(true)
+            .help("Mint of the premium currency"),
+        Arg::with_name("claims_adjuster")
+            .long("adjuster")
+            .validator(is_valid_pubkey)
+            .value_name("PUBKEY")
+            .takes_value(true)
+            .help("Authority allowed to approve or reject claims [default: fee payer]"),
+        Arg::with_name("keeper_reward")
+            .long("keeper-reward")
+            .validator(is_amount)
+            .value_name("SOL")
+            .takes_value(true)
+            .default_value("0")
+            .help("Reward reserved for the keeper expiring the contract"),
+        Arg::with_name("cancellation_penalty_bps")
+            .long("cancellation-penalty")
+            .validator(is_valid_basis_points)
+            .value_name("BPS")
+            .takes_value(true)
+            .default_value("0")
+            .help("Share of the unearned premium kept on cancellation, in basis points"),
+        Arg::with_name("no_claims_bonus_bps")
+            .long("no-claims-bonus")
+            .validator(is_valid_basis_points)
+            .value_name("BPS")
+            .takes_value(true)
+            .default_value("0")
+            .help("Renewal premium discount per claim-free term, in basis points"),
+        Arg::with_name("installment_count")
+            .long("installments")
+            .validator(is_parsable::<u8>)
+            .value_name("COUNT")
+            .takes_value(true)
+            .requires("installment_interval")
+            .help(
+                "Pay the premium in equal installments instead of at once, \
+                 the premium must divide evenly by the count",
+            ),
+        Arg::with_name("installment_interval")
+            .long("interval-days")
+            .validator(is_parsable::<u32>)
+            .value_name("DAYS")
+            .takes_value(true)
+            .requires("installment_count")
+            .help("Days between installment due dates"),
+        Arg::with_name("grace_period")
+            .long("grace-days")
+            .validator(is_parsable::<u32>)
+            .value_name("DAYS")
+            .takes_value(true)
+            .default_value("0")
+            .help("Days an installment can stay unpaid before the contract lapses"),
+        Arg::with_name("waiting_period")
+            .long("waiting-days")
+            .validator(is_parsable::<u32>)
+            .value_name("DAYS")
+            .takes_value(true)
+            .default_value("0")
+            .help("Days after the coverage start incidents are not covered in"),
+        Arg::with_name("assignment_consent")
+            .long("assignment-consent")
+            .takes_value(false)
+            .help("Require the authority to sign assignments of the policy"),
+        Arg::with_name("deductible")
+            .long("deductible")
+            .validator(is_amount)
+            .value_name("AMOUNT")
+            .takes_value(true)
+            .default_value("0")
+            .help("Part of each claim borne by the policyholder"),
+        Arg::with_name("per_occurrence_limit")
+            .long("per-claim-limit")
+            .validator(is_amount)
+            .value_name("AMOUNT")
+            .takes_value(true)
+            .help("Maximum payout of a single claim [default: unlimited]"),
+        Arg::with_name("annual_aggregate_limit")
+            .long("aggregate-limit")
+            .validator(is_amount)
+            .value_name("AMOUNT")
+            .takes_value(true)
+            .help("Maximum payouts within a policy year [default: unlimited]"),
+        Arg::with_name("peril")
+            .long("peril")
+            .validator(is_valid_peril)
+            .value_name("CODE:LIMIT")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .max_values(MAX_PERILS as u64)
+            .help(
+                "Covered peril and its sub-limit, 0 for none. \
+                May be repeated [default: all risks]",
+            ),
+    ]
+}
+
 fn get_clap_app<'a, 'b>(name: &'a str, desc: &'a str, version: &'a str) -> App<'a, 'b> {
     App::new(name)
         .about(desc)
@@ -36,103 +195,1557 @@ fn get_clap_app<'a, 'b>(name: &'a str, desc: &'a str, version: &'a str) -> App<'
         )
         .subcommand(
             SubCommand::with_name("save")
-                .about("Creates on-chain account stored the InsuranceContract identifier")
+                .about("Creates on-chain account stored the InsuranceContract identifier and terms")
                 .arg(
                     Arg::with_name("insurance_contract_id")
                         .validator(is_valid_id)
                         .value_name("u32")
                         .takes_value(true)
                         .help("Insurance contract ID"),
+                )
+                .arg(
+                    Arg::with_name("coverage_start")
+                        .long("start")
+                        .validator(is_rfc3339_datetime)
+                        .value_name("RFC3339 DATETIME")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Beginning of the coverage period"),
+                )
+                .arg(
+                    Arg::with_name("coverage_end")
+                        .long("end")
+                        .validator(is_rfc3339_datetime)
+                        .value_name("RFC3339 DATETIME")
+                        .takes_value(true)
+                        .required(true)
+                        .help("End of the coverage period"),
+                )
+                .args(&terms_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("create-product")
+                .about("Creates insurance product account InsuranceContracts are issued from")
+                .arg(
+                    Arg::with_name("product_id")
+                        .validator(is_valid_id)
+                        .value_name("u32")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance product ID"),
+                )
+                .arg(coverage_period_arg())
+                .args(&terms_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("update-product")
+                .about("Replaces the terms of contracts issued from insurance product from now on")
+                .arg(
+                    Arg::with_name("product")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance product account"),
+                )
+                .arg(coverage_period_arg())
+                .args(&terms_args()),
+        )
+        .subcommand(
+            SubCommand::with_name("issue")
+                .about("Creates InsuranceContract account with the terms of an insurance product")
+                .arg(
+                    Arg::with_name("product")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance product account of the fee payer"),
+                )
+                .arg(
+                    Arg::with_name("insurance_contract_id")
+                        .validator(is_valid_id)
+                        .value_name("u32")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract ID"),
+                )
+                .arg(
+                    Arg::with_name("coverage_start")
+                        .long("start")
+                        .validator(is_rfc3339_datetime)
+                        .value_name("RFC3339 DATETIME")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Beginning of the coverage period"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("retire-product")
+                .about("Retires insurance product, no more contracts can be issued from it")
+                .arg(
+                    Arg::with_name("product")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance product account"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("renew")
+                .about("Renews InsuranceContract with a successor paid from a token account")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account to renew"),
+                )
+                .arg(
+                    Arg::with_name("insurance_contract_id")
+                        .validator(is_valid_id)
+                        .value_name("u32")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Successor insurance contract ID"),
+                )
+                .arg(
+                    Arg::with_name("coverage_end")
+                        .long("end")
+                        .validator(is_rfc3339_datetime)
+                        .value_name("RFC3339 DATETIME")
+                        .takes_value(true)
+                        .required(true)
+                        .help("End of the successor coverage period"),
+                )
+                .arg(
+                    Arg::with_name("premium_amount")
+                        .long("premium")
+                        .validator(is_amount)
+                        .value_name("AMOUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Renewal premium before the no-claims discount"),
+                )
+                .arg(
+                    Arg::with_name("token_account")
+                        .long("from")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Payer token account holding premium mint tokens"),
+                )
+                .arg(
+                    Arg::with_name("premium_payer")
+                        .long("premium-payer")
+                        .validator(is_keypair)
+                        .value_name("KEYPAIR")
+                        .takes_value(true)
+                        .help("Owner of the payer token account [default: fee payer]"),
                 ),
         )
         .subcommand(
             SubCommand::with_name("close")
-                .about("Set up is_closed status on InsuranceContract account")
+                .about(
+                    "Closes InsuranceContract, --reclaim returns its rent and the vault premium",
+                )
                 .arg(
                     Arg::with_name("address")
                         .value_name("PUBKEY")
                         .validator(is_valid_pubkey)
                         .takes_value(true)
                         .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("reclaim")
+                        .long("reclaim")
+                        .takes_value(false)
+                        .help("Zero the closed account and return its rent to the destination"),
+                )
+                .arg(
+                    Arg::with_name("destination")
+                        .long("destination")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .requires("reclaim")
+                        .help("Account receiving the reclaimed rent [default: fee payer]"),
+                )
+                .arg(
+                    Arg::with_name("premium_to")
+                        .long("premium-to")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .requires("reclaim")
+                        .help(
+                            "Token account receiving the premium left in the vault \
+                             [default: token account of the fee payer]",
+                        ),
                 ),
         )
-        .subcommand(
-            SubCommand::with_name("show")
-                .about("Show InsuranceContract account data")
-                .arg(
-                    Arg::with_name("address")
-                        .value_name("PUBKEY")
-                        .validator(is_valid_pubkey)
-                        .takes_value(true)
-                        .help("Insurance contract data account"),
-                ),
+        .subcommand(
+            SubCommand::with_name("migrate")
+                .about(
+                    "Moves legacy InsuranceContract account into the account derived from its id, \
+                     accounts without an authority are migrated by the config admin",
+                )
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Legacy insurance contract data account"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pay-premium")
+                .about(
+                    "Pays InsuranceContract premium from a token account into the contract vault",
+                )
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .validator(is_amount)
+                        .value_name("AMOUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Premium amount to pay, in premium mint tokens"),
+                )
+                .arg(
+                    Arg::with_name("token_account")
+                        .long("from")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Payer token account holding premium mint tokens"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pay-installment")
+                .about(
+                    "Pays the next InsuranceContract premium installment into the contract vault",
+                )
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("token_account")
+                        .long("from")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Payer token account holding premium mint tokens"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("lapse")
+                .about(
+                    "Lapses InsuranceContract whose installment is overdue after the grace period",
+                )
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("cancel")
+                .about("Cancels InsuranceContract and refunds the unearned premium")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("refund_token_account")
+                        .long("to")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Policyholder token account receiving the refund"),
+                )
+                .arg(
+                    Arg::with_name("reason")
+                        .long("reason")
+                        .validator(is_parsable::<u8>)
+                        .value_name("CODE")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Cancellation reason code"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("expire")
+                .about("Expires InsuranceContract and collects the keeper reward")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set-authority")
+                .about("Proposes a new InsuranceContract authority or withdraws the proposal")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("new_authority")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required_unless("cancel")
+                        .help("Authority proposed to take over the insurance contract"),
+                )
+                .arg(
+                    Arg::with_name("cancel")
+                        .long("cancel")
+                        .takes_value(false)
+                        .conflicts_with("new_authority")
+                        .help("Withdraw the pending authority proposal"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("accept-authority")
+                .about("Accepts the InsuranceContract authority proposed to the fee payer")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("assign")
+                .about("Assigns InsuranceContract held by the fee payer to a new policyholder")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("new_holder")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Policyholder the insurance contract is assigned to"),
+                )
+                .arg(
+                    Arg::with_name("authority")
+                        .long("authority")
+                        .validator(is_keypair)
+                        .value_name("KEYPAIR")
+                        .takes_value(true)
+                        .help("Authority consenting to the assignment [default: fee payer]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("claim-payout")
+                .about("Previews the payout of a claim filed now against InsuranceContract")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("amount")
+                        .value_name("AMOUNT")
+                        .validator(is_amount)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Claimed amount"),
+                )
+                .arg(
+                    Arg::with_name("peril")
+                        .long("peril")
+                        .validator(is_parsable::<u16>)
+                        .value_name("CODE")
+                        .takes_value(true)
+                        .help("Peril of the claim [default: none]"),
+                )
+                .arg(
+                    Arg::with_name("incident_time")
+                        .long("incident-time")
+                        .validator(is_rfc3339_datetime)
+                        .value_name("RFC3339 DATETIME")
+                        .takes_value(true)
+                        .help("Time of the incident [default: now]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("set-config")
+                .about("Initializes or updates the program config administered by the fee payer")
+                .arg(
+                    Arg::with_name("protocol_fee_bps")
+                        .long("protocol-fee")
+                        .validator(is_valid_basis_points)
+                        .value_name("BPS")
+                        .takes_value(true)
+                        .default_value("0")
+                        .help("Fee charged on top of every premium payment, in basis points"),
+                )
+                .arg(
+                    Arg::with_name("treasury")
+                        .long("treasury")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .help("Owner of the token accounts receiving the protocol fees"),
+                )
+                .arg(
+                    Arg::with_name("allowed_mint")
+                        .long("allowed-mint")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .max_values(MAX_ALLOWED_MINTS as u64)
+                        .help("Mint premiums can be paid in. May be repeated [default: any mint]"),
+                )
+                .arg(
+                    Arg::with_name("guardian")
+                        .long("guardian")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .help("Key allowed to pause the program [default: the fee payer]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("pause")
+                .about("Stops new business of the program, or of one product, signed by the guardian")
+                .arg(
+                    Arg::with_name("product")
+                        .long("product")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .help("Insurance product to pause [default: the whole program]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("unpause")
+                .about("Resumes new business of the program, or of one product, signed by the guardian")
+                .arg(
+                    Arg::with_name("product")
+                        .long("product")
+                        .validator(is_valid_pubkey)
+                        .value_name("PUBKEY")
+                        .takes_value(true)
+                        .help("Insurance product to unpause [default: the whole program]"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("amend-document")
+                .about("Anchors the hash and URI of the InsuranceContract policy document")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Policy document, hashed with SHA-256"),
+                )
+                .arg(
+                    Arg::with_name("uri")
+                        .long("uri")
+                        .value_name("URI")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Where the policy document is published"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify-document")
+                .about("Checks a policy document against the hash anchored on the InsuranceContract")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .required(true)
+                        .help("Insurance contract data account"),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Policy document to verify"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("show")
+                .about("Show InsuranceContract account data")
+                .arg(
+                    Arg::with_name("address")
+                        .value_name("PUBKEY")
+                        .validator(is_valid_pubkey)
+                        .takes_value(true)
+                        .help("Insurance contract data account"),
+                ),
+        )
+}
+
+fn is_valid_id(string: String) -> Result<(), String> {
+    match string.parse::<u32>() {
+        Ok(_) => Ok(()),
+        Err(_) => Err(format!("Invalid id {}", string)),
+    }
+}
+
+fn is_valid_basis_points(string: String) -> Result<(), String> {
+    match string.parse::<u16>() {
+        Ok(bps) if bps <= BASIS_POINTS => Ok(()),
+        _ => Err(format!("Invalid basis points {}", string)),
+    }
+}
+
+fn parse_peril(string: &str) -> Option<(u16, f64)> {
+    let mut parts = string.splitn(2, ':');
+    let code = parts.next()?.parse::<u16>().ok()?;
+    let sub_limit = parts.next()?.parse::<f64>().ok()?;
+    if code == NO_PERIL || sub_limit.is_nan() || sub_limit < 0. {
+        return None;
+    }
+    Some((code, sub_limit))
+}
+
+fn is_valid_peril(string: String) -> Result<(), String> {
+    match parse_peril(&string) {
+        Some(_) => Ok(()),
+        None => Err(format!("Invalid peril {}, expected CODE:LIMIT", string)),
+    }
+}
+
+/// Parses the terms shared by InsuranceContracts and insurance products
+fn product_terms_of(
+    client: &RpcClient,
+    arg_matches: &ArgMatches,
+    payer: &Keypair,
+    coverage_period: UnixTimestamp,
+) -> ProductTerms {
+    let premium_mint = value_t_or_exit!(arg_matches, "premium_mint", Pubkey);
+    let decimals = get_mint_decimals(client, &premium_mint);
+    let premium_amount = ui_amount_to_amount(
+        value_t_or_exit!(arg_matches, "premium_amount", f64),
+        decimals,
+    );
+    let installment_count = if arg_matches.is_present("installment_count") {
+        value_t_or_exit!(arg_matches, "installment_count", u8)
+    } else {
+        0
+    };
+    let installment_amount = if installment_count > 0 {
+        premium_amount / installment_count as u64
+    } else {
+        0
+    };
+    // The program only accepts installments adding up to the premium exactly
+    if installment_count > 0 && installment_amount * installment_count as u64 != premium_amount {
+        eprintln!(
+            "Error: premium {} can't be split into {} equal installments",
+            amount_to_ui_amount(premium_amount, decimals),
+            installment_count
+        );
+        std::process::exit(1);
+    }
+    let installment_interval = if arg_matches.is_present("installment_interval") {
+        value_t_or_exit!(arg_matches, "installment_interval", u32) as UnixTimestamp
+            * SECONDS_PER_DAY as UnixTimestamp
+    } else {
+        0
+    };
+    let mut perils = [Peril::default(); MAX_PERILS];
+    for (peril, value) in perils
+        .iter_mut()
+        .zip(arg_matches.values_of("peril").into_iter().flatten())
+    {
+        let (code, sub_limit) = parse_peril(value).unwrap();
+        *peril = Peril {
+            code,
+            sub_limit: ui_amount_to_amount(sub_limit, decimals),
+        };
+    }
+    ProductTerms {
+        coverage_period,
+        sum_insured: ui_amount_to_amount(
+            value_t_or_exit!(arg_matches, "sum_insured", f64),
+            decimals,
+        ),
+        premium_amount,
+        premium_mint,
+        claims_adjuster: if arg_matches.is_present("claims_adjuster") {
+            value_t_or_exit!(arg_matches, "claims_adjuster", Pubkey)
+        } else {
+            payer.pubkey()
+        },
+        keeper_reward: sol_to_lamports(value_t_or_exit!(arg_matches, "keeper_reward", f64)),
+        cancellation_penalty_bps: value_t_or_exit!(arg_matches, "cancellation_penalty_bps", u16),
+        no_claims_bonus_bps: value_t_or_exit!(arg_matches, "no_claims_bonus_bps", u16),
+        installment_count,
+        installment_amount,
+        installment_interval,
+        grace_period: value_t_or_exit!(arg_matches, "grace_period", u32) as UnixTimestamp
+            * SECONDS_PER_DAY as UnixTimestamp,
+        assignment_consent_required: arg_matches.is_present("assignment_consent"),
+        deductible: ui_amount_to_amount(value_t_or_exit!(arg_matches, "deductible", f64), decimals),
+        per_occurrence_limit: if arg_matches.is_present("per_occurrence_limit") {
+            ui_amount_to_amount(
+                value_t_or_exit!(arg_matches, "per_occurrence_limit", f64),
+                decimals,
+            )
+        } else {
+            0
+        },
+        annual_aggregate_limit: if arg_matches.is_present("annual_aggregate_limit") {
+            ui_amount_to_amount(
+                value_t_or_exit!(arg_matches, "annual_aggregate_limit", f64),
+                decimals,
+            )
+        } else {
+            0
+        },
+        perils,
+        waiting_period: value_t_or_exit!(arg_matches, "waiting_period", u32) as UnixTimestamp
+            * SECONDS_PER_DAY as UnixTimestamp,
+    }
+}
+
+fn get_mint_decimals(client: &RpcClient, mint: &Pubkey) -> u8 {
+    let mint_account = client.get_account(mint).unwrap();
+    Mint::unpack(&mint_account.data).unwrap().decimals
+}
+
+/// Token account of the holder holding the policy token, or the derived policy token
+/// address when the policy was saved before tokenization
+fn get_policy_token_account(client: &RpcClient, data_address: &Pubkey, holder: &Pubkey) -> Pubkey {
+    let insurance_account = client.get_account(data_address).unwrap();
+    let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+    if insurance_data.is_tokenized() {
+        if let Some(keyed_account) = client
+            .get_token_accounts_by_owner(
+                holder,
+                TokenAccountsFilter::Mint(insurance_data.policy_mint),
+            )
+            .unwrap()
+            .first()
+        {
+            return keyed_account.pubkey.parse().unwrap();
+        }
+    }
+    find_policy_token_address(&insurance_contract::id(), data_address).0
+}
+
+/// Token account receiving the protocol fee of a premium paid in the given mint. The
+/// payer token account stands in while no fee is charged.
+fn get_treasury_token_account(
+    client: &RpcClient,
+    premium_mint: &Pubkey,
+    payer_token_account: &Pubkey,
+) -> Pubkey {
+    let (config_address, _) = find_config_address(&insurance_contract::id());
+    let config_data = match client.get_account(&config_address) {
+        Ok(config_account) => ConfigData::unpack(&config_account.data).unwrap(),
+        Err(_) => return *payer_token_account,
+    };
+    if !config_data.is_initialized() || config_data.settings.protocol_fee_bps == 0 {
+        return *payer_token_account;
+    }
+    client
+        .get_token_accounts_by_owner(
+            &config_data.settings.treasury,
+            TokenAccountsFilter::Mint(*premium_mint),
+        )
+        .unwrap()
+        .first()
+        .map(|keyed_account| keyed_account.pubkey.parse().unwrap())
+        .unwrap_or(*payer_token_account)
+}
+
+/// Token account of the fee payer receiving the premium left in the vault of a reclaimed
+/// InsuranceContract. The vault stands in while it is empty.
+fn get_premium_token_account(
+    client: &RpcClient,
+    data_address: &Pubkey,
+    premium_mint: &Pubkey,
+    payer: &Keypair,
+) -> Pubkey {
+    let (vault_address, _) = find_vault_address(&insurance_contract::id(), data_address);
+    let vault_amount = client
+        .get_account(&vault_address)
+        .ok()
+        .and_then(|vault_account| TokenAccount::unpack(&vault_account.data).ok())
+        .map_or(0, |vault| vault.amount);
+    if vault_amount == 0 {
+        return vault_address;
+    }
+    client
+        .get_token_accounts_by_owner(&payer.pubkey(), TokenAccountsFilter::Mint(*premium_mint))
+        .unwrap()
+        .first()
+        .map(|keyed_account| keyed_account.pubkey.parse().unwrap())
+        .unwrap_or_else(|| {
+            eprintln!(
+                "Error: the vault holds premium, --premium-to must name a token account of {}",
+                premium_mint
+            );
+            std::process::exit(1);
+        })
+}
+
+/// Prints the error of a failed transaction, decoding the program errors, and exits
+fn exit_with_client_error(error: ClientError) -> ! {
+    match error.get_transaction_error() {
+        Some(TransactionError::InstructionError(index, InstructionError::Custom(code))) => {
+            let program_error: Option<InsuranceContractError> =
+                InsuranceContractError::decode_custom_error_to_enum(code);
+            match program_error {
+                Some(program_error) => eprintln!(
+                    "Error: instruction {} failed: {} (error code {})",
+                    index, program_error, code
+                ),
+                None => eprintln!(
+                    "Error: instruction {} failed: custom program error {}",
+                    index, code
+                ),
+            }
+        }
+        Some(transaction_error) => eprintln!("Error: {}", transaction_error),
+        None => eprintln!("Error: {}", error),
+    }
+    std::process::exit(1);
+}
+
+/// Sends the transaction and prints the program events it emitted
+fn send_transaction(client: &RpcClient, transaction: &Transaction) {
+    let signature = match client.send_and_confirm_transaction_with_spinner(transaction) {
+        Ok(signature) => signature,
+        Err(error) => exit_with_client_error(error),
+    };
+    let logs = client
+        .get_transaction(&signature, UiTransactionEncoding::Json)
+        .ok()
+        .and_then(|transaction| transaction.transaction.meta)
+        .and_then(|meta| meta.log_messages)
+        .unwrap_or_default();
+    for event in decode_events(&insurance_contract::id(), &logs) {
+        println!("Event: {:?}", event);
+    }
+}
+
+fn format_document_hash(document_hash: &[u8; 32]) -> String {
+    document_hash
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn hash_document_file(path: &str) -> [u8; 32] {
+    let document = std::fs::read(path).unwrap_or_else(|error| {
+        eprintln!("Error: can't read {}: {}", path, error);
+        std::process::exit(1);
+    });
+    hash(&document).to_bytes()
+}
+
+fn format_timestamp(timestamp: UnixTimestamp) -> String {
+    Utc.timestamp(timestamp, 0).to_rfc3339()
+}
+
+// CLI commands handlers
+fn save(client: &RpcClient, payer: &Keypair, id: u32, terms: PolicyTerms) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::save_insurance_contract(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            id,
+            terms,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn create_product(client: &RpcClient, payer: &Keypair, product_id: u32, terms: ProductTerms) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::create_product(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            product_id,
+            terms,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn update_product(
+    client: &RpcClient,
+    payer: &Keypair,
+    product_address: &Pubkey,
+    terms: ProductTerms,
+) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::update_product(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            product_address,
+            terms,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn issue(
+    client: &RpcClient,
+    payer: &Keypair,
+    product_address: &Pubkey,
+    product_terms: &ProductTerms,
+    id: u32,
+    coverage_start: UnixTimestamp,
+) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[
+            insurance_contract::instruction::save_insurance_contract_from_product(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                id,
+                product_address,
+                product_terms,
+                coverage_start,
+            )
+            .unwrap(),
+        ],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn retire_product(client: &RpcClient, payer: &Keypair, product_address: &Pubkey) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::retire_product(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            product_address,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn renew(
+    client: &RpcClient,
+    payer: &Keypair,
+    premium_payer: &Keypair,
+    token_account: &Pubkey,
+    data_address: &Pubkey,
+    premium_mint: &Pubkey,
+    id: u32,
+    coverage_end: UnixTimestamp,
+    premium_amount: u64,
+    product: Option<&Pubkey>,
+) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::renew_insurance_contract(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            &premium_payer.pubkey(),
+            token_account,
+            data_address,
+            premium_mint,
+            &get_policy_token_account(client, data_address, &payer.pubkey()),
+            id,
+            coverage_end,
+            premium_amount,
+            &get_treasury_token_account(client, premium_mint, token_account),
+            product,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer, premium_payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn close(
+    client: &RpcClient,
+    payer: &Keypair,
+    data_address: &Pubkey,
+    reclaim_destination: Option<Pubkey>,
+    premium_token_account: Option<Pubkey>,
+) {
+    let insurance_account = client.get_account(data_address).unwrap();
+    let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+
+    let mut instructions = vec![];
+    if insurance_data.status != InsuranceContractStatus::Closed {
+        instructions.push(
+            insurance_contract::instruction::close_insurance_contract(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                data_address,
+                &payer.pubkey(),
+                &get_policy_token_account(client, data_address, &payer.pubkey()),
+            )
+            .unwrap(),
+        );
+    }
+    if let Some(destination) = reclaim_destination {
+        let premium_token_account = premium_token_account.unwrap_or_else(|| {
+            get_premium_token_account(client, data_address, &insurance_data.premium_mint, payer)
+        });
+        instructions.push(
+            insurance_contract::instruction::reclaim_insurance_contract(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                data_address,
+                &destination,
+                &premium_token_account,
+            )
+            .unwrap(),
+        );
+    }
+
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn migrate(client: &RpcClient, payer: &Keypair, legacy_data_address: &Pubkey, id: u32) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::migrate_insurance_contract(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            legacy_data_address,
+            id,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn pay_premium(
+    client: &RpcClient,
+    payer: &Keypair,
+    token_account: &Pubkey,
+    data_address: &Pubkey,
+    premium_mint: &Pubkey,
+    amount: u64,
+) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::pay_premium(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            token_account,
+            data_address,
+            premium_mint,
+            amount,
+            &get_treasury_token_account(client, premium_mint, token_account),
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn pay_installment(
+    client: &RpcClient,
+    payer: &Keypair,
+    token_account: &Pubkey,
+    data_address: &Pubkey,
+    premium_mint: &Pubkey,
+) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::pay_installment(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            token_account,
+            data_address,
+            premium_mint,
+            &get_treasury_token_account(client, premium_mint, token_account),
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn lapse(client: &RpcClient, payer: &Keypair, data_address: &Pubkey) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::lapse_insurance_contract(
+            &insurance_contract::id(),
+            data_address,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn cancel(
+    client: &RpcClient,
+    payer: &Keypair,
+    data_address: &Pubkey,
+    refund_token_account: &Pubkey,
+    reason: u8,
+) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::cancel_insurance_contract(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            data_address,
+            refund_token_account,
+            &get_policy_token_account(client, data_address, &payer.pubkey()),
+            reason,
         )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
 }
 
-fn is_valid_id(string: String) -> Result<(), String> {
-    match string.parse::<u32>() {
-        Ok(_) => Ok(()),
-        Err(_) => Err(format!("Invalid id {}", string)),
-    }
+fn expire(client: &RpcClient, payer: &Keypair, data_address: &Pubkey) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::expire_insurance_contract(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            data_address,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
 }
 
-// CLI commands handlers
-fn save(client: &RpcClient, payer: &Keypair, id: u32, data_address: &Keypair) {
-    let mut instructions = Vec::<Instruction>::with_capacity(4);
-    instructions.append(&mut vec![
-        system_instruction::create_account(
+fn set_authority(
+    client: &RpcClient,
+    payer: &Keypair,
+    data_address: &Pubkey,
+    new_authority: Option<Pubkey>,
+) {
+    let instruction = match new_authority {
+        Some(new_authority) => insurance_contract::instruction::propose_authority(
+            &insurance_contract::id(),
             &payer.pubkey(),
-            &data_address.pubkey(),
-            client
-                .get_minimum_balance_for_rent_exemption(INSURANCE_CONTRACT_DATA_LEN)
-                .unwrap(),
-            INSURANCE_CONTRACT_DATA_LEN as u64,
+            data_address,
+            &new_authority,
+        ),
+        None => insurance_contract::instruction::cancel_authority_proposal(
             &insurance_contract::id(),
+            &payer.pubkey(),
+            data_address,
         ),
-        insurance_contract::instruction::save_insurance_contract(
+    };
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction.unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn accept_authority(client: &RpcClient, payer: &Keypair, data_address: &Pubkey) {
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[insurance_contract::instruction::accept_authority(
             &insurance_contract::id(),
             &payer.pubkey(),
-            &data_address.pubkey(),
-            id,
+            data_address,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn assign(
+    client: &RpcClient,
+    payer: &Keypair,
+    data_address: &Pubkey,
+    new_holder: &Pubkey,
+    authority: Option<&Keypair>,
+) {
+    let insurance_account = client.get_account(data_address).unwrap();
+    let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+    let policy_token_account = get_policy_token_account(client, data_address, &payer.pubkey());
+    let new_holder_token_account = Keypair::new();
+    let new_holder_token_address = new_holder_token_account.pubkey();
+
+    let mut instructions = vec![];
+    let mut signers = vec![payer];
+    if insurance_data.is_tokenized() {
+        // The policy token moves to a new token account of the new holder
+        let lamports = client
+            .get_minimum_balance_for_rent_exemption(TokenAccount::LEN)
+            .unwrap();
+        instructions.push(system_instruction::create_account(
+            &payer.pubkey(),
+            &new_holder_token_account.pubkey(),
+            lamports,
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ));
+        instructions.push(
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &new_holder_token_account.pubkey(),
+                &insurance_data.policy_mint,
+                new_holder,
+            )
+            .unwrap(),
+        );
+        signers.push(&new_holder_token_account);
+        println!(
+            "Policy token account: {}",
+            new_holder_token_account.pubkey()
+        );
+    }
+    instructions.push(
+        insurance_contract::instruction::assign_policy(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            data_address,
+            new_holder,
+            if insurance_data.is_tokenized() {
+                Some((&policy_token_account, &new_holder_token_address))
+            } else {
+                None
+            },
+            authority.map(|authority| authority.pubkey()).as_ref(),
         )
         .unwrap(),
-    ]);
+    );
+    signers.extend(authority);
 
     let recent_blockhash = client.get_recent_blockhash().unwrap().0;
     let transaction = Transaction::new_signed_with_payer(
         &instructions,
         Some(&payer.pubkey()),
-        &[payer, data_address],
+        &signers,
         recent_blockhash,
     );
-    client
-        .send_and_confirm_transaction_with_spinner(&transaction)
-        .unwrap();
+    send_transaction(client, &transaction);
+}
+
+fn set_config(client: &RpcClient, payer: &Keypair, settings: ConfigSettings) {
+    let (config_address, _) = find_config_address(&insurance_contract::id());
+    let initialized = client
+        .get_account(&config_address)
+        .map(|config_account| {
+            ConfigData::unpack(&config_account.data)
+                .unwrap()
+                .is_initialized()
+        })
+        .unwrap_or(false);
+    let instruction = if initialized {
+        insurance_contract::instruction::update_config(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            settings,
+        )
+    } else {
+        insurance_contract::instruction::initialize_config(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            settings,
+        )
+    };
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction.unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
 }
 
-fn close(client: &RpcClient, payer: &Keypair, data_address: &Pubkey) {
+fn set_paused(client: &RpcClient, payer: &Keypair, product_address: Option<&Pubkey>, paused: bool) {
+    let instruction = if paused {
+        insurance_contract::instruction::pause(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            product_address,
+        )
+    } else {
+        insurance_contract::instruction::unpause(
+            &insurance_contract::id(),
+            &payer.pubkey(),
+            product_address,
+        )
+    };
+    let recent_blockhash = client.get_recent_blockhash().unwrap().0;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction.unwrap()],
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    send_transaction(client, &transaction);
+}
+
+fn amend_document(
+    client: &RpcClient,
+    payer: &Keypair,
+    data_address: &Pubkey,
+    document_hash: [u8; 32],
+    document_uri: DocumentUri,
+) {
     let recent_blockhash = client.get_recent_blockhash().unwrap().0;
     let transaction = Transaction::new_signed_with_payer(
-        &[insurance_contract::instruction::close_insurance_contract(
+        &[insurance_contract::instruction::amend_document(
             &insurance_contract::id(),
             &payer.pubkey(),
-            &data_address,
+            data_address,
+            document_hash,
+            document_uri,
         )
         .unwrap()],
         Some(&payer.pubkey()),
         &[payer],
         recent_blockhash,
     );
-    client
-        .send_and_confirm_transaction_with_spinner(&transaction)
-        .unwrap();
+    send_transaction(client, &transaction);
+}
+
+fn verify_document(client: &RpcClient, data_address: &Pubkey, document_hash: [u8; 32]) {
+    let insurance_account = client.get_account(data_address).unwrap();
+    let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+    if insurance_data.document_hash == [0; 32] {
+        eprintln!("Error: no policy document is anchored");
+        std::process::exit(1);
+    }
+    if insurance_data.document_hash == document_hash {
+        println!("Document matches the current policy document");
+    } else if insurance_data.document_history().contains(&document_hash) {
+        eprintln!("Error: document matches a superseded policy document");
+        std::process::exit(1);
+    } else {
+        eprintln!(
+            "Error: document doesn't match the policy document {}",
+            format_document_hash(&insurance_data.document_hash)
+        );
+        std::process::exit(1);
+    }
 }
 
 fn show(client: &RpcClient, data_address: &Pubkey) {
     let insurance_account = client.get_account(data_address).unwrap();
-    let insurance_data: InsuranceContractData =
-        try_from_slice_unchecked(&insurance_account.data).unwrap();
-    println!("{:?}", insurance_data);
+    let insurance_data =
+        InsuranceContractData::unpack_from_any_version(&insurance_account.data).unwrap();
+    println!(
+        "Insurance contract ID: {}",
+        insurance_data.insurance_contract_id
+    );
+    println!("Status: {:?}", insurance_data.status);
+    println!("Authority: {}", insurance_data.authority);
+    if insurance_data.pending_authority != Pubkey::default() {
+        println!("Pending authority: {}", insurance_data.pending_authority);
+    }
+    if insurance_data.is_tokenized() {
+        println!("Policy mint: {}", insurance_data.policy_mint);
+        let (policy_token_address, _) =
+            find_policy_token_address(&insurance_contract::id(), data_address);
+        if let Ok(policy_token_account) = client.get_account(&policy_token_address) {
+            let policy_token = TokenAccount::unpack(&policy_token_account.data).unwrap();
+            if policy_token.amount == 1 {
+                println!("Policyholder: {}", policy_token.owner);
+            }
+        }
+    } else {
+        println!("Policyholder: {}", insurance_data.policyholder());
+    }
+    if insurance_data.assignment_consent_required {
+        println!("Assignment requires the authority consent");
+    }
+    for assignment in insurance_data.assignment_history() {
+        println!(
+            "Assigned from {} at {}",
+            assignment.previous_holder,
+            format_timestamp(assignment.assigned_at)
+        );
+    }
+    if insurance_data.premium_mint == Pubkey::default() {
+        return;
+    }
+
+    let decimals = get_mint_decimals(client, &insurance_data.premium_mint);
+    println!(
+        "Coverage: {} - {}",
+        format_timestamp(insurance_data.coverage_start),
+        format_timestamp(insurance_data.coverage_end)
+    );
+    println!(
+        "Sum insured: {}",
+        amount_to_ui_amount(insurance_data.sum_insured, decimals)
+    );
+    println!(
+        "Premium: {}",
+        amount_to_ui_amount(insurance_data.premium_amount, decimals)
+    );
+    println!(
+        "Premium paid: {}",
+        amount_to_ui_amount(insurance_data.premium_paid, decimals)
+    );
+    println!("Premium mint: {}", insurance_data.premium_mint);
+    if insurance_data.installment_count > 0 {
+        println!(
+            "Installments paid: {} of {}",
+            insurance_data.installments_paid, insurance_data.installment_count
+        );
+        println!(
+            "Installment: {}",
+            amount_to_ui_amount(insurance_data.installment_amount, decimals)
+        );
+        if let Some(due) = insurance_data.next_installment_due() {
+            println!(
+                "Next installment due: {} (grace period ends {})",
+                format_timestamp(due),
+                format_timestamp(due.saturating_add(insurance_data.grace_period))
+            );
+        }
+    }
+    if insurance_data.waiting_period > 0 {
+        println!(
+            "Waiting period ends: {}",
+            format_timestamp(insurance_data.waiting_period_end())
+        );
+    }
+    for gap in insurance_data.coverage_gap_history() {
+        if gap.is_open() {
+            println!("Not covered since: {}", format_timestamp(gap.start));
+        } else {
+            println!(
+                "Not covered: {} - {}",
+                format_timestamp(gap.start),
+                format_timestamp(gap.end)
+            );
+        }
+    }
+    println!("Claims adjuster: {}", insurance_data.claims_adjuster);
+    println!("Claims submitted: {}", insurance_data.claim_count);
+    if insurance_data.unpaid_claim_count > 0 {
+        println!(
+            "Approved claims not paid: {}",
+            insurance_data.unpaid_claim_count
+        );
+    }
+    println!(
+        "Claims paid: {}",
+        amount_to_ui_amount(insurance_data.claims_paid, decimals)
+    );
+    if insurance_data.deductible > 0 {
+        println!(
+            "Deductible: {}",
+            amount_to_ui_amount(insurance_data.deductible, decimals)
+        );
+    }
+    if insurance_data.per_occurrence_limit > 0 {
+        println!(
+            "Per-claim limit: {}",
+            amount_to_ui_amount(insurance_data.per_occurrence_limit, decimals)
+        );
+    }
+    if insurance_data.annual_aggregate_limit > 0 {
+        println!(
+            "Annual aggregate limit: {}",
+            amount_to_ui_amount(insurance_data.annual_aggregate_limit, decimals)
+        );
+        let policy_years = insurance_data.policy_year(insurance_data.coverage_end) as usize + 1;
+        for (year, paid) in insurance_data
+            .aggregate_paid
+            .iter()
+            .take(policy_years)
+            .enumerate()
+        {
+            println!(
+                "Paid for incidents in policy year {}: {}",
+                year + 1,
+                amount_to_ui_amount(*paid, decimals)
+            );
+        }
+    }
+    if insurance_data.is_all_risk() {
+        println!("Perils: all risks");
+    }
+    for (peril, paid) in insurance_data
+        .perils
+        .iter()
+        .zip(insurance_data.peril_claims_paid.iter())
+        .filter(|(peril, _)| peril.code != NO_PERIL)
+    {
+        if peril.sub_limit > 0 {
+            println!(
+                "Peril {}: sub-limit {} ({} paid)",
+                peril.code,
+                amount_to_ui_amount(peril.sub_limit, decimals),
+                amount_to_ui_amount(*paid, decimals)
+            );
+        } else {
+            println!(
+                "Peril {}: no sub-limit ({} paid)",
+                peril.code,
+                amount_to_ui_amount(*paid, decimals)
+            );
+        }
+    }
+    if insurance_data.protocol_fees_paid > 0 {
+        println!(
+            "Protocol fees paid: {}",
+            amount_to_ui_amount(insurance_data.protocol_fees_paid, decimals)
+        );
+    }
+    println!(
+        "Keeper reward: {} SOL",
+        lamports_to_sol(insurance_data.keeper_reward)
+    );
+    println!(
+        "Cancellation penalty: {} bps",
+        insurance_data.cancellation_penalty_bps
+    );
+    println!(
+        "No-claims bonus: {} bps per claim-free term",
+        insurance_data.no_claims_bonus_bps
+    );
+    println!(
+        "Claim-free terms: {} ({} bps discount)",
+        insurance_data.claim_free_terms,
+        insurance_data.no_claims_discount_bps()
+    );
+    if insurance_data.product != Pubkey::default() {
+        println!("Issued from product: {}", insurance_data.product);
+    }
+    if insurance_data.predecessor != Pubkey::default() {
+        println!("Renewal of: {}", insurance_data.predecessor);
+    }
+    if insurance_data.successor != Pubkey::default() {
+        println!("Renewed by: {}", insurance_data.successor);
+    }
+    if insurance_data.document_hash != [0; 32] {
+        println!(
+            "Policy document: {} ({})",
+            insurance_data.document_uri.as_str(),
+            format_document_hash(&insurance_data.document_hash)
+        );
+        if insurance_data.document_amendment_count > 0 {
+            println!(
+                "Policy document amendments: {}",
+                insurance_data.document_amendment_count
+            );
+        }
+    }
+    if insurance_data.status == InsuranceContractStatus::Cancelled {
+        println!(
+            "Cancellation reason: {}",
+            insurance_data.cancellation_reason
+        );
+        println!(
+            "Premium refunded: {}",
+            amount_to_ui_amount(insurance_data.premium_refunded, decimals)
+        );
+    }
 }
 
 fn main() {
@@ -143,7 +1756,7 @@ fn main() {
     let json_rpc_url = normalize_to_url_if_moniker(
         app_matches
             .value_of("json_rpc_url")
-            .unwrap_or(&"https://api.devnet.solana.com".to_owned()),
+            .unwrap_or("https://api.devnet.solana.com"),
     );
     println!("RPC Client URL: {}", json_rpc_url);
     let client = RpcClient::new(json_rpc_url);
@@ -161,21 +1774,380 @@ fn main() {
     match (sub_command, sub_matches) {
         ("save", Some(arg_matches)) => {
             let contract_id = value_t_or_exit!(arg_matches, "insurance_contract_id", u32);
-            let address = Keypair::new();
-            println!(
-                "Generated new keypair for InsuranceContract Account: {}",
-                address.pubkey()
+            let (address, _) = insurance_contract::find_insurance_contract_address(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                contract_id,
             );
+            let coverage_start =
+                unix_timestamp_from_rfc3339_datetime(arg_matches, "coverage_start").unwrap();
+            let coverage_end =
+                unix_timestamp_from_rfc3339_datetime(arg_matches, "coverage_end").unwrap();
+            let terms =
+                product_terms_of(&client, arg_matches, &payer, coverage_end - coverage_start)
+                    .policy_terms(coverage_start);
+            println!("InsuranceContract Account: {}", address);
             println!("Saving new InsuranceContract with id: {}", contract_id);
 
-            save(&client, &payer, contract_id, &address);
+            save(&client, &payer, contract_id, terms);
+        }
+
+        ("create-product", Some(arg_matches)) => {
+            let product_id = value_t_or_exit!(arg_matches, "product_id", u32);
+            let (product_address, _) = find_insurance_product_address(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                product_id,
+            );
+            let coverage_period = value_t_or_exit!(arg_matches, "coverage_period", u32)
+                as UnixTimestamp
+                * SECONDS_PER_DAY as UnixTimestamp;
+            let terms = product_terms_of(&client, arg_matches, &payer, coverage_period);
+            println!("Insurance product Account: {}", product_address);
+            println!("Creating insurance product with id: {}", product_id);
+
+            create_product(&client, &payer, product_id, terms);
+        }
+
+        ("update-product", Some(arg_matches)) => {
+            let product_address = value_t_or_exit!(arg_matches, "product", Pubkey);
+            let coverage_period = value_t_or_exit!(arg_matches, "coverage_period", u32)
+                as UnixTimestamp
+                * SECONDS_PER_DAY as UnixTimestamp;
+            let terms = product_terms_of(&client, arg_matches, &payer, coverage_period);
+            println!("Update insurance product: {}", product_address);
+
+            update_product(&client, &payer, &product_address, terms);
+        }
+
+        ("issue", Some(arg_matches)) => {
+            let product_address = value_t_or_exit!(arg_matches, "product", Pubkey);
+            let contract_id = value_t_or_exit!(arg_matches, "insurance_contract_id", u32);
+            let coverage_start =
+                unix_timestamp_from_rfc3339_datetime(arg_matches, "coverage_start").unwrap();
+            let (address, _) = insurance_contract::find_insurance_contract_address(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                contract_id,
+            );
+            let product_account = client.get_account(&product_address).unwrap();
+            let product_data = InsuranceProductData::unpack(&product_account.data).unwrap();
+            println!("Issue from insurance product: {}", product_address);
+            println!("InsuranceContract Account: {}", address);
+
+            issue(
+                &client,
+                &payer,
+                &product_address,
+                &product_data.terms,
+                contract_id,
+                coverage_start,
+            );
+        }
+
+        ("retire-product", Some(arg_matches)) => {
+            let product_address = value_t_or_exit!(arg_matches, "product", Pubkey);
+            println!("Retire insurance product: {}", product_address);
+
+            retire_product(&client, &payer, &product_address);
+        }
+
+        ("renew", Some(arg_matches)) => {
+            let predecessor_address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let contract_id = value_t_or_exit!(arg_matches, "insurance_contract_id", u32);
+            let token_account = value_t_or_exit!(arg_matches, "token_account", Pubkey);
+            let premium_payer = arg_matches
+                .value_of("premium_payer")
+                .map(|path| read_keypair_file(path).unwrap());
+            let (address, _) = insurance_contract::find_insurance_contract_address(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                contract_id,
+            );
+            let predecessor_account = client.get_account(&predecessor_address).unwrap();
+            let predecessor_data =
+                InsuranceContractData::unpack(&predecessor_account.data).unwrap();
+            let decimals = get_mint_decimals(&client, &predecessor_data.premium_mint);
+            let coverage_end =
+                unix_timestamp_from_rfc3339_datetime(arg_matches, "coverage_end").unwrap();
+            let premium_amount = ui_amount_to_amount(
+                value_t_or_exit!(arg_matches, "premium_amount", f64),
+                decimals,
+            );
+            println!("Renew InsuranceContract: {}", predecessor_address);
+            println!("Successor InsuranceContract Account: {}", address);
+
+            // A successor of a contract issued from a product is a policy of the product
+            let product =
+                Some(predecessor_data.product).filter(|product| *product != Pubkey::default());
+
+            renew(
+                &client,
+                &payer,
+                premium_payer.as_ref().unwrap_or(&payer),
+                &token_account,
+                &predecessor_address,
+                &predecessor_data.premium_mint,
+                contract_id,
+                coverage_end,
+                premium_amount,
+                product.as_ref(),
+            );
         }
 
         ("close", Some(arg_matches)) => {
             let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let reclaim_destination = if arg_matches.is_present("reclaim") {
+                Some(if arg_matches.is_present("destination") {
+                    value_t_or_exit!(arg_matches, "destination", Pubkey)
+                } else {
+                    payer.pubkey()
+                })
+            } else {
+                None
+            };
+            let premium_token_account = pubkey_of(arg_matches, "premium_to");
             println!("Close InsuranceContract: {}", address);
 
-            close(&client, &payer, &address);
+            close(
+                &client,
+                &payer,
+                &address,
+                reclaim_destination,
+                premium_token_account,
+            );
+        }
+
+        ("migrate", Some(arg_matches)) => {
+            let legacy_address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let legacy_account = client.get_account(&legacy_address).unwrap();
+            let legacy_data =
+                InsuranceContractData::unpack_from_any_version(&legacy_account.data).unwrap();
+            let (address, _) = insurance_contract::find_insurance_contract_address(
+                &insurance_contract::id(),
+                &payer.pubkey(),
+                legacy_data.insurance_contract_id,
+            );
+            println!("Migrate InsuranceContract: {}", legacy_address);
+            println!("New InsuranceContract Account: {}", address);
+
+            migrate(
+                &client,
+                &payer,
+                &legacy_address,
+                legacy_data.insurance_contract_id,
+            );
+        }
+
+        ("pay-premium", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let token_account = value_t_or_exit!(arg_matches, "token_account", Pubkey);
+            let insurance_account = client.get_account(&address).unwrap();
+            let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+            let decimals = get_mint_decimals(&client, &insurance_data.premium_mint);
+            let amount =
+                ui_amount_to_amount(value_t_or_exit!(arg_matches, "amount", f64), decimals);
+            println!("Pay premium for InsuranceContract: {}", address);
+
+            pay_premium(
+                &client,
+                &payer,
+                &token_account,
+                &address,
+                &insurance_data.premium_mint,
+                amount,
+            );
+        }
+
+        ("pay-installment", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let token_account = value_t_or_exit!(arg_matches, "token_account", Pubkey);
+            let insurance_account = client.get_account(&address).unwrap();
+            let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+            println!("Pay installment for InsuranceContract: {}", address);
+
+            pay_installment(
+                &client,
+                &payer,
+                &token_account,
+                &address,
+                &insurance_data.premium_mint,
+            );
+        }
+
+        ("lapse", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            println!("Lapse InsuranceContract: {}", address);
+
+            lapse(&client, &payer, &address);
+        }
+
+        ("cancel", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let refund_token_account =
+                value_t_or_exit!(arg_matches, "refund_token_account", Pubkey);
+            let reason = value_t_or_exit!(arg_matches, "reason", u8);
+            println!("Cancel InsuranceContract: {}", address);
+
+            cancel(&client, &payer, &address, &refund_token_account, reason);
+        }
+
+        ("expire", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            println!("Expire InsuranceContract: {}", address);
+
+            expire(&client, &payer, &address);
+        }
+
+        ("set-authority", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let new_authority = if arg_matches.is_present("cancel") {
+                println!(
+                    "Withdraw authority proposal for InsuranceContract: {}",
+                    address
+                );
+                None
+            } else {
+                let new_authority = value_t_or_exit!(arg_matches, "new_authority", Pubkey);
+                println!("Propose authority for InsuranceContract: {}", address);
+                println!("New authority: {}", new_authority);
+                Some(new_authority)
+            };
+
+            set_authority(&client, &payer, &address, new_authority);
+        }
+
+        ("accept-authority", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            println!("Accept authority of InsuranceContract: {}", address);
+
+            accept_authority(&client, &payer, &address);
+        }
+
+        ("assign", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let new_holder = value_t_or_exit!(arg_matches, "new_holder", Pubkey);
+            let insurance_account = client.get_account(&address).unwrap();
+            let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+            let authority = arg_matches
+                .value_of("authority")
+                .map(|path| read_keypair_file(path).unwrap());
+            let consenting_authority = if insurance_data.assignment_consent_required {
+                Some(authority.as_ref().unwrap_or(&payer))
+            } else {
+                None
+            };
+            println!("Assign InsuranceContract: {}", address);
+            println!("New policyholder: {}", new_holder);
+
+            assign(&client, &payer, &address, &new_holder, consenting_authority);
+        }
+
+        ("claim-payout", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let insurance_account = client.get_account(&address).unwrap();
+            let insurance_data = InsuranceContractData::unpack(&insurance_account.data).unwrap();
+            let decimals = get_mint_decimals(&client, &insurance_data.premium_mint);
+            let amount =
+                ui_amount_to_amount(value_t_or_exit!(arg_matches, "amount", f64), decimals);
+            let peril = if arg_matches.is_present("peril") {
+                value_t_or_exit!(arg_matches, "peril", u16)
+            } else {
+                NO_PERIL
+            };
+            let incident_time = if arg_matches.is_present("incident_time") {
+                unix_timestamp_from_rfc3339_datetime(arg_matches, "incident_time").unwrap()
+            } else {
+                Utc::now().timestamp()
+            };
+            if insurance_data.covers_peril(peril) {
+                let payout = insurance_data.claim_payout(amount, peril, incident_time);
+                println!("Claim payout: {}", amount_to_ui_amount(payout, decimals));
+            } else {
+                println!("Peril {} is not covered", peril);
+            }
+        }
+
+        ("set-config", Some(arg_matches)) => {
+            let mut allowed_premium_mints = [Pubkey::default(); MAX_ALLOWED_MINTS];
+            for (allowed_mint, value) in allowed_premium_mints
+                .iter_mut()
+                .zip(arg_matches.values_of("allowed_mint").into_iter().flatten())
+            {
+                *allowed_mint = value.parse().unwrap();
+            }
+            let settings = ConfigSettings {
+                protocol_fee_bps: value_t_or_exit!(arg_matches, "protocol_fee_bps", u16),
+                treasury: if arg_matches.is_present("treasury") {
+                    value_t_or_exit!(arg_matches, "treasury", Pubkey)
+                } else {
+                    Pubkey::default()
+                },
+                allowed_premium_mints,
+                guardian: if arg_matches.is_present("guardian") {
+                    value_t_or_exit!(arg_matches, "guardian", Pubkey)
+                } else {
+                    payer.pubkey()
+                },
+            };
+            println!(
+                "Program config: {}",
+                find_config_address(&insurance_contract::id()).0
+            );
+
+            set_config(&client, &payer, settings);
+        }
+
+        ("pause", Some(arg_matches)) => {
+            let product_address = pubkey_of(arg_matches, "product");
+            match product_address {
+                Some(product_address) => println!("Pause insurance product: {}", product_address),
+                None => println!("Pause program"),
+            }
+
+            set_paused(&client, &payer, product_address.as_ref(), true);
+        }
+
+        ("unpause", Some(arg_matches)) => {
+            let product_address = pubkey_of(arg_matches, "product");
+            match product_address {
+                Some(product_address) => println!("Unpause insurance product: {}", product_address),
+                None => println!("Unpause program"),
+            }
+
+            set_paused(&client, &payer, product_address.as_ref(), false);
+        }
+
+        ("amend-document", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let document_hash = hash_document_file(arg_matches.value_of("file").unwrap());
+            let uri = arg_matches.value_of("uri").unwrap();
+            let document_uri = DocumentUri::new(uri).unwrap_or_else(|| {
+                eprintln!(
+                    "Error: document URI must be at most {} bytes",
+                    MAX_DOCUMENT_URI_LEN
+                );
+                std::process::exit(1);
+            });
+            println!(
+                "Amend policy document of InsuranceContract {}: {}",
+                address,
+                format_document_hash(&document_hash)
+            );
+
+            amend_document(&client, &payer, &address, document_hash, document_uri);
+        }
+
+        ("verify-document", Some(arg_matches)) => {
+            let address = value_t_or_exit!(arg_matches, "address", Pubkey);
+            let document_hash = hash_document_file(arg_matches.value_of("file").unwrap());
+            println!(
+                "Verify policy document of InsuranceContract {}: {}",
+                address,
+                format_document_hash(&document_hash)
+            );
+
+            verify_document(&client, &address, document_hash);
         }
 
         ("show", Some(arg_matches)) => {
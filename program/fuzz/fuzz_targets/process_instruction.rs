@@ -0,0 +1,110 @@
+//! Feeds arbitrary instruction data and account sets to the program processor.
+//! Any panic is a bug, errors are expected.
+#![no_main]
+use arbitrary::Arbitrary;
+use insurance_contract::{find_config_address, id, processor::Processor, state};
+use libfuzzer_sys::fuzz_target;
+use solana_program::{
+    account_info::AccountInfo,
+    clock::{Clock, UnixTimestamp},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::{self, Sysvar},
+};
+
+const MAX_ACCOUNTS: usize = 12;
+
+#[derive(Arbitrary, Debug)]
+struct FuzzAccount {
+    key: u8,
+    owner: u8,
+    is_signer: bool,
+    is_writable: bool,
+    lamports: u64,
+    layout: u8,
+    data: Vec<u8>,
+}
+
+#[derive(Arbitrary, Debug)]
+struct FuzzInstruction {
+    instruction_data: Vec<u8>,
+    accounts: Vec<FuzzAccount>,
+    unix_timestamp: UnixTimestamp,
+}
+
+fuzz_target!(|input: FuzzInstruction| {
+    let keys = [
+        id(),
+        system_program::id(),
+        spl_token::id(),
+        sysvar::rent::id(),
+        sysvar::clock::id(),
+        find_config_address(&id()).0,
+        Pubkey::new_from_array([1; 32]),
+        Pubkey::new_from_array([2; 32]),
+        Pubkey::new_from_array([3; 32]),
+        Pubkey::new_from_array([4; 32]),
+    ];
+    let owners = [id(), system_program::id(), spl_token::id(), sysvar::id()];
+
+    let mut accounts = input
+        .accounts
+        .into_iter()
+        .take(MAX_ACCOUNTS)
+        .map(|mut account| {
+            let key = &keys[account.key as usize % keys.len()];
+            // Sized like the accounts the program expects to reach deeper code paths
+            let data_len = match account.layout % 7 {
+                0 => account.data.len(),
+                1 => state::INSURANCE_CONTRACT_DATA_LEN,
+                2 => state::CLAIM_DATA_LEN,
+                3 => state::INSURANCE_PRODUCT_DATA_LEN,
+                4 => state::CONFIG_DATA_LEN,
+                5 => spl_token::state::Account::LEN,
+                _ => spl_token::state::Mint::LEN,
+            };
+            account.data.resize(data_len, 0);
+            if *key == sysvar::rent::id() {
+                account.data = vec![0; Rent::size_of()];
+            } else if *key == sysvar::clock::id() {
+                account.data = vec![0; Clock::size_of()];
+            }
+            (key, account)
+        })
+        .collect::<Vec<_>>();
+
+    let mut account_infos: Vec<AccountInfo> = Vec::with_capacity(accounts.len());
+    for (key, account) in accounts.iter_mut() {
+        // Accounts repeated in a transaction share their data
+        let duplicate = account_infos.iter().find(|info| info.key == *key).cloned();
+        if let Some(account_info) = duplicate {
+            account_infos.push(account_info);
+            continue;
+        }
+
+        let mut account_info = AccountInfo::new(
+            key,
+            account.is_signer,
+            account.is_writable,
+            &mut account.lamports,
+            &mut account.data,
+            &owners[account.owner as usize % owners.len()],
+            false,
+            0,
+        );
+        if **key == sysvar::rent::id() {
+            Rent::default().to_account_info(&mut account_info);
+        } else if **key == sysvar::clock::id() {
+            Clock {
+                unix_timestamp: input.unix_timestamp,
+                ..Clock::default()
+            }
+            .to_account_info(&mut account_info);
+        }
+        account_infos.push(account_info);
+    }
+
+    let _ = Processor::process(&id(), &account_infos, &input.instruction_data);
+});
@@ -1,7 +1,8 @@
 //! Program entrypoint
-use crate::processor::Processor;
+use crate::{error::InsuranceContractError, processor::Processor};
 use solana_program::{
-    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult,
+    program_error::PrintProgramError, pubkey::Pubkey,
 };
 
 entrypoint!(process_instruction);
@@ -10,5 +11,10 @@ fn process_instruction(
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    Processor::process(program_id, accounts, instruction_data)
+    if let Err(error) = Processor::process(program_id, accounts, instruction_data) {
+        // Catch the error so we can print it
+        error.print::<InsuranceContractError>();
+        return Err(error);
+    }
+    Ok(())
 }
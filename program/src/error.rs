@@ -1,22 +1,144 @@
 //! Error types
-use solana_program::program_error::ProgramError;
+use num_derive::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
 use thiserror::Error;
 
 /// Errors that may be returned by the InsuranceContract program.
-#[derive(Error, Debug, Copy, Clone)]
+///
+/// The discriminant is the `ProgramError::Custom` code of the error, codes are part of
+/// the program interface and never change, new errors must be appended to the end.
+#[derive(Error, Debug, Copy, Clone, PartialEq, FromPrimitive)]
 pub enum InsuranceContractError {
     /// Invalid instruction
     #[error("Invalid Instruction")]
-    InvalidInstruction,
+    InvalidInstruction = 0,
     /// Not initialized
     #[error("Not initialized")]
-    NotInitialized,
+    NotInitialized = 1,
     /// Already initialized
     #[error("Already initialized")]
-    AlreadyInitialized,
+    AlreadyInitialized = 2,
     /// Already closed
     #[error("Already closed")]
-    AlreadyClosed,
+    AlreadyClosed = 3,
+    /// Authority mismatch
+    #[error("Insurance contract authority mismatch")]
+    AuthorityMismatch = 4,
+    /// Account uses a legacy layout
+    #[error("Account uses a legacy layout and must be migrated")]
+    MigrationRequired = 5,
+    /// Unsupported account version
+    #[error("Unsupported account version")]
+    InvalidAccountVersion = 6,
+    /// Account already migrated
+    #[error("Account already uses the current layout")]
+    AlreadyMigrated = 7,
+    /// Operation overflowed
+    #[error("Operation overflowed")]
+    Overflow = 8,
+    /// Status transition is not allowed
+    #[error("Invalid insurance contract status transition")]
+    InvalidStatusTransition = 9,
+    /// Status is already set
+    #[error("Insurance contract already has this status")]
+    StatusUnchanged = 10,
+    /// Invalid coverage period
+    #[error("Coverage period is invalid or already over")]
+    InvalidCoveragePeriod = 11,
+    /// Invalid sum insured
+    #[error("Sum insured must be greater than zero")]
+    InvalidSumInsured = 12,
+    /// Operation is not allowed in the current status
+    #[error("Operation is not allowed in the current insurance contract status")]
+    InvalidContractStatus = 13,
+    /// Invalid premium amount
+    #[error("Premium amount is zero or exceeds the outstanding premium")]
+    InvalidPremiumAmount = 14,
+    /// Invalid mint
+    #[error("Mint does not match the insurance contract premium mint")]
+    InvalidMint = 15,
+    /// Invalid incident time
+    #[error("Incident time is outside of the coverage period")]
+    InvalidIncidentTime = 16,
+    /// Invalid claim amount
+    #[error("Claim amount is zero or exceeds the remaining coverage")]
+    InvalidClaimAmount = 17,
+    /// Operation is not allowed in the current claim status
+    #[error("Operation is not allowed in the current claim status")]
+    InvalidClaimStatus = 18,
+    /// Claim is filed against another contract
+    #[error("Claim does not belong to the insurance contract")]
+    ClaimContractMismatch = 19,
+    /// Vault can't cover the payout
+    #[error("Insufficient funds in the insurance contract vault")]
+    InsufficientVaultFunds = 20,
+    /// Payout destination is not owned by the claimant
+    #[error("Beneficiary token account is not owned by the claimant")]
+    BeneficiaryMismatch = 21,
+    /// Vault still holds tokens
+    #[error("Insurance contract vault is not empty")]
+    VaultNotEmpty = 22,
+    /// Coverage period is not over
+    #[error("Coverage period is not over yet")]
+    CoverageNotEnded = 23,
+    /// Invalid cancellation penalty
+    #[error("Cancellation penalty exceeds the whole premium")]
+    InvalidCancellationPenalty = 24,
+    /// Contract already has a successor
+    #[error("Insurance contract is already renewed")]
+    AlreadyRenewed = 25,
+    /// Invalid premium installment schedule
+    #[error("Invalid premium installment schedule")]
+    InvalidPremiumSchedule = 26,
+    /// Installment grace period is not over
+    #[error("No installment is overdue after the grace period")]
+    GracePeriodNotOver = 27,
+    /// Authority handover is not proposed
+    #[error("No authority handover is pending")]
+    NoPendingAuthority = 28,
+    /// Signer is not the policyholder
+    #[error("Policy holder mismatch")]
+    HolderMismatch = 29,
+    /// Policy is assigned to its current holder or to the default address
+    #[error("Invalid policy assignment")]
+    InvalidAssignment = 30,
+    /// Deductible or claim limits exceed the sum insured
+    #[error("Deductible or claim limits exceed the sum insured")]
+    InvalidClaimLimits = 31,
+    /// Claim is filed for a peril the contract doesn't cover
+    #[error("Peril is not covered by the insurance contract")]
+    UncoveredPeril = 32,
+    /// InsuranceContracts can't be issued from a retired product
+    #[error("Insurance product is retired")]
+    ProductRetired = 33,
+    /// Terms of the InsuranceContract differ from the product ones
+    #[error("Terms don't match the insurance product")]
+    ProductTermsMismatch = 34,
+    /// Signer is not the admin of the program config
+    #[error("Program config admin mismatch")]
+    AdminMismatch = 35,
+    /// Protocol fee exceeds the maximum or has no treasury
+    #[error("Invalid protocol fee")]
+    InvalidProtocolFee = 36,
+    /// Premium mint is not allowed by the program config
+    #[error("Premium mint is not allowed")]
+    PremiumMintNotAllowed = 37,
+    /// Fee destination is not a treasury token account of the premium mint
+    #[error("Token account is not a treasury account of the premium mint")]
+    TreasuryMismatch = 38,
+    /// New business is stopped by the guardian
+    #[error("Program is paused")]
+    ProgramPaused = 39,
+    /// Signer is not the guardian of the program config
+    #[error("Program config guardian mismatch")]
+    GuardianMismatch = 40,
+    /// Policy document hash is zero or its URI is not UTF-8
+    #[error("Invalid policy document hash or URI")]
+    InvalidDocument = 41,
 }
 
 impl From<InsuranceContractError> for ProgramError {
@@ -24,3 +146,65 @@ impl From<InsuranceContractError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl<T> DecodeError<T> for InsuranceContractError {
+    fn type_of() -> &'static str {
+        "InsuranceContractError"
+    }
+}
+
+impl PrintProgramError for InsuranceContractError {
+    fn print<E>(&self)
+    where
+        E: 'static
+            + std::error::Error
+            + DecodeError<E>
+            + PrintProgramError
+            + num_traits::FromPrimitive,
+    {
+        msg!(&self.to_string());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn test_stable_codes() {
+        assert_eq!(
+            ProgramError::from(InsuranceContractError::InvalidInstruction),
+            ProgramError::Custom(0)
+        );
+        assert_eq!(
+            ProgramError::from(InsuranceContractError::ProgramPaused),
+            ProgramError::Custom(39)
+        );
+        assert_eq!(
+            ProgramError::from(InsuranceContractError::GuardianMismatch),
+            ProgramError::Custom(40)
+        );
+        assert_eq!(
+            ProgramError::from(InsuranceContractError::InvalidDocument),
+            ProgramError::Custom(41)
+        );
+    }
+
+    #[test]
+    fn test_decode_custom_error() {
+        for code in 0..=InsuranceContractError::InvalidDocument as u32 {
+            let error = InsuranceContractError::from_u32(code).unwrap();
+            assert_eq!(ProgramError::from(error), ProgramError::Custom(code));
+        }
+        let decoded: Option<InsuranceContractError> =
+            InsuranceContractError::decode_custom_error_to_enum(
+                InsuranceContractError::UncoveredPeril as u32,
+            );
+        assert_eq!(Some(InsuranceContractError::UncoveredPeril), decoded);
+        assert_eq!(
+            None,
+            InsuranceContractError::from_u32(InsuranceContractError::InvalidDocument as u32 + 1)
+        );
+    }
+}
@@ -0,0 +1,394 @@
+//! Program events
+//!
+//! Events are logged as `EVENT_LOG_PREFIX` followed by the base64 encoded Borsh
+//! serialization of `InsuranceContractEvent`, see `decode_events` to read them back
+//! from the transaction logs.
+use crate::state::{ClaimStatus, InsuranceContractStatus};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{msg, pubkey::Pubkey};
+
+/// Prefix of the log messages carrying program events
+pub const EVENT_LOG_PREFIX: &str = "insurance-contract-event: ";
+
+/// Prefix the runtime adds to the messages logged by programs
+const PROGRAM_LOG_PREFIX: &str = "Program log: ";
+
+/// InsuranceContract was saved
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContractSaved {
+    pub insurance_contract: Pubkey,
+    pub authority: Pubkey,
+    pub insurance_contract_id: u32,
+    /// Insurance product the contract is issued from, default when issued directly
+    pub product: Pubkey,
+    pub status: InsuranceContractStatus,
+}
+
+/// InsuranceContract moved to a new status, other than Cancelled or Closed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContractStatusChanged {
+    pub insurance_contract: Pubkey,
+    pub status: InsuranceContractStatus,
+}
+
+/// InsuranceContract was cancelled
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContractCancelled {
+    pub insurance_contract: Pubkey,
+    pub reason: u8,
+    /// Premium refunded to the policyholder
+    pub refund: u64,
+}
+
+/// InsuranceContract was closed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContractClosed {
+    pub insurance_contract: Pubkey,
+}
+
+/// Rent of a closed InsuranceContract was reclaimed
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContractReclaimed {
+    pub insurance_contract: Pubkey,
+    pub destination: Pubkey,
+}
+
+/// InsuranceContract was renewed with a successor
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ContractRenewed {
+    pub predecessor: Pubkey,
+    pub insurance_contract: Pubkey,
+}
+
+/// Premium, an installment or a renewal premium was paid into the vault
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PremiumPaid {
+    pub insurance_contract: Pubkey,
+    pub payer: Pubkey,
+    pub amount: u64,
+    /// Protocol fee paid on top of the amount
+    pub protocol_fee: u64,
+    /// Premium paid so far
+    pub premium_paid: u64,
+}
+
+/// Claim was filed against an InsuranceContract
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ClaimSubmitted {
+    pub insurance_contract: Pubkey,
+    pub claim: Pubkey,
+    pub claim_index: u32,
+    pub amount: u64,
+    pub peril: u16,
+}
+
+/// Claim was approved or rejected by the claims adjuster
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ClaimAdjusted {
+    pub insurance_contract: Pubkey,
+    pub claim: Pubkey,
+    pub status: ClaimStatus,
+}
+
+/// Approved Claim was paid out
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ClaimPaid {
+    pub insurance_contract: Pubkey,
+    pub claim: Pubkey,
+    pub payout: u64,
+}
+
+/// Authority handover was accepted
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuthorityChanged {
+    pub insurance_contract: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// Policy was assigned to a new holder
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PolicyAssigned {
+    pub insurance_contract: Pubkey,
+    pub previous_holder: Pubkey,
+    pub new_holder: Pubkey,
+}
+
+/// Program or insurance product was paused or unpaused by the guardian
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PauseChanged {
+    /// Program config or insurance product account
+    pub account: Pubkey,
+    pub paused: bool,
+}
+
+/// Policy document of an InsuranceContract was amended
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct DocumentAmended {
+    pub insurance_contract: Pubkey,
+    /// SHA-256 hash of the new policy document
+    pub document_hash: [u8; 32],
+    /// Hash of the replaced policy document, zero when none was anchored
+    pub previous_document_hash: [u8; 32],
+}
+
+/// Events emitted by the InsuranceContract program.
+///
+/// The first byte of the encoding is the variant tag, which is the position of the
+/// variant in this enum. Tags are part of the program interface, new variants must be
+/// appended to the end.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
+pub enum InsuranceContractEvent {
+    ContractSaved(ContractSaved),
+    ContractStatusChanged(ContractStatusChanged),
+    ContractCancelled(ContractCancelled),
+    ContractClosed(ContractClosed),
+    ContractReclaimed(ContractReclaimed),
+    ContractRenewed(ContractRenewed),
+    PremiumPaid(PremiumPaid),
+    ClaimSubmitted(ClaimSubmitted),
+    ClaimAdjusted(ClaimAdjusted),
+    ClaimPaid(ClaimPaid),
+    AuthorityChanged(AuthorityChanged),
+    PolicyAssigned(PolicyAssigned),
+    PauseChanged(PauseChanged),
+    DocumentAmended(DocumentAmended),
+}
+
+impl InsuranceContractEvent {
+    /// Logs the event
+    pub fn emit(&self) {
+        let log = self.to_log();
+        #[cfg(test)]
+        EMITTED_LOGS.with(|logs| logs.borrow_mut().push(log.clone()));
+        msg!(&log);
+    }
+
+    /// Encodes the event as a log message
+    pub fn to_log(&self) -> String {
+        // Serialization to a vector can't fail
+        let data = self.try_to_vec().unwrap();
+        format!("{}{}", EVENT_LOG_PREFIX, base64::encode(data))
+    }
+
+    /// Decodes the event from a log message, with or without the runtime prefix.
+    /// Returns `None` for the messages that don't carry an event.
+    pub fn from_log(log: &str) -> Option<Self> {
+        let log = log.strip_prefix(PROGRAM_LOG_PREFIX).unwrap_or(log);
+        let data = base64::decode(log.strip_prefix(EVENT_LOG_PREFIX)?).ok()?;
+        Self::try_from_slice(&data).ok()
+    }
+}
+
+macro_rules! impl_from_event {
+    ($($event:ident),*) => {
+        $(impl From<$event> for InsuranceContractEvent {
+            fn from(event: $event) -> Self {
+                Self::$event(event)
+            }
+        })*
+    };
+}
+
+impl_from_event!(
+    ContractSaved,
+    ContractStatusChanged,
+    ContractCancelled,
+    ContractClosed,
+    ContractReclaimed,
+    ContractRenewed,
+    PremiumPaid,
+    ClaimSubmitted,
+    ClaimAdjusted,
+    ClaimPaid,
+    AuthorityChanged,
+    PolicyAssigned,
+    PauseChanged,
+    DocumentAmended
+);
+
+/// Logs the event
+pub fn emit<E: Into<InsuranceContractEvent>>(event: E) {
+    event.into().emit()
+}
+
+/// Invocation boundary the runtime logs around the messages of every program
+enum FrameLog {
+    Invoke(Pubkey),
+    Success(Pubkey),
+    Failed(Pubkey),
+}
+
+impl FrameLog {
+    /// Parses `Program <id> invoke [<depth>]`, `Program <id> success` and
+    /// `Program <id> failed: <error>`. Messages logged by programs are prefixed by the
+    /// runtime, so they never parse as boundaries.
+    fn parse(log: &str) -> Option<Self> {
+        let (program_id, boundary) = log.strip_prefix("Program ")?.split_once(' ')?;
+        let program_id = program_id.parse().ok()?;
+        if boundary.starts_with("invoke [") {
+            Some(Self::Invoke(program_id))
+        } else if boundary == "success" {
+            Some(Self::Success(program_id))
+        } else if boundary.starts_with("failed") {
+            Some(Self::Failed(program_id))
+        } else {
+            None
+        }
+    }
+}
+
+/// Decodes the events emitted by the given program from the transaction logs, skipping
+/// other messages. Only the messages logged within a successful invocation of the program
+/// are decoded, so other programs can't forge its events.
+pub fn decode_events<S: AsRef<str>>(
+    program_id: &Pubkey,
+    logs: &[S],
+) -> Vec<InsuranceContractEvent> {
+    let mut events = vec![];
+    // Invoked programs with the events logged by each of them, innermost last
+    let mut frames: Vec<(Pubkey, Vec<InsuranceContractEvent>)> = vec![];
+    for log in logs.iter().map(AsRef::as_ref) {
+        match FrameLog::parse(log) {
+            Some(FrameLog::Invoke(invoked_program_id)) => frames.push((invoked_program_id, vec![])),
+            Some(FrameLog::Success(finished_program_id)) => match frames.pop() {
+                Some((frame_program_id, frame_events))
+                    if frame_program_id == finished_program_id =>
+                {
+                    match frames.last_mut() {
+                        Some((_, parent_events)) => parent_events.extend(frame_events),
+                        None => events.extend(frame_events),
+                    }
+                }
+                _ => return events,
+            },
+            Some(FrameLog::Failed(_)) => {
+                frames.pop();
+            }
+            None => {
+                if let Some((frame_program_id, frame_events)) = frames.last_mut() {
+                    if frame_program_id == program_id && log.starts_with(PROGRAM_LOG_PREFIX) {
+                        frame_events.extend(InsuranceContractEvent::from_log(log));
+                    }
+                }
+            }
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+thread_local! {
+    static EMITTED_LOGS: std::cell::RefCell<Vec<String>> = std::cell::RefCell::new(Vec::new());
+}
+
+/// Takes the event log messages emitted on the current thread
+#[cfg(test)]
+pub(crate) fn take_emitted_logs() -> Vec<String> {
+    EMITTED_LOGS.with(|logs| logs.take())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_log_round_trip() {
+        let event = InsuranceContractEvent::ClaimSubmitted(ClaimSubmitted {
+            insurance_contract: Pubkey::new_unique(),
+            claim: Pubkey::new_unique(),
+            claim_index: 3,
+            amount: 1_000,
+            peril: 2,
+        });
+        let log = event.to_log();
+        assert!(log.starts_with(EVENT_LOG_PREFIX));
+        assert_eq!(Some(event.clone()), InsuranceContractEvent::from_log(&log));
+
+        let program_id = crate::id();
+        let logs = vec![
+            format!("Program {} invoke [1]", program_id),
+            "Program log: Instruction: submit claim".to_string(),
+            format!("{}{}", PROGRAM_LOG_PREFIX, log),
+            format!("{}{}not base64", PROGRAM_LOG_PREFIX, EVENT_LOG_PREFIX),
+            format!(
+                "{}{}{}",
+                PROGRAM_LOG_PREFIX,
+                EVENT_LOG_PREFIX,
+                base64::encode([255])
+            ),
+            format!(
+                "Program {} consumed 5000 of 200000 compute units",
+                program_id
+            ),
+            format!("Program {} success", program_id),
+        ];
+        assert_eq!(vec![event], decode_events(&program_id, &logs));
+    }
+
+    #[test]
+    fn test_forged_events() {
+        let program_id = crate::id();
+        let other_program_id = Pubkey::new_unique();
+        let event = InsuranceContractEvent::ClaimPaid(ClaimPaid {
+            insurance_contract: Pubkey::new_unique(),
+            claim: Pubkey::new_unique(),
+            payout: 1_000,
+        });
+        let log = format!("{}{}", PROGRAM_LOG_PREFIX, event.to_log());
+        let forged = InsuranceContractEvent::ClaimPaid(ClaimPaid {
+            insurance_contract: Pubkey::new_unique(),
+            claim: Pubkey::new_unique(),
+            payout: 1_000_000,
+        });
+        let forged_log = format!("{}{}", PROGRAM_LOG_PREFIX, forged.to_log());
+
+        let logs = vec![
+            // Another program logs an event of its own, it also logs the invocation
+            // boundaries as messages to pass them off as a frame of the program
+            format!("Program {} invoke [1]", other_program_id),
+            forged_log.clone(),
+            format!("{}Program {} invoke [2]", PROGRAM_LOG_PREFIX, program_id),
+            forged_log.clone(),
+            format!("{}Program {} success", PROGRAM_LOG_PREFIX, program_id),
+            // It invokes the program, then logs again once the program returned
+            format!("Program {} invoke [2]", program_id),
+            log.clone(),
+            format!("Program {} success", program_id),
+            forged_log.clone(),
+            format!("Program {} success", other_program_id),
+            // Events without the runtime prefix or outside of any invocation
+            event.to_log(),
+            forged_log,
+            // Events of a failed invocation are dropped
+            format!("Program {} invoke [1]", program_id),
+            log,
+            format!("Program {} failed: custom program error: 0x1", program_id),
+        ];
+        assert_eq!(vec![event], decode_events(&program_id, &logs));
+    }
+
+    #[test]
+    fn test_stable_tags() {
+        let event = InsuranceContractEvent::ContractClosed(ContractClosed {
+            insurance_contract: Pubkey::new_from_array([1; 32]),
+        });
+        let mut expected = vec![3];
+        expected.extend_from_slice(&[1; 32]);
+        assert_eq!(expected, event.try_to_vec().unwrap());
+
+        emit(PauseChanged {
+            account: Pubkey::default(),
+            paused: true,
+        });
+        let logs = take_emitted_logs();
+        assert_eq!(1, logs.len());
+        assert!(matches!(
+            InsuranceContractEvent::from_log(&logs[0]),
+            Some(InsuranceContractEvent::PauseChanged(PauseChanged {
+                paused: true,
+                ..
+            }))
+        ));
+        assert!(take_emitted_logs().is_empty());
+    }
+}
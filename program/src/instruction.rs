@@ -1,76 +1,469 @@
 //! Instruction types
-use crate::check_program_account;
+#![allow(clippy::vec_init_then_push)]
 use crate::error::InsuranceContractError::InvalidInstruction;
+use crate::state::{ConfigSettings, DocumentUri, PolicyTerms, ProductTerms};
+use crate::{
+    check_program_account, find_claim_address, find_config_address,
+    find_insurance_contract_address, find_insurance_product_address, find_policy_mint_address,
+    find_policy_token_address, find_program_data_address, find_vault_address,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
+    clock::UnixTimestamp,
     instruction::{AccountMeta, Instruction},
     program_error::ProgramError,
     pubkey::Pubkey,
-    sysvar,
+    system_program, sysvar,
 };
-use std::{convert::TryInto, mem::size_of};
 
 /// Instructions supported by the InsuranceContract program.
+///
+/// Instructions are Borsh encoded: the first byte is the variant tag, which is the
+/// position of the variant in this enum, followed by the variant fields. Tags are part
+/// of the program interface, new variants must be appended to the end. The variant fields
+/// are not versioned and follow the program, e.g. `PolicyTerms` grows as terms are added,
+/// so clients have to be built against the deployed program.
+///
+/// Instructions stopped while the program is paused, see `is_pausable`, take the program
+/// config account as their last account, after the accounts listed below. Instructions
+/// listing the config account already reuse it, see `config_account_index`.
 #[repr(C)]
-#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, PartialEq)]
 pub enum InsuranceContractInstruction {
-    /// Creates on-chain account stored the InsuranceContract identifier.
+    /// Creates on-chain account stored the InsuranceContract identifier and terms.
+    /// The account address is derived from the authority and the identifier,
+    /// see `find_insurance_contract_address`. A single policy token is minted to the
+    /// authority, its holder is the policyholder, see `find_policy_mint_address` and
+    /// `find_policy_token_address`. The policy token is frozen when assignments need
+    /// the consent of the authority.
     ///
     /// Accounts expected by this instruction:
-    /// `[signer]` Insurance contract authority (storage payer)
+    /// `[writable, signer]` Insurance contract authority (storage payer)
     /// `[writable]` Insurance contract data account
     /// `[]` Rent system account
+    /// `[]` Clock system account
+    /// `[]` System program
+    /// `[writable]` Policy token mint
+    /// `[writable]` Policy token account
+    /// `[]` Token program
+    /// `[writable]` Insurance product account, optional. The terms must be the product
+    ///   ones, see `ProductTerms::policy_terms`.
     SaveInsuranceContract {
         /// Inner identifier for InsuranceContract
         insurance_contract_id: u32,
+        /// Coverage and premium terms
+        terms: PolicyTerms,
     },
 
-    /// Set up is_closed status on InsuranceContract account.
+    /// Moves the InsuranceContract to the Closed status. The policy token is burned,
+    /// which needs the signature of its holder. The policy token mint stays with a zero
+    /// supply and its rent is not recovered, SPL Token mints can't be closed.
     ///
     /// Accounts expected by this instruction:
     /// `[signer]` Insurance contract authority (storage payer)
     /// `[writable]` Insurance contract data account
+    /// `[writable]` Policy token mint
+    /// `[writable]` Policyholder token account
+    /// `[writable, signer]` Policyholder
+    /// `[]` Token program
     CloseInsuranceContract,
+
+    /// Moves an InsuranceContract stored with a legacy layout into the account derived
+    /// from its authority and identifier. The legacy account is zeroed and its lamports
+    /// are returned to the authority. Legacy accounts without a stored authority can only
+    /// be migrated by the program config admin, who becomes their authority and hands
+    /// them over to their owners, see `ProposeAuthority`.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Insurance contract authority (storage payer)
+    /// `[writable]` Legacy insurance contract data account
+    /// `[writable]` New insurance contract data account
+    /// `[]` Rent system account
+    /// `[]` System program
+    /// `[]` Program config account, see `find_config_address`
+    MigrateInsuranceContract,
+
+    /// Moves an Active InsuranceContract to the Suspended status.
+    /// The time of the suspension opens a coverage gap incidents are not covered in.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Insurance contract authority
+    /// `[writable]` Insurance contract data account
+    /// `[]` Clock sysvar
+    SuspendInsuranceContract,
+
+    /// Moves a Suspended or Lapsed InsuranceContract back to the Active status.
+    /// The time of the reinstatement ends the coverage gap.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Insurance contract authority
+    /// `[writable]` Insurance contract data account
+    /// `[]` Clock sysvar
+    ReinstateInsuranceContract,
+
+    /// Moves the InsuranceContract to the Cancelled status and refunds the unearned
+    /// premium, less the short-rate cancellation penalty, from the vault to the
+    /// policyholder token account.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Insurance contract authority
+    /// `[writable]` Insurance contract data account
+    /// `[writable]` Vault token account
+    /// `[writable]` Refund token account of the policyholder
+    /// `[]` Clock system account
+    /// `[]` Token program
+    /// `[]` Policy token account of the policyholder
+    CancelInsuranceContract {
+        /// Application defined code of the cancellation reason
+        reason: u8,
+    },
+
+    /// Transfers premium tokens into the vault of a Draft InsuranceContract paid at once.
+    /// The vault token account is derived from the contract address, see `find_vault_address`,
+    /// and is created on the first payment. The contract becomes Active once the premium
+    /// is fully paid.
+    /// The protocol fee set in the program config is paid to the treasury on top of the premium.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Premium payer (vault storage payer)
+    /// `[writable]` Payer token account
+    /// `[writable]` Insurance contract data account
+    /// `[writable]` Vault token account
+    /// `[]` Premium mint
+    /// `[]` Rent system account
+    /// `[]` System program
+    /// `[]` Token program
+    /// `[]` Program config account, see `find_config_address`
+    /// `[writable]` Treasury token account receiving the protocol fee, unused while the
+    ///   config is not initialized or the fee is zero
+    PayPremium {
+        /// Amount of premium mint tokens to pay
+        amount: u64,
+    },
+
+    /// Files a Claim against an Active or Expired InsuranceContract on behalf of the
+    /// policyholder. The claim account address is derived from the contract and the
+    /// contract claim count, see `find_claim_address`.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Policyholder (storage payer)
+    /// `[writable]` Insurance contract data account
+    /// `[writable]` Claim data account
+    /// `[]` Rent system account
+    /// `[]` Clock system account
+    /// `[]` System program
+    /// `[]` Policy token account of the policyholder
+    SubmitClaim {
+        /// Claimed amount, in premium mint tokens
+        amount: u64,
+        /// Time the insured event happened
+        incident_time: UnixTimestamp,
+        /// Hash of the off-chain evidence documents
+        evidence_hash: [u8; 32],
+        /// Code of the covered peril, NO_PERIL for all-risk contracts
+        peril: u16,
+    },
+
+    /// Accepts a Submitted Claim.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Claims adjuster
+    /// `[writable]` Insurance contract data account
+    /// `[writable]` Claim data account
+    ApproveClaim,
+
+    /// Declines a Submitted Claim.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Claims adjuster
+    /// `[]` Insurance contract data account
+    /// `[writable]` Claim data account
+    RejectClaim,
+
+    /// Transfers the payout of an Approved Claim, its amount less the deductible and capped
+    /// by the claim limits, from the InsuranceContract vault to the token account of the
+    /// current policyholder and marks the claim Paid.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Claims adjuster
+    /// `[writable]` Insurance contract data account
+    /// `[writable]` Claim data account
+    /// `[writable]` Vault token account
+    /// `[writable]` Beneficiary token account
+    /// `[]` Token program
+    /// `[]` Policy token account of the beneficiary
+    PayClaim,
+
+    /// Returns the lamports of a Closed InsuranceContract to a destination chosen by
+    /// the authority. The account data is zeroed and marked reclaimed so the account
+    /// can't be used again within the same transaction. The premium left in the vault,
+    /// if any, is earned and transferred to a token account chosen by the authority, the
+    /// vault is then closed to the same destination as the contract. The policy token mint
+    /// is left in place, see `CloseInsuranceContract`. Fails while Approved claims are
+    /// not paid.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Insurance contract authority
+    /// `[writable]` Insurance contract data account
+    /// `[writable]` Vault token account
+    /// `[writable]` Destination account
+    /// `[]` Token program
+    /// `[writable]` Token account receiving the premium left in the vault, unused while
+    ///   the vault is empty
+    ReclaimInsuranceContract,
+
+    /// Moves an InsuranceContract whose coverage period is over to the Expired status.
+    /// Anyone can send this instruction, the keeper reward reserved on the contract
+    /// account is paid to the keeper.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Keeper
+    /// `[writable]` Insurance contract data account
+    /// `[]` Rent system account
+    /// `[]` Clock system account
+    ExpireInsuranceContract,
+
+    /// Renews an Active or Expired InsuranceContract with a successor contract whose
+    /// coverage starts at the end of the predecessor coverage, or at the renewal time when
+    /// the predecessor already expired: coverage is never backdated. The successor keeps the
+    /// predecessor terms and is linked to it. The renewal premium, less the no-claims
+    /// discount earned by consecutive claim-free terms, is paid at once into the
+    /// successor vault. The successor policy token is minted to the predecessor policyholder.
+    /// A successor of a contract issued from a product counts as a new policy of the
+    /// product, which must be neither retired nor paused.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Insurance contract authority (storage payer)
+    /// `[writable, signer]` Premium payer (vault storage payer)
+    /// `[writable]` Payer token account
+    /// `[writable]` Predecessor insurance contract data account
+    /// `[writable]` Successor insurance contract data account
+    /// `[writable]` Successor vault token account
+    /// `[]` Premium mint
+    /// `[]` Rent system account
+    /// `[]` Clock system account
+    /// `[]` System program
+    /// `[]` Token program
+    /// `[]` Predecessor policy token account of the policyholder
+    /// `[writable]` Successor policy token mint
+    /// `[writable]` Successor policy token account
+    /// `[]` Program config account, see `find_config_address`
+    /// `[writable]` Treasury token account receiving the protocol fee, unused while the
+    ///   config is not initialized or the fee is zero
+    /// `[writable]` Insurance product account, only when the predecessor was issued
+    ///   from a product
+    RenewInsuranceContract {
+        /// Inner identifier of the successor InsuranceContract
+        insurance_contract_id: u32,
+        /// End of the successor coverage period
+        coverage_end: UnixTimestamp,
+        /// Renewal premium before the no-claims discount, in premium mint tokens
+        premium_amount: u64,
+    },
+
+    /// Transfers the next installment of the premium schedule into the vault of the
+    /// InsuranceContract. The first installment activates a Draft contract, paying the
+    /// overdue installments reinstates a Lapsed one.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Premium payer (vault storage payer)
+    /// `[writable]` Payer token account
+    /// `[writable]` Insurance contract data account
+    /// `[writable]` Vault token account
+    /// `[]` Premium mint
+    /// `[]` Rent system account
+    /// `[]` Clock system account
+    /// `[]` System program
+    /// `[]` Token program
+    /// `[]` Program config account, see `find_config_address`
+    /// `[writable]` Treasury token account receiving the protocol fee, unused while the
+    ///   config is not initialized or the fee is zero
+    PayInstallment,
+
+    /// Moves an Active InsuranceContract whose installment is still unpaid after the
+    /// grace period to the Lapsed status. Anyone can send this instruction.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable]` Insurance contract data account
+    /// `[]` Clock system account
+    LapseInsuranceContract,
+
+    /// Proposes a new authority for the InsuranceContract, the handover completes once
+    /// the new authority accepts it. A new proposal replaces the pending one. The contract
+    /// address stays derived from the original authority.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Insurance contract authority
+    /// `[writable]` Insurance contract data account
+    ProposeAuthority {
+        /// Authority proposed to take over the InsuranceContract
+        new_authority: Pubkey,
+    },
+
+    /// Completes the authority handover proposed by the current authority.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Proposed insurance contract authority
+    /// `[writable]` Insurance contract data account
+    AcceptAuthority,
+
+    /// Withdraws the pending authority handover.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Insurance contract authority
+    /// `[writable]` Insurance contract data account
+    CancelAuthorityProposal,
+
+    /// Assigns the policy to a new holder, who files the claims and receives the refunds
+    /// from then on. The authority signs as well when the policy requires its consent.
+    /// The assignment is appended to the history kept on the InsuranceContract.
+    ///
+    /// The policy token of a tokenized policy is moved to the token account of the new
+    /// holder, which stays frozen when assignments need consent. Tokens which are not
+    /// frozen can also be transferred directly, without a record in the history.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Policyholder
+    /// `[writable]` Insurance contract data account
+    /// `[]` Clock sysvar
+    /// `[writable]` Policy token account of the policyholder, tokenized policies only
+    /// `[writable]` Policy token account of the new holder, tokenized policies only
+    /// `[]` Policy token mint, tokenized policies only
+    /// `[]` Token program, tokenized policies only
+    /// `[signer]` Insurance contract authority, optional unless consent is required
+    AssignPolicy {
+        /// Policyholder the policy is assigned to
+        new_holder: Pubkey,
+    },
+
+    /// Creates on-chain account stored the InsuranceProduct identifier and terms.
+    /// The account address is derived from the authority and the identifier,
+    /// see `find_insurance_product_address`.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Product authority (storage payer)
+    /// `[writable]` Insurance product data account
+    /// `[]` Rent system account
+    /// `[]` Clock system account
+    /// `[]` System program
+    CreateProduct {
+        /// Inner identifier for InsuranceProduct
+        product_id: u32,
+        /// Terms of the InsuranceContracts issued from the product
+        terms: ProductTerms,
+    },
+
+    /// Replaces the terms of an active InsuranceProduct. InsuranceContracts issued
+    /// before keep their terms.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Product authority
+    /// `[writable]` Insurance product data account
+    /// `[]` Clock system account
+    UpdateProduct {
+        /// Terms of the InsuranceContracts issued from now on
+        terms: ProductTerms,
+    },
+
+    /// Moves the InsuranceProduct to the Retired status, no more InsuranceContracts
+    /// can be issued from it.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Product authority
+    /// `[writable]` Insurance product data account
+    RetireProduct,
+
+    /// Creates the singleton program config account, the signer becomes its admin.
+    /// Only the upgrade authority of the program can initialize the config.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[writable, signer]` Config admin (storage payer), the program upgrade authority
+    /// `[writable]` Program config account, see `find_config_address`
+    /// `[]` Rent system account
+    /// `[]` System program
+    /// `[]` Program data account, see `find_program_data_address`
+    InitializeConfig {
+        /// Protocol fee, treasury, allowed premium mints and guardian
+        settings: ConfigSettings,
+    },
+
+    /// Replaces the program config settings
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Config admin
+    /// `[writable]` Program config account
+    UpdateConfig {
+        /// Protocol fee, treasury, allowed premium mints and guardian from now on
+        settings: ConfigSettings,
+    },
+
+    /// Stops new business of the whole program or, given a product, of that product only
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Config guardian
+    /// `[writable]` Program config account
+    /// `[writable]` Insurance product data account, optional
+    Pause,
+
+    /// Resumes the business stopped by `Pause`
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Config guardian
+    /// `[writable]` Program config account
+    /// `[writable]` Insurance product data account, optional
+    Unpause,
+
+    /// Anchors the off-chain policy document of the InsuranceContract by its SHA-256 hash.
+    /// The replaced hash is appended to the history kept on the InsuranceContract.
+    ///
+    /// Accounts expected by this instruction:
+    /// `[signer]` Insurance contract authority
+    /// `[writable]` Insurance contract data account
+    AmendDocument {
+        /// SHA-256 hash of the policy document
+        document_hash: [u8; 32],
+        /// URI the policy document is published at
+        document_uri: DocumentUri,
+    },
 }
 
 impl InsuranceContractInstruction {
+    /// Unpacks a byte buffer into a InsuranceContractInstruction.
+    /// The whole buffer must be consumed.
     pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
-        let (tag, rest) = input.split_first().ok_or(InvalidInstruction)?;
-        Ok(match tag {
-            0 => {
-                let (insurance_contract_id, _) = rest.split_at(4);
-                let insurance_contract_id = insurance_contract_id
-                    .try_into()
-                    .ok()
-                    .map(u32::from_le_bytes)
-                    .ok_or(InvalidInstruction)?;
-
-                Self::SaveInsuranceContract {
-                    insurance_contract_id,
-                }
-            }
-
-            1 => Self::CloseInsuranceContract,
-
-            _ => return Err(InvalidInstruction.into()),
-        })
+        Self::try_from_slice(input).map_err(|_| InvalidInstruction.into())
     }
 
     /// Packs a InsuranceContractInstruction into a byte buffer.
     pub fn pack(&self) -> Vec<u8> {
-        let mut buf = Vec::with_capacity(size_of::<Self>());
-        match self {
-            Self::SaveInsuranceContract {
-                insurance_contract_id,
-            } => {
-                buf.push(0);
-                buf.extend_from_slice(&insurance_contract_id.to_le_bytes());
-            }
+        self.try_to_vec().unwrap()
+    }
 
-            Self::CloseInsuranceContract => {
-                buf.push(1);
-            }
-        };
-        buf
+    /// Checks if the instruction is stopped while the program is paused. Payouts of
+    /// approved claims, refunds and the administration of the program go on.
+    pub fn is_pausable(&self) -> bool {
+        !matches!(
+            self,
+            Self::PayClaim
+                | Self::CancelInsuranceContract { .. }
+                | Self::InitializeConfig { .. }
+                | Self::UpdateConfig { .. }
+                | Self::Pause
+                | Self::Unpause
+        )
+    }
+
+    /// Position of the program config account among the accounts listed for the
+    /// instruction, `None` when the config is only appended for the pause check
+    pub fn config_account_index(&self) -> Option<usize> {
+        match self {
+            Self::MigrateInsuranceContract => Some(5),
+            Self::PayPremium { .. } => Some(8),
+            Self::RenewInsuranceContract { .. } => Some(14),
+            Self::PayInstallment => Some(9),
+            _ => None,
+        }
     }
 }
 
@@ -78,23 +471,125 @@ impl InsuranceContractInstruction {
 pub fn save_insurance_contract(
     program_id: &Pubkey,
     insurance_contract_authority: &Pubkey,
-    insurance_contract_account: &Pubkey,
     insurance_contract_id: u32,
+    terms: PolicyTerms,
 ) -> Result<Instruction, ProgramError> {
     check_program_account(program_id)?;
 
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        program_id,
+        insurance_contract_authority,
+        insurance_contract_id,
+    );
+
     let data = InsuranceContractInstruction::SaveInsuranceContract {
         insurance_contract_id,
+        terms,
     }
     .pack();
 
-    let mut accounts = Vec::with_capacity(3);
+    let (policy_mint, _) = find_policy_mint_address(program_id, &insurance_contract_account);
+    let (policy_token_account, _) =
+        find_policy_token_address(program_id, &insurance_contract_account);
+
+    let mut accounts = Vec::with_capacity(9);
+    accounts.push(AccountMeta::new(*insurance_contract_authority, true));
+    accounts.push(AccountMeta::new(insurance_contract_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new(policy_mint, false));
+    accounts.push(AccountMeta::new(policy_token_account, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SaveInsuranceContract` instruction issuing an InsuranceContract from
+/// a product with the given coverage start
+pub fn save_insurance_contract_from_product(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_id: u32,
+    product_account: &Pubkey,
+    product_terms: &ProductTerms,
+    coverage_start: UnixTimestamp,
+) -> Result<Instruction, ProgramError> {
+    let mut instruction = save_insurance_contract(
+        program_id,
+        insurance_contract_authority,
+        insurance_contract_id,
+        product_terms.policy_terms(coverage_start),
+    )?;
+    // The product account goes before the program config account
+    let product_position = instruction.accounts.len() - 1;
+    instruction
+        .accounts
+        .insert(product_position, AccountMeta::new(*product_account, false));
+    Ok(instruction)
+}
+
+/// Creates a `CloseInsuranceContract` instruction
+pub fn close_insurance_contract(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    policyholder: &Pubkey,
+    policy_token_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (policy_mint, _) = find_policy_mint_address(program_id, insurance_contract_account);
+    let data = InsuranceContractInstruction::CloseInsuranceContract {}.pack();
+
+    let mut accounts = Vec::with_capacity(7);
     accounts.push(AccountMeta::new_readonly(
         *insurance_contract_authority,
         true,
     ));
     accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(policy_mint, false));
+    accounts.push(AccountMeta::new(*policy_token_account, false));
+    accounts.push(AccountMeta::new(*policyholder, true));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `MigrateInsuranceContract` instruction
+pub fn migrate_insurance_contract(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    legacy_insurance_contract_account: &Pubkey,
+    insurance_contract_id: u32,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        program_id,
+        insurance_contract_authority,
+        insurance_contract_id,
+    );
+    let (config_account, _) = find_config_address(program_id);
+    let data = InsuranceContractInstruction::MigrateInsuranceContract.pack();
+
+    let mut accounts = Vec::with_capacity(6);
+    accounts.push(AccountMeta::new(*insurance_contract_authority, true));
+    accounts.push(AccountMeta::new(*legacy_insurance_contract_account, false));
+    accounts.push(AccountMeta::new(insurance_contract_account, false));
     accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(config_account, false));
 
     Ok(Instruction {
         program_id: *program_id,
@@ -103,23 +598,160 @@ pub fn save_insurance_contract(
     })
 }
 
+/// Creates a `SuspendInsuranceContract` instruction
+pub fn suspend_insurance_contract(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
 
-/// Creates a `CloseInsuranceContract` instruction
-pub fn close_insurance_contract(
+    let data = InsuranceContractInstruction::SuspendInsuranceContract.pack();
+
+    let mut accounts = Vec::with_capacity(4);
+    accounts.push(AccountMeta::new_readonly(
+        *insurance_contract_authority,
+        true,
+    ));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ReinstateInsuranceContract` instruction
+pub fn reinstate_insurance_contract(
     program_id: &Pubkey,
     insurance_contract_authority: &Pubkey,
     insurance_contract_account: &Pubkey,
 ) -> Result<Instruction, ProgramError> {
     check_program_account(program_id)?;
 
-    let data = InsuranceContractInstruction::CloseInsuranceContract {}.pack();
+    let data = InsuranceContractInstruction::ReinstateInsuranceContract.pack();
 
-    let mut accounts = Vec::with_capacity(3);
+    let mut accounts = Vec::with_capacity(4);
+    accounts.push(AccountMeta::new_readonly(
+        *insurance_contract_authority,
+        true,
+    ));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CancelInsuranceContract` instruction
+pub fn cancel_insurance_contract(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    refund_token_account: &Pubkey,
+    policy_token_account: &Pubkey,
+    reason: u8,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (vault_account, _) = find_vault_address(program_id, insurance_contract_account);
+    let data = InsuranceContractInstruction::CancelInsuranceContract { reason }.pack();
+
+    let mut accounts = Vec::with_capacity(7);
     accounts.push(AccountMeta::new_readonly(
         *insurance_contract_authority,
         true,
     ));
     accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(vault_account, false));
+    accounts.push(AccountMeta::new(*refund_token_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(*policy_token_account, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `PayPremium` instruction
+pub fn pay_premium(
+    program_id: &Pubkey,
+    premium_payer: &Pubkey,
+    premium_payer_token_account: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    premium_mint: &Pubkey,
+    amount: u64,
+    treasury_token_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (vault_account, _) = find_vault_address(program_id, insurance_contract_account);
+    let (config_account, _) = find_config_address(program_id);
+    let data = InsuranceContractInstruction::PayPremium { amount }.pack();
+
+    let mut accounts = Vec::with_capacity(10);
+    accounts.push(AccountMeta::new(*premium_payer, true));
+    accounts.push(AccountMeta::new(*premium_payer_token_account, false));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(vault_account, false));
+    accounts.push(AccountMeta::new_readonly(*premium_mint, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(config_account, false));
+    accounts.push(AccountMeta::new(*treasury_token_account, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `SubmitClaim` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn submit_claim(
+    program_id: &Pubkey,
+    policyholder: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    claim_index: u32,
+    amount: u64,
+    incident_time: UnixTimestamp,
+    evidence_hash: [u8; 32],
+    peril: u16,
+    policy_token_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (claim_account, _) =
+        find_claim_address(program_id, insurance_contract_account, claim_index);
+    let data = InsuranceContractInstruction::SubmitClaim {
+        amount,
+        incident_time,
+        evidence_hash,
+        peril,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(8);
+    accounts.push(AccountMeta::new(*policyholder, true));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(claim_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(*policy_token_account, false));
+    accounts.push(pause_check_account(program_id));
 
     Ok(Instruction {
         program_id: *program_id,
@@ -127,3 +759,939 @@ pub fn close_insurance_contract(
         data,
     })
 }
+
+/// Creates an `ApproveClaim` instruction
+pub fn approve_claim(
+    program_id: &Pubkey,
+    claims_adjuster: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    claim_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::ApproveClaim.pack();
+
+    let mut accounts = Vec::with_capacity(4);
+    accounts.push(AccountMeta::new_readonly(*claims_adjuster, true));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(*claim_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RejectClaim` instruction
+pub fn reject_claim(
+    program_id: &Pubkey,
+    claims_adjuster: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    claim_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::RejectClaim.pack();
+
+    let mut accounts = Vec::with_capacity(4);
+    accounts.push(AccountMeta::new_readonly(*claims_adjuster, true));
+    accounts.push(AccountMeta::new_readonly(
+        *insurance_contract_account,
+        false,
+    ));
+    accounts.push(AccountMeta::new(*claim_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `PayClaim` instruction
+pub fn pay_claim(
+    program_id: &Pubkey,
+    claims_adjuster: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    claim_account: &Pubkey,
+    beneficiary_token_account: &Pubkey,
+    policy_token_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (vault_account, _) = find_vault_address(program_id, insurance_contract_account);
+    let data = InsuranceContractInstruction::PayClaim.pack();
+
+    let mut accounts = Vec::with_capacity(7);
+    accounts.push(AccountMeta::new_readonly(*claims_adjuster, true));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(*claim_account, false));
+    accounts.push(AccountMeta::new(vault_account, false));
+    accounts.push(AccountMeta::new(*beneficiary_token_account, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(*policy_token_account, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ReclaimInsuranceContract` instruction
+pub fn reclaim_insurance_contract(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    destination: &Pubkey,
+    premium_token_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (vault_account, _) = find_vault_address(program_id, insurance_contract_account);
+    let data = InsuranceContractInstruction::ReclaimInsuranceContract.pack();
+
+    let mut accounts = Vec::with_capacity(7);
+    accounts.push(AccountMeta::new_readonly(
+        *insurance_contract_authority,
+        true,
+    ));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(vault_account, false));
+    accounts.push(AccountMeta::new(*destination, false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new(*premium_token_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `ExpireInsuranceContract` instruction
+pub fn expire_insurance_contract(
+    program_id: &Pubkey,
+    keeper: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::ExpireInsuranceContract.pack();
+
+    let mut accounts = Vec::with_capacity(5);
+    accounts.push(AccountMeta::new(*keeper, true));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RenewInsuranceContract` instruction
+#[allow(clippy::too_many_arguments)]
+pub fn renew_insurance_contract(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    premium_payer: &Pubkey,
+    premium_payer_token_account: &Pubkey,
+    predecessor_insurance_contract_account: &Pubkey,
+    premium_mint: &Pubkey,
+    predecessor_policy_token_account: &Pubkey,
+    insurance_contract_id: u32,
+    coverage_end: UnixTimestamp,
+    premium_amount: u64,
+    treasury_token_account: &Pubkey,
+    product_account: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        program_id,
+        insurance_contract_authority,
+        insurance_contract_id,
+    );
+    let (vault_account, _) = find_vault_address(program_id, &insurance_contract_account);
+    let (policy_mint, _) = find_policy_mint_address(program_id, &insurance_contract_account);
+    let (policy_token_account, _) =
+        find_policy_token_address(program_id, &insurance_contract_account);
+    let (config_account, _) = find_config_address(program_id);
+    let data = InsuranceContractInstruction::RenewInsuranceContract {
+        insurance_contract_id,
+        coverage_end,
+        premium_amount,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(17);
+    accounts.push(AccountMeta::new(*insurance_contract_authority, true));
+    accounts.push(AccountMeta::new(*premium_payer, true));
+    accounts.push(AccountMeta::new(*premium_payer_token_account, false));
+    accounts.push(AccountMeta::new(
+        *predecessor_insurance_contract_account,
+        false,
+    ));
+    accounts.push(AccountMeta::new(insurance_contract_account, false));
+    accounts.push(AccountMeta::new(vault_account, false));
+    accounts.push(AccountMeta::new_readonly(*premium_mint, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(
+        *predecessor_policy_token_account,
+        false,
+    ));
+    accounts.push(AccountMeta::new(policy_mint, false));
+    accounts.push(AccountMeta::new(policy_token_account, false));
+    accounts.push(AccountMeta::new_readonly(config_account, false));
+    accounts.push(AccountMeta::new(*treasury_token_account, false));
+    if let Some(product_account) = product_account {
+        accounts.push(AccountMeta::new(*product_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `PayInstallment` instruction
+pub fn pay_installment(
+    program_id: &Pubkey,
+    premium_payer: &Pubkey,
+    premium_payer_token_account: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    premium_mint: &Pubkey,
+    treasury_token_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (vault_account, _) = find_vault_address(program_id, insurance_contract_account);
+    let (config_account, _) = find_config_address(program_id);
+    let data = InsuranceContractInstruction::PayInstallment.pack();
+
+    let mut accounts = Vec::with_capacity(11);
+    accounts.push(AccountMeta::new(*premium_payer, true));
+    accounts.push(AccountMeta::new(*premium_payer_token_account, false));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new(vault_account, false));
+    accounts.push(AccountMeta::new_readonly(*premium_mint, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    accounts.push(AccountMeta::new_readonly(config_account, false));
+    accounts.push(AccountMeta::new(*treasury_token_account, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `LapseInsuranceContract` instruction
+pub fn lapse_insurance_contract(
+    program_id: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::LapseInsuranceContract.pack();
+
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `ProposeAuthority` instruction
+pub fn propose_authority(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    new_authority: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::ProposeAuthority {
+        new_authority: *new_authority,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new_readonly(
+        *insurance_contract_authority,
+        true,
+    ));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `AcceptAuthority` instruction
+pub fn accept_authority(
+    program_id: &Pubkey,
+    new_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::AcceptAuthority.pack();
+
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new_readonly(*new_authority, true));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CancelAuthorityProposal` instruction
+pub fn cancel_authority_proposal(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::CancelAuthorityProposal.pack();
+
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new_readonly(
+        *insurance_contract_authority,
+        true,
+    ));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `AssignPolicy` instruction. The policy token accounts of the policyholder
+/// and of the new holder are given for tokenized policies.
+pub fn assign_policy(
+    program_id: &Pubkey,
+    policyholder: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    new_holder: &Pubkey,
+    policy_token_accounts: Option<(&Pubkey, &Pubkey)>,
+    insurance_contract_authority: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::AssignPolicy {
+        new_holder: *new_holder,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(9);
+    accounts.push(AccountMeta::new_readonly(*policyholder, true));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    if let Some((policy_token_account, new_holder_token_account)) = policy_token_accounts {
+        let (policy_mint, _) = find_policy_mint_address(program_id, insurance_contract_account);
+        accounts.push(AccountMeta::new(*policy_token_account, false));
+        accounts.push(AccountMeta::new(*new_holder_token_account, false));
+        accounts.push(AccountMeta::new_readonly(policy_mint, false));
+        accounts.push(AccountMeta::new_readonly(spl_token::id(), false));
+    }
+    if let Some(insurance_contract_authority) = insurance_contract_authority {
+        accounts.push(AccountMeta::new_readonly(
+            *insurance_contract_authority,
+            true,
+        ));
+    }
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `CreateProduct` instruction
+pub fn create_product(
+    program_id: &Pubkey,
+    product_authority: &Pubkey,
+    product_id: u32,
+    terms: ProductTerms,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (product_account, _) =
+        find_insurance_product_address(program_id, product_authority, product_id);
+
+    let data = InsuranceContractInstruction::CreateProduct { product_id, terms }.pack();
+
+    let mut accounts = Vec::with_capacity(6);
+    accounts.push(AccountMeta::new(*product_authority, true));
+    accounts.push(AccountMeta::new(product_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateProduct` instruction
+pub fn update_product(
+    program_id: &Pubkey,
+    product_authority: &Pubkey,
+    product_account: &Pubkey,
+    terms: ProductTerms,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::UpdateProduct { terms }.pack();
+
+    let mut accounts = Vec::with_capacity(4);
+    accounts.push(AccountMeta::new_readonly(*product_authority, true));
+    accounts.push(AccountMeta::new(*product_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::clock::id(), false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `RetireProduct` instruction
+pub fn retire_product(
+    program_id: &Pubkey,
+    product_authority: &Pubkey,
+    product_account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::RetireProduct.pack();
+
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new_readonly(*product_authority, true));
+    accounts.push(AccountMeta::new(*product_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `InitializeConfig` instruction
+pub fn initialize_config(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    settings: ConfigSettings,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (config_account, _) = find_config_address(program_id);
+    let (program_data_account, _) = find_program_data_address(program_id);
+    let data = InsuranceContractInstruction::InitializeConfig { settings }.pack();
+
+    let mut accounts = Vec::with_capacity(5);
+    accounts.push(AccountMeta::new(*admin, true));
+    accounts.push(AccountMeta::new(config_account, false));
+    accounts.push(AccountMeta::new_readonly(sysvar::rent::id(), false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(program_data_account, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `UpdateConfig` instruction
+pub fn update_config(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    settings: ConfigSettings,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (config_account, _) = find_config_address(program_id);
+    let data = InsuranceContractInstruction::UpdateConfig { settings }.pack();
+
+    let mut accounts = Vec::with_capacity(2);
+    accounts.push(AccountMeta::new_readonly(*admin, true));
+    accounts.push(AccountMeta::new(config_account, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates a `Pause` instruction, pausing the given product or the whole program
+pub fn pause(
+    program_id: &Pubkey,
+    guardian: &Pubkey,
+    product_account: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    set_paused(
+        program_id,
+        guardian,
+        product_account,
+        InsuranceContractInstruction::Pause,
+    )
+}
+
+/// Creates an `Unpause` instruction, unpausing the given product or the whole program
+pub fn unpause(
+    program_id: &Pubkey,
+    guardian: &Pubkey,
+    product_account: Option<&Pubkey>,
+) -> Result<Instruction, ProgramError> {
+    set_paused(
+        program_id,
+        guardian,
+        product_account,
+        InsuranceContractInstruction::Unpause,
+    )
+}
+
+fn set_paused(
+    program_id: &Pubkey,
+    guardian: &Pubkey,
+    product_account: Option<&Pubkey>,
+    instruction: InsuranceContractInstruction,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let (config_account, _) = find_config_address(program_id);
+    let data = instruction.pack();
+
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new_readonly(*guardian, true));
+    accounts.push(AccountMeta::new(config_account, false));
+    if let Some(product_account) = product_account {
+        accounts.push(AccountMeta::new(*product_account, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Creates an `AmendDocument` instruction
+pub fn amend_document(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    document_hash: [u8; 32],
+    document_uri: DocumentUri,
+) -> Result<Instruction, ProgramError> {
+    check_program_account(program_id)?;
+
+    let data = InsuranceContractInstruction::AmendDocument {
+        document_hash,
+        document_uri,
+    }
+    .pack();
+
+    let mut accounts = Vec::with_capacity(3);
+    accounts.push(AccountMeta::new_readonly(
+        *insurance_contract_authority,
+        true,
+    ));
+    accounts.push(AccountMeta::new(*insurance_contract_account, false));
+    accounts.push(pause_check_account(program_id));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
+/// Program config account the pausable instructions end with
+fn pause_check_account(program_id: &Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(find_config_address(program_id).0, false)
+}
+
+// Codec tests
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::{Peril, MAX_DOCUMENT_URI_LEN};
+    use proptest::prelude::*;
+    use InsuranceContractInstruction::*;
+
+    /// Stable tag of every instruction, the match keeps the list exhaustive
+    fn tag(instruction: &InsuranceContractInstruction) -> u8 {
+        match instruction {
+            SaveInsuranceContract { .. } => 0,
+            CloseInsuranceContract => 1,
+            MigrateInsuranceContract => 2,
+            SuspendInsuranceContract => 3,
+            ReinstateInsuranceContract => 4,
+            CancelInsuranceContract { .. } => 5,
+            PayPremium { .. } => 6,
+            SubmitClaim { .. } => 7,
+            ApproveClaim => 8,
+            RejectClaim => 9,
+            PayClaim => 10,
+            ReclaimInsuranceContract => 11,
+            ExpireInsuranceContract => 12,
+            RenewInsuranceContract { .. } => 13,
+            PayInstallment => 14,
+            LapseInsuranceContract => 15,
+            ProposeAuthority { .. } => 16,
+            AcceptAuthority => 17,
+            CancelAuthorityProposal => 18,
+            AssignPolicy { .. } => 19,
+            CreateProduct { .. } => 20,
+            UpdateProduct { .. } => 21,
+            RetireProduct => 22,
+            InitializeConfig { .. } => 23,
+            UpdateConfig { .. } => 24,
+            Pause => 25,
+            Unpause => 26,
+            AmendDocument { .. } => 27,
+        }
+    }
+
+    fn pubkey() -> impl Strategy<Value = Pubkey> {
+        any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+    }
+
+    /// Arbitrary URI bytes, the codec doesn't check the padding
+    fn document_uri() -> impl Strategy<Value = DocumentUri> {
+        prop::collection::vec(any::<u8>(), MAX_DOCUMENT_URI_LEN).prop_map(|bytes| {
+            let mut uri = DocumentUri::default();
+            uri.0.copy_from_slice(&bytes);
+            uri
+        })
+    }
+
+    fn policy_terms() -> impl Strategy<Value = PolicyTerms> {
+        // Tuple strategies are limited to 12 elements
+        (
+            (
+                any::<i64>(),
+                any::<i64>(),
+                any::<u64>(),
+                any::<u64>(),
+                pubkey(),
+                pubkey(),
+            ),
+            (
+                any::<u64>(),
+                any::<u16>(),
+                any::<u16>(),
+                any::<u8>(),
+                any::<u64>(),
+                any::<i64>(),
+                any::<i64>(),
+                any::<bool>(),
+            ),
+            (
+                any::<u64>(),
+                any::<u64>(),
+                any::<u64>(),
+                prop::array::uniform6(
+                    (any::<u16>(), any::<u64>())
+                        .prop_map(|(code, sub_limit)| Peril { code, sub_limit }),
+                ),
+                any::<i64>(),
+            ),
+        )
+            .prop_map(
+                |(
+                    (
+                        coverage_start,
+                        coverage_end,
+                        sum_insured,
+                        premium_amount,
+                        premium_mint,
+                        claims_adjuster,
+                    ),
+                    (
+                        keeper_reward,
+                        cancellation_penalty_bps,
+                        no_claims_bonus_bps,
+                        installment_count,
+                        installment_amount,
+                        installment_interval,
+                        grace_period,
+                        assignment_consent_required,
+                    ),
+                    (
+                        deductible,
+                        per_occurrence_limit,
+                        annual_aggregate_limit,
+                        perils,
+                        waiting_period,
+                    ),
+                )| PolicyTerms {
+                    coverage_start,
+                    coverage_end,
+                    sum_insured,
+                    premium_amount,
+                    premium_mint,
+                    claims_adjuster,
+                    keeper_reward,
+                    cancellation_penalty_bps,
+                    no_claims_bonus_bps,
+                    installment_count,
+                    installment_amount,
+                    installment_interval,
+                    grace_period,
+                    assignment_consent_required,
+                    deductible,
+                    per_occurrence_limit,
+                    annual_aggregate_limit,
+                    perils,
+                    waiting_period,
+                },
+            )
+    }
+
+    fn config_settings() -> impl Strategy<Value = ConfigSettings> {
+        (
+            any::<u16>(),
+            pubkey(),
+            prop::array::uniform8(pubkey()),
+            pubkey(),
+        )
+            .prop_map(
+                |(protocol_fee_bps, treasury, allowed_premium_mints, guardian)| ConfigSettings {
+                    protocol_fee_bps,
+                    treasury,
+                    allowed_premium_mints,
+                    guardian,
+                },
+            )
+    }
+
+    /// Product terms made of arbitrary policy terms, the coverage period is taken from
+    /// the coverage end
+    fn product_terms(terms: &PolicyTerms) -> ProductTerms {
+        ProductTerms {
+            coverage_period: terms.coverage_end,
+            waiting_period: terms.waiting_period,
+            sum_insured: terms.sum_insured,
+            premium_amount: terms.premium_amount,
+            premium_mint: terms.premium_mint,
+            claims_adjuster: terms.claims_adjuster,
+            keeper_reward: terms.keeper_reward,
+            cancellation_penalty_bps: terms.cancellation_penalty_bps,
+            no_claims_bonus_bps: terms.no_claims_bonus_bps,
+            installment_count: terms.installment_count,
+            installment_amount: terms.installment_amount,
+            installment_interval: terms.installment_interval,
+            grace_period: terms.grace_period,
+            assignment_consent_required: terms.assignment_consent_required,
+            deductible: terms.deductible,
+            per_occurrence_limit: terms.per_occurrence_limit,
+            annual_aggregate_limit: terms.annual_aggregate_limit,
+            perils: terms.perils,
+        }
+    }
+
+    fn instruction() -> impl Strategy<Value = InsuranceContractInstruction> {
+        prop_oneof![
+            (any::<u32>(), policy_terms()).prop_map(|(insurance_contract_id, terms)| {
+                SaveInsuranceContract {
+                    insurance_contract_id,
+                    terms,
+                }
+            }),
+            Just(CloseInsuranceContract),
+            Just(MigrateInsuranceContract),
+            Just(SuspendInsuranceContract),
+            Just(ReinstateInsuranceContract),
+            any::<u8>().prop_map(|reason| CancelInsuranceContract { reason }),
+            any::<u64>().prop_map(|amount| PayPremium { amount }),
+            (any::<u64>(), any::<i64>(), any::<[u8; 32]>(), any::<u16>()).prop_map(
+                |(amount, incident_time, evidence_hash, peril)| SubmitClaim {
+                    amount,
+                    incident_time,
+                    evidence_hash,
+                    peril,
+                }
+            ),
+            Just(ApproveClaim),
+            Just(RejectClaim),
+            Just(PayClaim),
+            Just(ReclaimInsuranceContract),
+            Just(ExpireInsuranceContract),
+            (any::<u32>(), any::<i64>(), any::<u64>()).prop_map(
+                |(insurance_contract_id, coverage_end, premium_amount)| RenewInsuranceContract {
+                    insurance_contract_id,
+                    coverage_end,
+                    premium_amount,
+                }
+            ),
+            Just(PayInstallment),
+            Just(LapseInsuranceContract),
+            pubkey().prop_map(|new_authority| ProposeAuthority { new_authority }),
+            Just(AcceptAuthority),
+            Just(CancelAuthorityProposal),
+            pubkey().prop_map(|new_holder| AssignPolicy { new_holder }),
+            (any::<u32>(), policy_terms()).prop_map(|(product_id, terms)| CreateProduct {
+                product_id,
+                terms: product_terms(&terms),
+            }),
+            policy_terms().prop_map(|terms| UpdateProduct {
+                terms: product_terms(&terms),
+            }),
+            Just(RetireProduct),
+            config_settings().prop_map(|settings| InitializeConfig { settings }),
+            config_settings().prop_map(|settings| UpdateConfig { settings }),
+            Just(Pause),
+            Just(Unpause),
+            (any::<[u8; 32]>(), document_uri()).prop_map(|(document_hash, document_uri)| {
+                AmendDocument {
+                    document_hash,
+                    document_uri,
+                }
+            }),
+        ]
+    }
+
+    fn invalid_instruction() -> Result<InsuranceContractInstruction, ProgramError> {
+        Err(InvalidInstruction.into())
+    }
+
+    proptest! {
+        #[test]
+        fn test_pack_unpack(instruction in instruction()) {
+            let packed = instruction.pack();
+            prop_assert_eq!(packed[0], tag(&instruction));
+            prop_assert_eq!(InsuranceContractInstruction::unpack(&packed), Ok(instruction));
+        }
+
+        #[test]
+        fn test_unpack_truncated(instruction in instruction(), cut in any::<prop::sample::Index>()) {
+            let packed = instruction.pack();
+            let truncated = &packed[..cut.index(packed.len())];
+            prop_assert_eq!(InsuranceContractInstruction::unpack(truncated), invalid_instruction());
+        }
+
+        #[test]
+        fn test_unpack_trailing_bytes(
+            instruction in instruction(),
+            trailing in prop::collection::vec(any::<u8>(), 1..16),
+        ) {
+            let mut packed = instruction.pack();
+            packed.extend_from_slice(&trailing);
+            prop_assert_eq!(InsuranceContractInstruction::unpack(&packed), invalid_instruction());
+        }
+
+        #[test]
+        fn test_unpack_arbitrary(data in prop::collection::vec(any::<u8>(), 0..256)) {
+            if let Ok(instruction) = InsuranceContractInstruction::unpack(&data) {
+                prop_assert_eq!(instruction.pack(), data);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unpack_unknown_tag() {
+        assert_eq!(
+            InsuranceContractInstruction::unpack(&[]),
+            invalid_instruction()
+        );
+        let last = AmendDocument {
+            document_hash: [0; 32],
+            document_uri: DocumentUri::default(),
+        };
+        assert_eq!(
+            InsuranceContractInstruction::unpack(&[tag(&last) + 1]),
+            invalid_instruction()
+        );
+        // Short SaveInsuranceContract used to panic
+        assert_eq!(
+            InsuranceContractInstruction::unpack(&[0, 1, 2]),
+            invalid_instruction()
+        );
+    }
+
+    #[test]
+    fn test_config_account_index() {
+        let program_id = crate::id();
+        let (config_account, _) = find_config_address(&program_id);
+        let key = Pubkey::new_unique;
+        let instructions = vec![
+            migrate_insurance_contract(&program_id, &key(), &key(), 1).unwrap(),
+            pay_premium(&program_id, &key(), &key(), &key(), &key(), 1, &key()).unwrap(),
+            renew_insurance_contract(
+                &program_id,
+                &key(),
+                &key(),
+                &key(),
+                &key(),
+                &key(),
+                &key(),
+                1,
+                2,
+                3,
+                &key(),
+                Some(&key()),
+            )
+            .unwrap(),
+            pay_installment(&program_id, &key(), &key(), &key(), &key(), &key()).unwrap(),
+            suspend_insurance_contract(&program_id, &key(), &key()).unwrap(),
+        ];
+        for instruction in instructions {
+            let config_index = InsuranceContractInstruction::unpack(&instruction.data)
+                .unwrap()
+                .config_account_index()
+                .unwrap_or(instruction.accounts.len() - 1);
+            assert_eq!(config_account, instruction.accounts[config_index].pubkey);
+            assert_eq!(
+                1,
+                instruction
+                    .accounts
+                    .iter()
+                    .filter(|account_meta| account_meta.pubkey == config_account)
+                    .count()
+            );
+        }
+    }
+}
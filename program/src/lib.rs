@@ -3,6 +3,7 @@
 use solana_program::{entrypoint::ProgramResult, program_error::ProgramError, pubkey::Pubkey};
 
 pub mod error;
+pub mod events;
 pub mod instruction;
 pub mod processor;
 pub mod state;
@@ -21,3 +22,122 @@ pub fn check_program_account(program_id: &Pubkey) -> ProgramResult {
     }
     Ok(())
 }
+
+/// Seed prefix of InsuranceContract account addresses
+pub const INSURANCE_CONTRACT_SEED: &[u8] = b"insurance_contract";
+
+/// Derives the InsuranceContract account address of the authority and contract identifier
+pub fn find_insurance_contract_address(
+    program_id: &Pubkey,
+    insurance_contract_authority: &Pubkey,
+    insurance_contract_id: u32,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            INSURANCE_CONTRACT_SEED,
+            &insurance_contract_authority.to_bytes(),
+            &insurance_contract_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Seed prefix of premium vault token account addresses
+pub const VAULT_SEED: &[u8] = b"vault";
+
+/// Derives the address of the token account holding the premiums of an InsuranceContract.
+/// The vault is owned by its own address.
+pub fn find_vault_address(
+    program_id: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[VAULT_SEED, &insurance_contract_account.to_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of policy token mint addresses
+pub const POLICY_MINT_SEED: &[u8] = b"policy_mint";
+
+/// Derives the address of the mint of the token representing an InsuranceContract.
+/// The mint is its own mint and freeze authority, the supply is fixed to a single token.
+pub fn find_policy_mint_address(
+    program_id: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POLICY_MINT_SEED, &insurance_contract_account.to_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of policy token account addresses
+pub const POLICY_TOKEN_SEED: &[u8] = b"policy_token";
+
+/// Derives the address of the token account the policy token is minted to.
+/// The account is owned by the authority, the token can be moved to any other account.
+pub fn find_policy_token_address(
+    program_id: &Pubkey,
+    insurance_contract_account: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[POLICY_TOKEN_SEED, &insurance_contract_account.to_bytes()],
+        program_id,
+    )
+}
+
+/// Seed prefix of Claim account addresses
+pub const CLAIM_SEED: &[u8] = b"claim";
+
+/// Derives the address of a Claim account from its InsuranceContract and index
+pub fn find_claim_address(
+    program_id: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    claim_index: u32,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            CLAIM_SEED,
+            &insurance_contract_account.to_bytes(),
+            &claim_index.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Seed prefix of InsuranceProduct account addresses
+pub const INSURANCE_PRODUCT_SEED: &[u8] = b"insurance_product";
+
+/// Derives the InsuranceProduct account address of the authority and product identifier
+pub fn find_insurance_product_address(
+    program_id: &Pubkey,
+    product_authority: &Pubkey,
+    product_id: u32,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            INSURANCE_PRODUCT_SEED,
+            &product_authority.to_bytes(),
+            &product_id.to_le_bytes(),
+        ],
+        program_id,
+    )
+}
+
+/// Seed of the program config account address
+pub const CONFIG_SEED: &[u8] = b"config";
+
+/// Derives the address of the singleton program config account
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CONFIG_SEED], program_id)
+}
+
+/// Derives the address of the program data account the upgradeable BPF loader stores the
+/// program and its upgrade authority in
+pub fn find_program_data_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[&program_id.to_bytes()],
+        &solana_program::bpf_loader_upgradeable::id(),
+    )
+}
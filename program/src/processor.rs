@@ -2,19 +2,38 @@
 use crate::{
     check_program_account,
     error::InsuranceContractError,
+    events::{
+        self, AuthorityChanged, ClaimAdjusted, ClaimPaid, ClaimSubmitted, ContractCancelled,
+        ContractClosed, ContractReclaimed, ContractRenewed, ContractSaved, ContractStatusChanged,
+        DocumentAmended, PauseChanged, PolicyAssigned, PremiumPaid,
+    },
+    find_claim_address, find_config_address, find_insurance_contract_address,
+    find_insurance_product_address, find_policy_mint_address, find_policy_token_address,
+    find_program_data_address, find_vault_address,
     instruction::InsuranceContractInstruction,
-    state::{self, InsuranceContractData},
+    state::{
+        self, AccountType, ClaimData, ClaimStatus, ConfigData, ConfigSettings, DocumentUri,
+        InsuranceContractData, InsuranceContractStatus, InsuranceProductData, PolicyTerms,
+        ProductStatus, ProductTerms,
+    },
+    CLAIM_SEED, CONFIG_SEED, INSURANCE_CONTRACT_SEED, INSURANCE_PRODUCT_SEED, POLICY_MINT_SEED,
+    POLICY_TOKEN_SEED, VAULT_SEED,
 };
-use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
+    clock::{Clock, UnixTimestamp},
     entrypoint::ProgramResult,
     msg,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
+    program_pack::Pack,
     pubkey::Pubkey,
     rent::Rent,
+    system_instruction,
     sysvar::Sysvar,
 };
+use spl_token::state::{Account as TokenAccount, Mint};
 
 /// Program state handler.
 pub struct Processor;
@@ -27,18 +46,193 @@ impl Processor {
         check_program_account(program_id)?;
 
         let instruction = InsuranceContractInstruction::unpack(instruction_data)?;
+        let accounts = if instruction.is_pausable() {
+            let (config_info, accounts) = match instruction.config_account_index() {
+                Some(config_index) => (accounts.get(config_index), accounts),
+                None => match accounts.split_last() {
+                    Some((config_info, accounts)) => (Some(config_info), accounts),
+                    None => (None, accounts),
+                },
+            };
+            let config_info = config_info.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            Self::check_not_paused(program_id, config_info)?;
+            accounts
+        } else {
+            accounts
+        };
         match instruction {
             InsuranceContractInstruction::SaveInsuranceContract {
                 insurance_contract_id,
+                terms,
             } => {
                 msg!("Instruction: save insurance contract");
-                Self::process_save_insurance_contract(program_id, accounts, insurance_contract_id)
+                Self::process_save_insurance_contract(
+                    program_id,
+                    accounts,
+                    insurance_contract_id,
+                    &terms,
+                )
             }
 
             InsuranceContractInstruction::CloseInsuranceContract {} => {
                 msg!("Instruction: close insurance contract");
                 Self::process_close_insurance_contract(program_id, accounts)
             }
+
+            InsuranceContractInstruction::MigrateInsuranceContract => {
+                msg!("Instruction: migrate insurance contract");
+                Self::process_migrate_insurance_contract(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::SuspendInsuranceContract => {
+                msg!("Instruction: suspend insurance contract");
+                Self::process_suspend_insurance_contract(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::ReinstateInsuranceContract => {
+                msg!("Instruction: reinstate insurance contract");
+                Self::process_reinstate_insurance_contract(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::CancelInsuranceContract { reason } => {
+                msg!("Instruction: cancel insurance contract");
+                Self::process_cancel_insurance_contract(program_id, accounts, reason)
+            }
+
+            InsuranceContractInstruction::PayPremium { amount } => {
+                msg!("Instruction: pay premium");
+                Self::process_pay_premium(program_id, accounts, amount)
+            }
+
+            InsuranceContractInstruction::SubmitClaim {
+                amount,
+                incident_time,
+                evidence_hash,
+                peril,
+            } => {
+                msg!("Instruction: submit claim");
+                Self::process_submit_claim(
+                    program_id,
+                    accounts,
+                    amount,
+                    incident_time,
+                    evidence_hash,
+                    peril,
+                )
+            }
+
+            InsuranceContractInstruction::ApproveClaim => {
+                msg!("Instruction: approve claim");
+                Self::process_adjust_claim(program_id, accounts, ClaimStatus::Approved)
+            }
+
+            InsuranceContractInstruction::RejectClaim => {
+                msg!("Instruction: reject claim");
+                Self::process_adjust_claim(program_id, accounts, ClaimStatus::Rejected)
+            }
+
+            InsuranceContractInstruction::PayClaim => {
+                msg!("Instruction: pay claim");
+                Self::process_pay_claim(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::ReclaimInsuranceContract => {
+                msg!("Instruction: reclaim insurance contract");
+                Self::process_reclaim_insurance_contract(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::ExpireInsuranceContract => {
+                msg!("Instruction: expire insurance contract");
+                Self::process_expire_insurance_contract(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::RenewInsuranceContract {
+                insurance_contract_id,
+                coverage_end,
+                premium_amount,
+            } => {
+                msg!("Instruction: renew insurance contract");
+                Self::process_renew_insurance_contract(
+                    program_id,
+                    accounts,
+                    insurance_contract_id,
+                    coverage_end,
+                    premium_amount,
+                )
+            }
+
+            InsuranceContractInstruction::PayInstallment => {
+                msg!("Instruction: pay installment");
+                Self::process_pay_installment(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::LapseInsuranceContract => {
+                msg!("Instruction: lapse insurance contract");
+                Self::process_lapse_insurance_contract(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::ProposeAuthority { new_authority } => {
+                msg!("Instruction: propose authority");
+                Self::process_set_pending_authority(program_id, accounts, new_authority)
+            }
+
+            InsuranceContractInstruction::AcceptAuthority => {
+                msg!("Instruction: accept authority");
+                Self::process_accept_authority(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::CancelAuthorityProposal => {
+                msg!("Instruction: cancel authority proposal");
+                Self::process_set_pending_authority(program_id, accounts, Pubkey::default())
+            }
+
+            InsuranceContractInstruction::AssignPolicy { new_holder } => {
+                msg!("Instruction: assign policy");
+                Self::process_assign_policy(program_id, accounts, new_holder)
+            }
+
+            InsuranceContractInstruction::CreateProduct { product_id, terms } => {
+                msg!("Instruction: create product");
+                Self::process_create_product(program_id, accounts, product_id, &terms)
+            }
+
+            InsuranceContractInstruction::UpdateProduct { terms } => {
+                msg!("Instruction: update product");
+                Self::process_update_product(program_id, accounts, &terms)
+            }
+
+            InsuranceContractInstruction::RetireProduct => {
+                msg!("Instruction: retire product");
+                Self::process_retire_product(program_id, accounts)
+            }
+
+            InsuranceContractInstruction::InitializeConfig { settings } => {
+                msg!("Instruction: initialize config");
+                Self::process_initialize_config(program_id, accounts, &settings)
+            }
+
+            InsuranceContractInstruction::UpdateConfig { settings } => {
+                msg!("Instruction: update config");
+                Self::process_update_config(program_id, accounts, &settings)
+            }
+
+            InsuranceContractInstruction::Pause => {
+                msg!("Instruction: pause");
+                Self::process_set_paused(program_id, accounts, true)
+            }
+
+            InsuranceContractInstruction::Unpause => {
+                msg!("Instruction: unpause");
+                Self::process_set_paused(program_id, accounts, false)
+            }
+
+            InsuranceContractInstruction::AmendDocument {
+                document_hash,
+                document_uri,
+            } => {
+                msg!("Instruction: amend document");
+                Self::process_amend_document(program_id, accounts, document_hash, document_uri)
+            }
         }
     }
 
@@ -46,23 +240,60 @@ impl Processor {
         program_id: &Pubkey,
         accounts: &[AccountInfo],
         insurance_contract_id: u32,
+        terms: &PolicyTerms,
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let insurance_contract_authority = next_account_info(accounts_iter)?;
         let insurance_contract_account = next_account_info(accounts_iter)?;
         let rent_info = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        let policy_mint_info = next_account_info(accounts_iter)?;
+        let policy_token_account = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
 
         if !insurance_contract_authority.is_signer {
             msg!("Missing Insurance contract authority signature");
             return Err(ProgramError::MissingRequiredSignature);
         }
-
-        if insurance_contract_account.owner != program_id {
-            msg!("Invalid owner for InsuranceContractDccount data account");
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
             return Err(ProgramError::IncorrectProgramId);
         }
 
+        let clock = Clock::from_account_info(clock_info)?;
+        Self::check_policy_terms(terms, &clock)?;
+
+        // Contracts issued from a product carry its terms unchanged
+        let product = match accounts_iter.next() {
+            Some(product_account) => {
+                let mut product_data = Self::load_product(program_id, product_account)?;
+                Self::check_product_authority(insurance_contract_authority, &product_data)?;
+                Self::check_product_issuable(&product_data)?;
+                if *terms != product_data.terms.policy_terms(terms.coverage_start) {
+                    msg!("Terms don't match the insurance product");
+                    return Err(InsuranceContractError::ProductTermsMismatch.into());
+                }
+                product_data.policy_count = product_data
+                    .policy_count
+                    .checked_add(1)
+                    .ok_or(InsuranceContractError::Overflow)?;
+                product_data.pack(&mut product_account.data.borrow_mut())?;
+                *product_account.key
+            }
+            None => Pubkey::default(),
+        };
+
         let rent = Rent::from_account_info(rent_info)?;
+        Self::create_insurance_contract_account(
+            program_id,
+            insurance_contract_authority,
+            insurance_contract_account,
+            insurance_contract_id,
+            &rent,
+            system_program_info,
+        )?;
+
         if !rent.is_exempt(
             insurance_contract_account.lamports(),
             state::INSURANCE_CONTRACT_DATA_LEN,
@@ -72,258 +303,7219 @@ impl Processor {
         }
 
         let mut insurance_contract_data =
-            InsuranceContractData::try_from_slice(&insurance_contract_account.data.borrow())?;
-        if insurance_contract_data.is_initialized {
+            InsuranceContractData::unpack(&insurance_contract_account.data.borrow())?;
+        if insurance_contract_data.is_initialized() {
             msg!("Insurance data account already initialized!");
             return Err(InsuranceContractError::AlreadyInitialized.into());
         }
-        if insurance_contract_data.is_closed {
-            msg!("Insurance contract already closed!");
-            return Err(InsuranceContractError::AlreadyClosed.into());
-        }
 
-        insurance_contract_data.is_initialized = true;
-        insurance_contract_data.is_closed = false;
+        insurance_contract_data.account_type = AccountType::InsuranceContract;
+        insurance_contract_data.version = state::INSURANCE_CONTRACT_VERSION;
+        insurance_contract_data.status = if terms.premium_amount == 0 {
+            InsuranceContractStatus::Active
+        } else {
+            InsuranceContractStatus::Draft
+        };
         insurance_contract_data.insurance_contract_id = insurance_contract_id;
+        insurance_contract_data.authority = *insurance_contract_authority.key;
+        insurance_contract_data.set_terms(terms);
+        insurance_contract_data.policy_mint = *policy_mint_info.key;
+        insurance_contract_data.product = product;
 
-        insurance_contract_data
-            .serialize(&mut &mut insurance_contract_account.data.borrow_mut()[..])?;
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(ContractSaved {
+            insurance_contract: *insurance_contract_account.key,
+            authority: *insurance_contract_authority.key,
+            insurance_contract_id,
+            product,
+            status: insurance_contract_data.status,
+        });
 
-        Ok(())
+        Self::mint_policy_token(
+            program_id,
+            insurance_contract_authority,
+            insurance_contract_account,
+            policy_mint_info,
+            policy_token_account,
+            insurance_contract_authority.key,
+            terms.assignment_consent_required,
+            rent_info,
+            &rent,
+            system_program_info,
+            token_program_info,
+        )?;
+
+        Self::reserve_keeper_reward(
+            insurance_contract_authority,
+            insurance_contract_account,
+            terms.keeper_reward,
+            system_program_info,
+        )
     }
 
     pub fn process_close_insurance_contract(
         program_id: &Pubkey,
         accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let _insurance_contract_authority = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+
+        let insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        if !insurance_contract_data.is_tokenized() {
+            return Self::process_status_transition(
+                program_id,
+                accounts,
+                InsuranceContractStatus::Closed,
+            );
+        }
+
+        let policy_mint_info = next_account_info(accounts_iter)?;
+        let policy_token_account = next_account_info(accounts_iter)?;
+        let policyholder = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
+
+        if !policyholder.is_signer {
+            msg!("Missing policyholder signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Self::check_policyholder(policyholder, policy_token_account, &insurance_contract_data)?;
+
+        Self::process_status_transition(program_id, accounts, InsuranceContractStatus::Closed)?;
+
+        // The policy token is burned and its account is closed to the policyholder. The
+        // mint is kept deliberately: SPL Token can't close mints, its rent stays locked and
+        // its zero supply records that the policy was closed.
+        if TokenAccount::unpack(&policy_token_account.data.borrow())?.is_frozen() {
+            Self::set_policy_token_frozen(
+                program_id,
+                insurance_contract_account,
+                policy_mint_info,
+                policy_token_account,
+                token_program_info,
+                false,
+            )?;
+        }
+        invoke(
+            &spl_token::instruction::burn(
+                token_program_info.key,
+                policy_token_account.key,
+                policy_mint_info.key,
+                policyholder.key,
+                &[],
+                1,
+            )?,
+            &[
+                policy_token_account.clone(),
+                policy_mint_info.clone(),
+                policyholder.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::close_account(
+                token_program_info.key,
+                policy_token_account.key,
+                policyholder.key,
+                policyholder.key,
+                &[],
+            )?,
+            &[
+                policy_token_account.clone(),
+                policyholder.clone(),
+                token_program_info.clone(),
+            ],
+        )
+    }
+
+    pub fn process_migrate_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
     ) -> ProgramResult {
         let accounts_iter = &mut accounts.iter();
         let insurance_contract_authority = next_account_info(accounts_iter)?;
+        let legacy_insurance_contract_account = next_account_info(accounts_iter)?;
         let insurance_contract_account = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        let config_info = next_account_info(accounts_iter)?;
 
         if !insurance_contract_authority.is_signer {
             msg!("Missing Insurance contract authority signature");
             return Err(ProgramError::MissingRequiredSignature);
         }
 
-        if insurance_contract_account.owner != program_id {
+        if legacy_insurance_contract_account.owner != program_id {
             msg!("Invalid owner for InsuranceContractDccount data account");
             return Err(ProgramError::IncorrectProgramId);
         }
 
-        let mut insurance_contract_data =
-            InsuranceContractData::try_from_slice(&insurance_contract_account.data.borrow())?;
-        if !insurance_contract_data.is_initialized {
+        match InsuranceContractData::unpack(&legacy_insurance_contract_account.data.borrow()) {
+            Err(error) if error == InsuranceContractError::MigrationRequired.into() => {}
+            Err(error) => return Err(error),
+            Ok(_) => {
+                msg!("Insurance data account already migrated!");
+                return Err(InsuranceContractError::AlreadyMigrated.into());
+            }
+        }
+        let mut insurance_contract_data = InsuranceContractData::unpack_from_any_version(
+            &legacy_insurance_contract_account.data.borrow(),
+        )?;
+        if !insurance_contract_data.is_initialized() {
             msg!("Insurance data account is not initialized!");
             return Err(InsuranceContractError::NotInitialized.into());
         }
-        if insurance_contract_data.is_closed {
-            msg!("Insurance contract already closed!");
-            return Err(InsuranceContractError::AlreadyClosed.into());
+        if insurance_contract_data.authority == Pubkey::default() {
+            // v0 accounts were created before the authority was stored, nothing ties them
+            // to their owner so only the admin takes them over
+            match Self::load_config(program_id, config_info)? {
+                Some(config_data) if config_data.admin == *insurance_contract_authority.key => {}
+                _ => {
+                    msg!("Legacy account without authority can only be migrated by the admin");
+                    return Err(InsuranceContractError::AdminMismatch.into());
+                }
+            }
+            insurance_contract_data.authority = *insurance_contract_authority.key;
+        }
+        Self::check_authority(insurance_contract_authority, &insurance_contract_data)?;
+
+        let rent = Rent::from_account_info(rent_info)?;
+        Self::create_insurance_contract_account(
+            program_id,
+            insurance_contract_authority,
+            insurance_contract_account,
+            insurance_contract_data.insurance_contract_id,
+            &rent,
+            system_program_info,
+        )?;
+
+        if !rent.is_exempt(
+            insurance_contract_account.lamports(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+        ) {
+            msg!("Rent exempt error for InsuranceContractData account");
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        if legacy_insurance_contract_account.key == insurance_contract_account.key {
+            // Versioned accounts already live at the derived address and are migrated in place
+            insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+            return Ok(());
+        }
+
+        let new_insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_account.data.borrow())?;
+        if new_insurance_contract_data.is_initialized() {
+            msg!("Insurance data account already initialized!");
+            return Err(InsuranceContractError::AlreadyInitialized.into());
+        }
+
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+
+        // Close the legacy account
+        legacy_insurance_contract_account
+            .data
+            .borrow_mut()
+            .iter_mut()
+            .for_each(|byte| *byte = 0);
+        let authority_starting_lamports = insurance_contract_authority.lamports();
+        **insurance_contract_authority.lamports.borrow_mut() = authority_starting_lamports
+            .checked_add(legacy_insurance_contract_account.lamports())
+            .ok_or(InsuranceContractError::Overflow)?;
+        **legacy_insurance_contract_account.lamports.borrow_mut() = 0;
+
+        Ok(())
+    }
+
+    pub fn process_suspend_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        Self::process_status_transition(program_id, accounts, InsuranceContractStatus::Suspended)
+    }
+
+    pub fn process_reinstate_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        Self::process_status_transition(program_id, accounts, InsuranceContractStatus::Active)
+    }
+
+    pub fn process_cancel_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        reason: u8,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let insurance_contract_authority = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let refund_token_account = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
+        let policy_token_account = next_account_info(accounts_iter)?;
+
+        if !insurance_contract_authority.is_signer {
+            msg!("Missing Insurance contract authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        Self::check_authority(insurance_contract_authority, &insurance_contract_data)?;
+        Self::check_status_transition(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Cancelled,
+        )?;
+
+        let (vault_address, bump_seed) =
+            find_vault_address(program_id, insurance_contract_account.key);
+        if vault_address != *vault_account.key {
+            msg!("Vault address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let vault_balance = if vault_account.owner == token_program_info.key {
+            TokenAccount::unpack(&vault_account.data.borrow())?.amount
+        } else {
+            0
+        };
+
+        let clock = Clock::from_account_info(clock_info)?;
+        let mut refund = insurance_contract_data.cancellation_refund(clock.unix_timestamp);
+        if refund > vault_balance {
+            // Claims were paid out of the premium
+            msg!("Refund is limited to the vault balance {}", vault_balance);
+            refund = vault_balance;
+        }
+
+        if refund > 0 {
+            let refund_token = TokenAccount::unpack(&refund_token_account.data.borrow())?;
+            if refund_token.mint != insurance_contract_data.premium_mint {
+                msg!("Refund token account mint mismatch");
+                return Err(InsuranceContractError::InvalidMint.into());
+            }
+            if refund_token.owner
+                != Self::load_policyholder(&insurance_contract_data, policy_token_account)?
+            {
+                msg!("Refund token account is not owned by the policyholder");
+                return Err(InsuranceContractError::BeneficiaryMismatch.into());
+            }
+
+            invoke_signed(
+                &spl_token::instruction::transfer(
+                    token_program_info.key,
+                    vault_account.key,
+                    refund_token_account.key,
+                    vault_account.key,
+                    &[],
+                    refund,
+                )?,
+                &[
+                    vault_account.clone(),
+                    refund_token_account.clone(),
+                    token_program_info.clone(),
+                ],
+                &[&[
+                    VAULT_SEED,
+                    insurance_contract_account.key.as_ref(),
+                    &[bump_seed],
+                ]],
+            )?;
+        }
+
+        insurance_contract_data.status = InsuranceContractStatus::Cancelled;
+        insurance_contract_data.cancellation_reason = reason;
+        insurance_contract_data.premium_refunded = refund;
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(ContractCancelled {
+            insurance_contract: *insurance_contract_account.key,
+            reason,
+            refund,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_pay_premium(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let premium_payer = next_account_info(accounts_iter)?;
+        let premium_payer_token_account = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let premium_mint_info = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
+        let config_info = next_account_info(accounts_iter)?;
+        let treasury_token_account = next_account_info(accounts_iter)?;
+
+        if !premium_payer.is_signer {
+            msg!("Missing premium payer signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        if insurance_contract_data.status != InsuranceContractStatus::Draft {
+            msg!(
+                "Premium can't be paid for {:?} insurance contract",
+                insurance_contract_data.status
+            );
+            return Err(InsuranceContractError::InvalidContractStatus.into());
+        }
+        if insurance_contract_data.installment_count > 0 {
+            msg!("Premium is paid in installments");
+            return Err(InsuranceContractError::InvalidPremiumSchedule.into());
+        }
+        if insurance_contract_data.premium_mint != *premium_mint_info.key {
+            msg!("Premium mint mismatch");
+            return Err(InsuranceContractError::InvalidMint.into());
+        }
+
+        let premium_paid = insurance_contract_data
+            .premium_paid
+            .checked_add(amount)
+            .ok_or(InsuranceContractError::Overflow)?;
+        if amount == 0 || premium_paid > insurance_contract_data.premium_amount {
+            msg!("Premium amount is zero or exceeds the outstanding premium");
+            return Err(InsuranceContractError::InvalidPremiumAmount.into());
+        }
+
+        let protocol_fee = Self::pay_protocol_fee(
+            program_id,
+            &mut insurance_contract_data,
+            premium_payer,
+            premium_payer_token_account,
+            premium_mint_info,
+            config_info,
+            treasury_token_account,
+            token_program_info,
+            amount,
+        )?;
+
+        Self::deposit_premium(
+            program_id,
+            premium_payer,
+            premium_payer_token_account,
+            insurance_contract_account,
+            vault_account,
+            premium_mint_info,
+            rent_info,
+            system_program_info,
+            token_program_info,
+            amount,
+        )?;
+
+        insurance_contract_data.premium_paid = premium_paid;
+        events::emit(PremiumPaid {
+            insurance_contract: *insurance_contract_account.key,
+            payer: *premium_payer.key,
+            amount,
+            protocol_fee,
+            premium_paid,
+        });
+        if premium_paid == insurance_contract_data.premium_amount {
+            Self::check_status_transition(
+                insurance_contract_data.status,
+                InsuranceContractStatus::Active,
+            )?;
+            insurance_contract_data.status = InsuranceContractStatus::Active;
+            events::emit(ContractStatusChanged {
+                insurance_contract: *insurance_contract_account.key,
+                status: insurance_contract_data.status,
+            });
+        }
+
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_submit_claim(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        amount: u64,
+        incident_time: UnixTimestamp,
+        evidence_hash: [u8; 32],
+        peril: u16,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let policyholder = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let claim_account = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        let policy_token_account = next_account_info(accounts_iter)?;
+
+        if !policyholder.is_signer {
+            msg!("Missing policyholder signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        Self::check_policyholder(policyholder, policy_token_account, &insurance_contract_data)?;
+        if !matches!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Active | InsuranceContractStatus::Expired
+        ) {
+            msg!(
+                "Claim can't be filed against {:?} insurance contract",
+                insurance_contract_data.status
+            );
+            return Err(InsuranceContractError::InvalidContractStatus.into());
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        if incident_time < insurance_contract_data.coverage_start
+            || incident_time >= insurance_contract_data.coverage_end
+            || incident_time > clock.unix_timestamp
+        {
+            msg!("Incident time is outside of the coverage period");
+            return Err(InsuranceContractError::InvalidIncidentTime.into());
+        }
+        if incident_time < insurance_contract_data.waiting_period_end() {
+            msg!("Incident happened within the waiting period");
+            return Err(InsuranceContractError::InvalidIncidentTime.into());
+        }
+        if insurance_contract_data.is_in_coverage_gap(incident_time) {
+            msg!("Incident happened while the coverage was suspended or lapsed");
+            return Err(InsuranceContractError::InvalidIncidentTime.into());
+        }
+        if amount == 0 || amount > insurance_contract_data.remaining_coverage() {
+            msg!("Claim amount is zero or exceeds the remaining coverage");
+            return Err(InsuranceContractError::InvalidClaimAmount.into());
+        }
+        if !insurance_contract_data.covers_peril(peril) {
+            msg!("Peril {} is not covered", peril);
+            return Err(InsuranceContractError::UncoveredPeril.into());
+        }
+
+        let claim_index = insurance_contract_data.claim_count;
+        let rent = Rent::from_account_info(rent_info)?;
+        Self::create_claim_account(
+            program_id,
+            policyholder,
+            insurance_contract_account,
+            claim_account,
+            claim_index,
+            &rent,
+            system_program_info,
+        )?;
+
+        if !rent.is_exempt(claim_account.lamports(), state::CLAIM_DATA_LEN) {
+            msg!("Rent exempt error for ClaimData account");
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let mut claim_data = ClaimData::unpack(&claim_account.data.borrow())?;
+        if claim_data.is_initialized() {
+            msg!("Claim data account already initialized!");
+            return Err(InsuranceContractError::AlreadyInitialized.into());
+        }
+
+        claim_data.account_type = AccountType::Claim;
+        claim_data.version = state::CLAIM_VERSION;
+        claim_data.status = ClaimStatus::Submitted;
+        claim_data.insurance_contract = *insurance_contract_account.key;
+        claim_data.claim_index = claim_index;
+        claim_data.claimant = *policyholder.key;
+        claim_data.amount = amount;
+        claim_data.incident_time = incident_time;
+        claim_data.submitted_at = clock.unix_timestamp;
+        claim_data.evidence_hash = evidence_hash;
+        claim_data.peril = peril;
+        claim_data.pack(&mut claim_account.data.borrow_mut())?;
+
+        insurance_contract_data.claim_count = claim_index
+            .checked_add(1)
+            .ok_or(InsuranceContractError::Overflow)?;
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(ClaimSubmitted {
+            insurance_contract: *insurance_contract_account.key,
+            claim: *claim_account.key,
+            claim_index,
+            amount,
+            peril,
+        });
+
+        Ok(())
+    }
+
+    /// Records the claims adjuster decision on a Submitted Claim
+    fn process_adjust_claim(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        status: ClaimStatus,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let claims_adjuster = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let claim_account = next_account_info(accounts_iter)?;
+
+        if !claims_adjuster.is_signer {
+            msg!("Missing claims adjuster signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        if insurance_contract_data.claims_adjuster != *claims_adjuster.key {
+            msg!("Claims adjuster mismatch");
+            return Err(InsuranceContractError::AuthorityMismatch.into());
+        }
+
+        let mut claim_data =
+            Self::load_claim(program_id, claim_account, insurance_contract_account.key)?;
+        if claim_data.status != ClaimStatus::Submitted {
+            msg!("Claim is already {:?}", claim_data.status);
+            return Err(InsuranceContractError::InvalidClaimStatus.into());
+        }
+        if status == ClaimStatus::Approved
+            && claim_data.amount > insurance_contract_data.remaining_coverage()
+        {
+            msg!("Claim amount exceeds the remaining coverage");
+            return Err(InsuranceContractError::InvalidClaimAmount.into());
+        }
+
+        claim_data.status = status;
+        claim_data.pack(&mut claim_account.data.borrow_mut())?;
+        if status == ClaimStatus::Approved {
+            insurance_contract_data.unpaid_claim_count = insurance_contract_data
+                .unpaid_claim_count
+                .checked_add(1)
+                .ok_or(InsuranceContractError::Overflow)?;
+            insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        }
+        events::emit(ClaimAdjusted {
+            insurance_contract: *insurance_contract_account.key,
+            claim: *claim_account.key,
+            status,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_pay_claim(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let claims_adjuster = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let claim_account = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let beneficiary_token_account = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
+        let policy_token_account = next_account_info(accounts_iter)?;
+
+        if !claims_adjuster.is_signer {
+            msg!("Missing claims adjuster signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(ProgramError::IncorrectProgramId);
         }
 
-        insurance_contract_data.is_closed = true;
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        if insurance_contract_data.claims_adjuster != *claims_adjuster.key {
+            msg!("Claims adjuster mismatch");
+            return Err(InsuranceContractError::AuthorityMismatch.into());
+        }
+
+        let mut claim_data =
+            Self::load_claim(program_id, claim_account, insurance_contract_account.key)?;
+        if claim_data.status != ClaimStatus::Approved {
+            msg!("Claim can't be paid in {:?} status", claim_data.status);
+            return Err(InsuranceContractError::InvalidClaimStatus.into());
+        }
+        let payout = insurance_contract_data.claim_payout(
+            claim_data.amount,
+            claim_data.peril,
+            claim_data.incident_time,
+        );
+
+        let (vault_address, bump_seed) =
+            find_vault_address(program_id, insurance_contract_account.key);
+        if vault_address != *vault_account.key {
+            msg!("Vault address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let vault_balance = if vault_account.owner == token_program_info.key {
+            TokenAccount::unpack(&vault_account.data.borrow())?.amount
+        } else {
+            0
+        };
+        if vault_balance < payout {
+            msg!(
+                "Vault balance {} can't cover claim payout {}",
+                vault_balance,
+                payout
+            );
+            return Err(InsuranceContractError::InsufficientVaultFunds.into());
+        }
+
+        let beneficiary = TokenAccount::unpack(&beneficiary_token_account.data.borrow())?;
+        if beneficiary.mint != insurance_contract_data.premium_mint {
+            msg!("Beneficiary token account mint mismatch");
+            return Err(InsuranceContractError::InvalidMint.into());
+        }
+        if insurance_contract_data.is_tokenized() {
+            let policyholder =
+                Self::load_policyholder(&insurance_contract_data, policy_token_account)?;
+            if beneficiary.owner != policyholder {
+                msg!("Beneficiary token account is not owned by the policy token holder");
+                return Err(InsuranceContractError::BeneficiaryMismatch.into());
+            }
+        } else if beneficiary.owner != claim_data.claimant {
+            msg!("Beneficiary token account is not owned by the claimant");
+            return Err(InsuranceContractError::BeneficiaryMismatch.into());
+        }
+
+        invoke_signed(
+            &spl_token::instruction::transfer(
+                token_program_info.key,
+                vault_account.key,
+                beneficiary_token_account.key,
+                vault_account.key,
+                &[],
+                payout,
+            )?,
+            &[
+                vault_account.clone(),
+                beneficiary_token_account.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[
+                VAULT_SEED,
+                insurance_contract_account.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )?;
+
+        claim_data.status = ClaimStatus::Paid;
+        claim_data.payout = payout;
+        claim_data.pack(&mut claim_account.data.borrow_mut())?;
+
+        insurance_contract_data.record_claim_payout(
+            payout,
+            claim_data.peril,
+            claim_data.incident_time,
+        )?;
+        // Claims approved before the count was stored are not counted
+        insurance_contract_data.unpaid_claim_count =
+            insurance_contract_data.unpaid_claim_count.saturating_sub(1);
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(ClaimPaid {
+            insurance_contract: *insurance_contract_account.key,
+            claim: *claim_account.key,
+            payout,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_reclaim_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let insurance_contract_authority = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let destination_account = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
+        let premium_token_account = next_account_info(accounts_iter)?;
+
+        if !insurance_contract_authority.is_signer {
+            msg!("Missing Insurance contract authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if destination_account.key == insurance_contract_account.key
+            || destination_account.key == vault_account.key
+        {
+            msg!("Reclaimed lamports can't be sent to the closed accounts");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        Self::check_authority(insurance_contract_authority, &insurance_contract_data)?;
+        if insurance_contract_data.status != InsuranceContractStatus::Closed {
+            msg!("Only closed insurance contract can be reclaimed");
+            return Err(InsuranceContractError::InvalidContractStatus.into());
+        }
+        if insurance_contract_data.unpaid_claim_count > 0 {
+            msg!(
+                "{} approved claims are not paid yet",
+                insurance_contract_data.unpaid_claim_count
+            );
+            return Err(InsuranceContractError::VaultNotEmpty.into());
+        }
+
+        let (vault_address, bump_seed) =
+            find_vault_address(program_id, insurance_contract_account.key);
+        if vault_address != *vault_account.key {
+            msg!("Vault address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if vault_account.owner == token_program_info.key {
+            let vault_amount = TokenAccount::unpack(&vault_account.data.borrow())?.amount;
+            if vault_amount > 0 {
+                if premium_token_account.key == vault_account.key {
+                    msg!("Premium can't be sent to the vault itself");
+                    return Err(ProgramError::InvalidArgument);
+                }
+                invoke_signed(
+                    &spl_token::instruction::transfer(
+                        token_program_info.key,
+                        vault_account.key,
+                        premium_token_account.key,
+                        vault_account.key,
+                        &[],
+                        vault_amount,
+                    )?,
+                    &[
+                        vault_account.clone(),
+                        premium_token_account.clone(),
+                        token_program_info.clone(),
+                    ],
+                    &[&[
+                        VAULT_SEED,
+                        insurance_contract_account.key.as_ref(),
+                        &[bump_seed],
+                    ]],
+                )?;
+            }
+            invoke_signed(
+                &spl_token::instruction::close_account(
+                    token_program_info.key,
+                    vault_account.key,
+                    destination_account.key,
+                    vault_account.key,
+                    &[],
+                )?,
+                &[
+                    vault_account.clone(),
+                    destination_account.clone(),
+                    token_program_info.clone(),
+                ],
+                &[&[
+                    VAULT_SEED,
+                    insurance_contract_account.key.as_ref(),
+                    &[bump_seed],
+                ]],
+            )?;
+        }
+
+        InsuranceContractData::pack_reclaimed(&mut insurance_contract_account.data.borrow_mut());
+        let destination_starting_lamports = destination_account.lamports();
+        **destination_account.lamports.borrow_mut() = destination_starting_lamports
+            .checked_add(insurance_contract_account.lamports())
+            .ok_or(InsuranceContractError::Overflow)?;
+        **insurance_contract_account.lamports.borrow_mut() = 0;
+        events::emit(ContractReclaimed {
+            insurance_contract: *insurance_contract_account.key,
+            destination: *destination_account.key,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_expire_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let keeper = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+
+        if !keeper.is_signer {
+            msg!("Missing keeper signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if keeper.key == insurance_contract_account.key {
+            msg!("Keeper can't be the insurance contract account");
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        let clock = Clock::from_account_info(clock_info)?;
+        if clock.unix_timestamp < insurance_contract_data.coverage_end {
+            msg!("Coverage period is not over yet");
+            return Err(InsuranceContractError::CoverageNotEnded.into());
+        }
+        Self::check_status_transition(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Expired,
+        )?;
+        insurance_contract_data.status = InsuranceContractStatus::Expired;
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(ContractStatusChanged {
+            insurance_contract: *insurance_contract_account.key,
+            status: insurance_contract_data.status,
+        });
+
+        // The reward never touches the rent exempt balance
+        let rent = Rent::from_account_info(rent_info)?;
+        let keeper_reward = insurance_contract_account
+            .lamports()
+            .saturating_sub(rent.minimum_balance(insurance_contract_account.data_len()))
+            .min(insurance_contract_data.keeper_reward);
+        if keeper_reward > 0 {
+            let keeper_starting_lamports = keeper.lamports();
+            **keeper.lamports.borrow_mut() = keeper_starting_lamports
+                .checked_add(keeper_reward)
+                .ok_or(InsuranceContractError::Overflow)?;
+            **insurance_contract_account.lamports.borrow_mut() -= keeper_reward;
+        }
+
+        Ok(())
+    }
+
+    pub fn process_renew_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        insurance_contract_id: u32,
+        coverage_end: UnixTimestamp,
+        premium_amount: u64,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let insurance_contract_authority = next_account_info(accounts_iter)?;
+        let premium_payer = next_account_info(accounts_iter)?;
+        let premium_payer_token_account = next_account_info(accounts_iter)?;
+        let predecessor_account = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let premium_mint_info = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
+        let predecessor_policy_token_account = next_account_info(accounts_iter)?;
+        let policy_mint_info = next_account_info(accounts_iter)?;
+        let policy_token_account = next_account_info(accounts_iter)?;
+        let config_info = next_account_info(accounts_iter)?;
+        let treasury_token_account = next_account_info(accounts_iter)?;
+
+        if !insurance_contract_authority.is_signer {
+            msg!("Missing Insurance contract authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if !premium_payer.is_signer {
+            msg!("Missing premium payer signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut predecessor_data = Self::load_insurance_contract(program_id, predecessor_account)?;
+        Self::check_authority(insurance_contract_authority, &predecessor_data)?;
+        if !matches!(
+            predecessor_data.status,
+            InsuranceContractStatus::Active | InsuranceContractStatus::Expired
+        ) {
+            msg!(
+                "{:?} insurance contract can't be renewed",
+                predecessor_data.status
+            );
+            return Err(InsuranceContractError::InvalidContractStatus.into());
+        }
+        if predecessor_data.successor != Pubkey::default() {
+            msg!("Insurance contract is already renewed");
+            return Err(InsuranceContractError::AlreadyRenewed.into());
+        }
+        if predecessor_data.premium_mint != *premium_mint_info.key {
+            msg!("Premium mint mismatch");
+            return Err(InsuranceContractError::InvalidMint.into());
+        }
+        let policyholder =
+            Self::load_policyholder(&predecessor_data, predecessor_policy_token_account)?;
+
+        // The successor continues the predecessor coverage with the same terms and
+        // without a new waiting period, the renewal premium is paid at once. A late
+        // renewal starts now, the time between the two coverages stays uncovered.
+        let clock = Clock::from_account_info(clock_info)?;
+        let terms = PolicyTerms {
+            coverage_start: predecessor_data.coverage_end.max(clock.unix_timestamp),
+            coverage_end,
+            premium_amount,
+            installment_count: 0,
+            installment_amount: 0,
+            installment_interval: 0,
+            grace_period: 0,
+            waiting_period: 0,
+            ..predecessor_data.terms()
+        };
+        Self::check_policy_terms(&terms, &clock)?;
+
+        // A successor of a contract issued from a product is a new policy of the
+        // product, it can't be issued while the product is retired or paused
+        if predecessor_data.product != Pubkey::default() {
+            let product_account = next_account_info(accounts_iter)?;
+            if *product_account.key != predecessor_data.product {
+                msg!("Insurance product account mismatch");
+                return Err(ProgramError::InvalidArgument);
+            }
+            let mut product_data = Self::load_product(program_id, product_account)?;
+            Self::check_product_issuable(&product_data)?;
+            product_data.policy_count = product_data
+                .policy_count
+                .checked_add(1)
+                .ok_or(InsuranceContractError::Overflow)?;
+            product_data.pack(&mut product_account.data.borrow_mut())?;
+        }
+
+        let rent = Rent::from_account_info(rent_info)?;
+        Self::create_insurance_contract_account(
+            program_id,
+            insurance_contract_authority,
+            insurance_contract_account,
+            insurance_contract_id,
+            &rent,
+            system_program_info,
+        )?;
+
+        if !rent.is_exempt(
+            insurance_contract_account.lamports(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+        ) {
+            msg!("Rent exempt error for InsuranceContractData account");
+            return Err(ProgramError::AccountNotRentExempt);
+        }
+
+        let mut insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_account.data.borrow())?;
+        if insurance_contract_data.is_initialized() {
+            msg!("Insurance data account already initialized!");
+            return Err(InsuranceContractError::AlreadyInitialized.into());
+        }
+
+        insurance_contract_data.account_type = AccountType::InsuranceContract;
+        insurance_contract_data.version = state::INSURANCE_CONTRACT_VERSION;
+        insurance_contract_data.status = InsuranceContractStatus::Active;
+        insurance_contract_data.insurance_contract_id = insurance_contract_id;
+        insurance_contract_data.authority = *insurance_contract_authority.key;
+        insurance_contract_data.set_terms(&terms);
+        insurance_contract_data.policy_mint = *policy_mint_info.key;
+        insurance_contract_data.predecessor = *predecessor_account.key;
+        insurance_contract_data.product = predecessor_data.product;
+        insurance_contract_data.claim_free_terms = predecessor_data.renewal_claim_free_terms();
+        insurance_contract_data.premium_amount =
+            insurance_contract_data.discounted_premium(premium_amount);
+        insurance_contract_data.premium_paid = insurance_contract_data.premium_amount;
+
+        if insurance_contract_data.premium_paid > 0 {
+            let premium_paid = insurance_contract_data.premium_paid;
+            let protocol_fee = Self::pay_protocol_fee(
+                program_id,
+                &mut insurance_contract_data,
+                premium_payer,
+                premium_payer_token_account,
+                premium_mint_info,
+                config_info,
+                treasury_token_account,
+                token_program_info,
+                premium_paid,
+            )?;
+            Self::deposit_premium(
+                program_id,
+                premium_payer,
+                premium_payer_token_account,
+                insurance_contract_account,
+                vault_account,
+                premium_mint_info,
+                rent_info,
+                system_program_info,
+                token_program_info,
+                insurance_contract_data.premium_paid,
+            )?;
+            events::emit(PremiumPaid {
+                insurance_contract: *insurance_contract_account.key,
+                payer: *premium_payer.key,
+                amount: premium_paid,
+                protocol_fee,
+                premium_paid,
+            });
+        }
+
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+
+        predecessor_data.successor = *insurance_contract_account.key;
+        predecessor_data.pack(&mut predecessor_account.data.borrow_mut())?;
+        events::emit(ContractRenewed {
+            predecessor: *predecessor_account.key,
+            insurance_contract: *insurance_contract_account.key,
+        });
+
+        Self::mint_policy_token(
+            program_id,
+            insurance_contract_authority,
+            insurance_contract_account,
+            policy_mint_info,
+            policy_token_account,
+            &policyholder,
+            terms.assignment_consent_required,
+            rent_info,
+            &rent,
+            system_program_info,
+            token_program_info,
+        )?;
+
+        Self::reserve_keeper_reward(
+            insurance_contract_authority,
+            insurance_contract_account,
+            terms.keeper_reward,
+            system_program_info,
+        )
+    }
+
+    pub fn process_pay_installment(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let premium_payer = next_account_info(accounts_iter)?;
+        let premium_payer_token_account = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let vault_account = next_account_info(accounts_iter)?;
+        let premium_mint_info = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        let token_program_info = next_account_info(accounts_iter)?;
+        let config_info = next_account_info(accounts_iter)?;
+        let treasury_token_account = next_account_info(accounts_iter)?;
+
+        if !premium_payer.is_signer {
+            msg!("Missing premium payer signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if *token_program_info.key != spl_token::id() {
+            msg!("Invalid token program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        if !matches!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Draft
+                | InsuranceContractStatus::Active
+                | InsuranceContractStatus::Suspended
+                | InsuranceContractStatus::Lapsed
+        ) {
+            msg!(
+                "Installment can't be paid for {:?} insurance contract",
+                insurance_contract_data.status
+            );
+            return Err(InsuranceContractError::InvalidContractStatus.into());
+        }
+        if insurance_contract_data.installment_count == 0 {
+            msg!("Premium is not paid in installments");
+            return Err(InsuranceContractError::InvalidPremiumSchedule.into());
+        }
+        if insurance_contract_data.installments_paid >= insurance_contract_data.installment_count {
+            msg!("All installments are already paid");
+            return Err(InsuranceContractError::InvalidPremiumAmount.into());
+        }
+        if insurance_contract_data.premium_mint != *premium_mint_info.key {
+            msg!("Premium mint mismatch");
+            return Err(InsuranceContractError::InvalidMint.into());
+        }
+
+        let amount = insurance_contract_data.installment_amount;
+        let protocol_fee = Self::pay_protocol_fee(
+            program_id,
+            &mut insurance_contract_data,
+            premium_payer,
+            premium_payer_token_account,
+            premium_mint_info,
+            config_info,
+            treasury_token_account,
+            token_program_info,
+            amount,
+        )?;
+        Self::deposit_premium(
+            program_id,
+            premium_payer,
+            premium_payer_token_account,
+            insurance_contract_account,
+            vault_account,
+            premium_mint_info,
+            rent_info,
+            system_program_info,
+            token_program_info,
+            amount,
+        )?;
+
+        insurance_contract_data.premium_paid = insurance_contract_data
+            .premium_paid
+            .checked_add(amount)
+            .ok_or(InsuranceContractError::Overflow)?;
+        insurance_contract_data.installments_paid += 1;
+        events::emit(PremiumPaid {
+            insurance_contract: *insurance_contract_account.key,
+            payer: *premium_payer.key,
+            amount,
+            protocol_fee,
+            premium_paid: insurance_contract_data.premium_paid,
+        });
+
+        let clock = Clock::from_account_info(clock_info)?;
+        let reinstated = match insurance_contract_data.status {
+            InsuranceContractStatus::Draft => true,
+            InsuranceContractStatus::Lapsed => {
+                !insurance_contract_data.is_installment_overdue(clock.unix_timestamp)
+            }
+            _ => false,
+        };
+        if reinstated {
+            Self::check_status_transition(
+                insurance_contract_data.status,
+                InsuranceContractStatus::Active,
+            )?;
+            insurance_contract_data.status = InsuranceContractStatus::Active;
+            insurance_contract_data.close_coverage_gap(clock.unix_timestamp);
+            events::emit(ContractStatusChanged {
+                insurance_contract: *insurance_contract_account.key,
+                status: insurance_contract_data.status,
+            });
+        }
+
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_lapse_insurance_contract(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        if insurance_contract_data.status != InsuranceContractStatus::Active {
+            msg!(
+                "{:?} insurance contract can't lapse",
+                insurance_contract_data.status
+            );
+            return Err(InsuranceContractError::InvalidContractStatus.into());
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        if !insurance_contract_data.is_installment_overdue(clock.unix_timestamp) {
+            msg!("No installment is overdue after the grace period");
+            return Err(InsuranceContractError::GracePeriodNotOver.into());
+        }
+
+        Self::check_status_transition(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Lapsed,
+        )?;
+        insurance_contract_data.status = InsuranceContractStatus::Lapsed;
+        insurance_contract_data.open_coverage_gap(clock.unix_timestamp);
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(ContractStatusChanged {
+            insurance_contract: *insurance_contract_account.key,
+            status: insurance_contract_data.status,
+        });
+
+        Ok(())
+    }
+
+    /// Records or withdraws the authority handover proposed by the current authority
+    fn process_set_pending_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        pending_authority: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let insurance_contract_authority = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+
+        if !insurance_contract_authority.is_signer {
+            msg!("Missing Insurance contract authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        Self::check_authority(insurance_contract_authority, &insurance_contract_data)?;
+        if pending_authority == Pubkey::default()
+            && insurance_contract_data.pending_authority == Pubkey::default()
+        {
+            msg!("No authority handover is pending");
+            return Err(InsuranceContractError::NoPendingAuthority.into());
+        }
+
+        insurance_contract_data.pending_authority = pending_authority;
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_accept_authority(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let new_authority = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+
+        if !new_authority.is_signer {
+            msg!("Missing new authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        if insurance_contract_data.pending_authority == Pubkey::default() {
+            msg!("No authority handover is pending");
+            return Err(InsuranceContractError::NoPendingAuthority.into());
+        }
+        if insurance_contract_data.pending_authority != *new_authority.key {
+            msg!("Authority handover is proposed to another account");
+            return Err(InsuranceContractError::AuthorityMismatch.into());
+        }
+
+        insurance_contract_data.authority = *new_authority.key;
+        insurance_contract_data.pending_authority = Pubkey::default();
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(AuthorityChanged {
+            insurance_contract: *insurance_contract_account.key,
+            authority: *new_authority.key,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_assign_policy(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        new_holder: Pubkey,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let policyholder = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+
+        if !policyholder.is_signer {
+            msg!("Missing policyholder signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        let policy_token_accounts = if insurance_contract_data.is_tokenized() {
+            Some((
+                next_account_info(accounts_iter)?,
+                next_account_info(accounts_iter)?,
+                next_account_info(accounts_iter)?,
+                next_account_info(accounts_iter)?,
+            ))
+        } else {
+            None
+        };
+        let previous_holder = match policy_token_accounts {
+            Some((policy_token_account, ..)) => {
+                Self::load_policyholder(&insurance_contract_data, policy_token_account)?
+            }
+            None => insurance_contract_data.policyholder(),
+        };
+        if previous_holder != *policyholder.key {
+            msg!("Policyholder mismatch");
+            return Err(InsuranceContractError::HolderMismatch.into());
+        }
+        if insurance_contract_data.assignment_consent_required {
+            let insurance_contract_authority = next_account_info(accounts_iter)?;
+            if !insurance_contract_authority.is_signer {
+                msg!("Missing Insurance contract authority consent");
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            Self::check_authority(insurance_contract_authority, &insurance_contract_data)?;
+        }
+        if matches!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Cancelled | InsuranceContractStatus::Closed
+        ) {
+            msg!(
+                "{:?} insurance contract can't be assigned",
+                insurance_contract_data.status
+            );
+            return Err(InsuranceContractError::InvalidContractStatus.into());
+        }
+        if new_holder == Pubkey::default() || new_holder == *policyholder.key {
+            msg!("Policy must be assigned to another holder");
+            return Err(InsuranceContractError::InvalidAssignment.into());
+        }
+
+        match policy_token_accounts {
+            Some((
+                policy_token_account,
+                new_holder_token_account,
+                policy_mint_info,
+                token_program_info,
+            )) => {
+                if *token_program_info.key != spl_token::id() {
+                    msg!("Invalid token program");
+                    return Err(ProgramError::IncorrectProgramId);
+                }
+                let new_holder_token = Self::load_token_account(new_holder_token_account)?;
+                if new_holder_token.mint != insurance_contract_data.policy_mint
+                    || new_holder_token.owner != new_holder
+                {
+                    msg!("Policy token must be moved to a token account of the new holder");
+                    return Err(InsuranceContractError::InvalidAssignment.into());
+                }
+                Self::transfer_policy_token(
+                    program_id,
+                    insurance_contract_account,
+                    policy_mint_info,
+                    policy_token_account,
+                    new_holder_token_account,
+                    policyholder,
+                    token_program_info,
+                    insurance_contract_data.assignment_consent_required,
+                )?;
+            }
+            None => insurance_contract_data.holder = new_holder,
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        insurance_contract_data.record_assignment(previous_holder, clock.unix_timestamp)?;
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(PolicyAssigned {
+            insurance_contract: *insurance_contract_account.key,
+            previous_holder,
+            new_holder,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_amend_document(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        document_hash: [u8; 32],
+        document_uri: DocumentUri,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let insurance_contract_authority = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+
+        if !insurance_contract_authority.is_signer {
+            msg!("Missing Insurance contract authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        Self::check_authority(insurance_contract_authority, &insurance_contract_data)?;
+        if insurance_contract_data.status == InsuranceContractStatus::Closed {
+            msg!("Insurance contract already closed!");
+            return Err(InsuranceContractError::AlreadyClosed.into());
+        }
+        if document_hash == [0; 32] || !document_uri.is_valid() {
+            msg!("Invalid policy document hash or URI");
+            return Err(InsuranceContractError::InvalidDocument.into());
+        }
+
+        let previous_document_hash = insurance_contract_data.document_hash;
+        insurance_contract_data.amend_document(document_hash, document_uri)?;
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        events::emit(DocumentAmended {
+            insurance_contract: *insurance_contract_account.key,
+            document_hash,
+            previous_document_hash,
+        });
+
+        Ok(())
+    }
+
+    pub fn process_create_product(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        product_id: u32,
+        terms: &ProductTerms,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let product_authority = next_account_info(accounts_iter)?;
+        let product_account = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+
+        if !product_authority.is_signer {
+            msg!("Missing product authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        Self::check_product_terms(terms, &clock)?;
+
+        let (product_address, bump_seed) =
+            find_insurance_product_address(program_id, product_authority.key, product_id);
+        if product_address != *product_account.key {
+            msg!("Insurance product address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let rent = Rent::from_account_info(rent_info)?;
+        if product_account.owner != program_id {
+            Self::create_pda_account(
+                product_authority,
+                &rent,
+                state::INSURANCE_PRODUCT_DATA_LEN,
+                program_id,
+                system_program_info,
+                product_account,
+                &[
+                    INSURANCE_PRODUCT_SEED,
+                    product_authority.key.as_ref(),
+                    &product_id.to_le_bytes(),
+                    &[bump_seed],
+                ],
+            )?;
+        }
+
+        let mut product_data = InsuranceProductData::unpack(&product_account.data.borrow())?;
+        if product_data.is_initialized() {
+            msg!("Insurance product account already initialized!");
+            return Err(InsuranceContractError::AlreadyInitialized.into());
+        }
+
+        product_data.account_type = AccountType::Product;
+        product_data.version = state::INSURANCE_PRODUCT_VERSION;
+        product_data.status = ProductStatus::Active;
+        product_data.product_id = product_id;
+        product_data.authority = *product_authority.key;
+        product_data.terms = *terms;
+
+        product_data.pack(&mut product_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_update_product(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        terms: &ProductTerms,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let product_authority = next_account_info(accounts_iter)?;
+        let product_account = next_account_info(accounts_iter)?;
+        let clock_info = next_account_info(accounts_iter)?;
+
+        if !product_authority.is_signer {
+            msg!("Missing product authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut product_data = Self::load_product(program_id, product_account)?;
+        Self::check_product_authority(product_authority, &product_data)?;
+        if product_data.status == ProductStatus::Retired {
+            msg!("Retired insurance product can't be updated");
+            return Err(InsuranceContractError::ProductRetired.into());
+        }
+
+        let clock = Clock::from_account_info(clock_info)?;
+        Self::check_product_terms(terms, &clock)?;
+        product_data.terms = *terms;
+
+        product_data.pack(&mut product_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_retire_product(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let product_authority = next_account_info(accounts_iter)?;
+        let product_account = next_account_info(accounts_iter)?;
+
+        if !product_authority.is_signer {
+            msg!("Missing product authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut product_data = Self::load_product(program_id, product_account)?;
+        Self::check_product_authority(product_authority, &product_data)?;
+        if product_data.status == ProductStatus::Retired {
+            msg!("Insurance product is already retired");
+            return Err(InsuranceContractError::ProductRetired.into());
+        }
+        product_data.status = ProductStatus::Retired;
+
+        product_data.pack(&mut product_account.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_initialize_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        settings: &ConfigSettings,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let admin = next_account_info(accounts_iter)?;
+        let config_info = next_account_info(accounts_iter)?;
+        let rent_info = next_account_info(accounts_iter)?;
+        let system_program_info = next_account_info(accounts_iter)?;
+        let program_data_info = next_account_info(accounts_iter)?;
+
+        if !admin.is_signer {
+            msg!("Missing config admin signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        Self::check_upgrade_authority(program_id, admin, program_data_info)?;
+        Self::check_config_settings(settings)?;
+
+        let (config_address, bump_seed) = find_config_address(program_id);
+        if config_address != *config_info.key {
+            msg!("Config address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        let rent = Rent::from_account_info(rent_info)?;
+        if config_info.owner != program_id {
+            Self::create_pda_account(
+                admin,
+                &rent,
+                state::CONFIG_DATA_LEN,
+                program_id,
+                system_program_info,
+                config_info,
+                &[CONFIG_SEED, &[bump_seed]],
+            )?;
+        }
+
+        let mut config_data = ConfigData::unpack(&config_info.data.borrow())?;
+        if config_data.is_initialized() {
+            msg!("Config account already initialized!");
+            return Err(InsuranceContractError::AlreadyInitialized.into());
+        }
+
+        config_data.account_type = AccountType::Config;
+        config_data.version = state::CONFIG_VERSION;
+        config_data.admin = *admin.key;
+        config_data.settings = *settings;
+
+        config_data.pack(&mut config_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    pub fn process_update_config(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        settings: &ConfigSettings,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let admin = next_account_info(accounts_iter)?;
+        let config_info = next_account_info(accounts_iter)?;
+
+        if !admin.is_signer {
+            msg!("Missing config admin signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config_data = match Self::load_config(program_id, config_info)? {
+            Some(config_data) => config_data,
+            None => {
+                msg!("Config account is not initialized!");
+                return Err(InsuranceContractError::NotInitialized.into());
+            }
+        };
+        if config_data.admin != *admin.key {
+            msg!("Config admin mismatch");
+            return Err(InsuranceContractError::AdminMismatch.into());
+        }
+        Self::check_config_settings(settings)?;
+        config_data.settings = *settings;
+
+        config_data.pack(&mut config_info.data.borrow_mut())?;
+
+        Ok(())
+    }
+
+    /// Pauses or unpauses the whole program or the given product on behalf of the guardian
+    fn process_set_paused(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        paused: bool,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let guardian = next_account_info(accounts_iter)?;
+        let config_info = next_account_info(accounts_iter)?;
+        let product_account = accounts_iter.next();
+
+        if !guardian.is_signer {
+            msg!("Missing guardian signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut config_data = match Self::load_config(program_id, config_info)? {
+            Some(config_data) => config_data,
+            None => {
+                msg!("Config account is not initialized!");
+                return Err(InsuranceContractError::NotInitialized.into());
+            }
+        };
+        if config_data.settings.guardian != *guardian.key {
+            msg!("Config guardian mismatch");
+            return Err(InsuranceContractError::GuardianMismatch.into());
+        }
+
+        let account = match product_account {
+            Some(product_account) => {
+                let mut product_data = Self::load_product(program_id, product_account)?;
+                product_data.paused = paused;
+                product_data.pack(&mut product_account.data.borrow_mut())?;
+                product_account
+            }
+            None => {
+                config_data.paused = paused;
+                config_data.pack(&mut config_info.data.borrow_mut())?;
+                config_info
+            }
+        };
+        events::emit(PauseChanged {
+            account: *account.key,
+            paused,
+        });
+
+        Ok(())
+    }
+
+    /// Moves the InsuranceContract to a new status on behalf of its authority
+    fn process_status_transition(
+        program_id: &Pubkey,
+        accounts: &[AccountInfo],
+        status: InsuranceContractStatus,
+    ) -> ProgramResult {
+        let accounts_iter = &mut accounts.iter();
+        let insurance_contract_authority = next_account_info(accounts_iter)?;
+        let insurance_contract_account = next_account_info(accounts_iter)?;
+
+        if !insurance_contract_authority.is_signer {
+            msg!("Missing Insurance contract authority signature");
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+
+        let mut insurance_contract_data =
+            Self::load_insurance_contract(program_id, insurance_contract_account)?;
+        Self::check_authority(insurance_contract_authority, &insurance_contract_data)?;
+
+        if status == InsuranceContractStatus::Active
+            && insurance_contract_data.status == InsuranceContractStatus::Draft
+        {
+            msg!("Draft insurance contract can't be reinstated");
+            return Err(InsuranceContractError::InvalidStatusTransition.into());
+        }
+        Self::check_status_transition(insurance_contract_data.status, status)?;
+        match status {
+            InsuranceContractStatus::Suspended => {
+                let clock = Clock::from_account_info(next_account_info(accounts_iter)?)?;
+                insurance_contract_data.open_coverage_gap(clock.unix_timestamp);
+            }
+            InsuranceContractStatus::Active => {
+                let clock = Clock::from_account_info(next_account_info(accounts_iter)?)?;
+                insurance_contract_data.close_coverage_gap(clock.unix_timestamp);
+            }
+            _ => {}
+        }
+        insurance_contract_data.status = status;
+
+        insurance_contract_data.pack(&mut insurance_contract_account.data.borrow_mut())?;
+        let insurance_contract = *insurance_contract_account.key;
+        if status == InsuranceContractStatus::Closed {
+            events::emit(ContractClosed { insurance_contract });
+        } else {
+            events::emit(ContractStatusChanged {
+                insurance_contract,
+                status,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the InsuranceContract lifecycle allows to move from one status to another
+    pub fn check_status_transition(
+        from: InsuranceContractStatus,
+        to: InsuranceContractStatus,
+    ) -> ProgramResult {
+        use InsuranceContractStatus::*;
+
+        if from == Closed {
+            msg!("Insurance contract already closed!");
+            return Err(InsuranceContractError::AlreadyClosed.into());
+        }
+        if from == to {
+            msg!("Insurance contract already has {:?} status", to);
+            return Err(InsuranceContractError::StatusUnchanged.into());
+        }
+
+        let allowed = match from {
+            Draft => matches!(to, Active | Cancelled | Closed),
+            Active => matches!(to, Suspended | Lapsed | Expired | Cancelled | Closed),
+            Suspended => matches!(to, Active | Expired | Cancelled | Closed),
+            Lapsed => matches!(to, Active | Expired | Cancelled | Closed),
+            Expired | Cancelled => to == Closed,
+            Closed => false,
+        };
+        if !allowed {
+            msg!("Insurance contract can't move from {:?} to {:?}", from, to);
+            return Err(InsuranceContractError::InvalidStatusTransition.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that the signer is the upgrade authority stored in the program data account,
+    /// which keeps anyone else from initializing the config once the program is deployed
+    fn check_upgrade_authority(
+        program_id: &Pubkey,
+        upgrade_authority: &AccountInfo,
+        program_data_info: &AccountInfo,
+    ) -> ProgramResult {
+        let (program_data_address, _) = find_program_data_address(program_id);
+        if program_data_address != *program_data_info.key
+            || *program_data_info.owner != bpf_loader_upgradeable::id()
+        {
+            msg!("Invalid program data account");
+            return Err(ProgramError::InvalidArgument);
+        }
+        match bincode::deserialize(&program_data_info.data.borrow()) {
+            Ok(UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address: Some(upgrade_authority_address),
+                ..
+            }) if upgrade_authority_address == *upgrade_authority.key => Ok(()),
+            Ok(_) => {
+                msg!("Config admin is not the program upgrade authority");
+                Err(InsuranceContractError::AdminMismatch.into())
+            }
+            Err(_) => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Checks the program config settings
+    fn check_config_settings(settings: &ConfigSettings) -> ProgramResult {
+        if settings.protocol_fee_bps > state::MAX_PROTOCOL_FEE_BPS
+            || (settings.protocol_fee_bps > 0 && settings.treasury == Pubkey::default())
+        {
+            msg!("Invalid protocol fee");
+            return Err(InsuranceContractError::InvalidProtocolFee.into());
+        }
+        Ok(())
+    }
+
+    /// Checks the terms of an InsuranceProduct as if a contract was issued from it now
+    fn check_product_terms(terms: &ProductTerms, clock: &Clock) -> ProgramResult {
+        Self::check_policy_terms(&terms.policy_terms(clock.unix_timestamp), clock)
+    }
+
+    /// Checks the terms of a new InsuranceContract against the current time
+    fn check_policy_terms(terms: &PolicyTerms, clock: &Clock) -> ProgramResult {
+        if terms.coverage_start >= terms.coverage_end || terms.coverage_end <= clock.unix_timestamp
+        {
+            msg!("Invalid coverage period");
+            return Err(InsuranceContractError::InvalidCoveragePeriod.into());
+        }
+        if terms.waiting_period < 0
+            || terms.coverage_start.saturating_add(terms.waiting_period) >= terms.coverage_end
+        {
+            msg!("Invalid waiting period");
+            return Err(InsuranceContractError::InvalidCoveragePeriod.into());
+        }
+        if terms.sum_insured == 0 {
+            msg!("Invalid sum insured");
+            return Err(InsuranceContractError::InvalidSumInsured.into());
+        }
+        if terms.cancellation_penalty_bps > state::BASIS_POINTS {
+            msg!("Invalid cancellation penalty");
+            return Err(InsuranceContractError::InvalidCancellationPenalty.into());
+        }
+        if terms.deductible >= terms.sum_insured
+            || terms.per_occurrence_limit > terms.sum_insured
+            || terms.annual_aggregate_limit > terms.sum_insured
+        {
+            msg!("Invalid deductible or claim limits");
+            return Err(InsuranceContractError::InvalidClaimLimits.into());
+        }
+        if terms.annual_aggregate_limit > 0
+            && terms.coverage_end.saturating_sub(terms.coverage_start)
+                > state::SECONDS_PER_POLICY_YEAR * state::MAX_AGGREGATE_YEARS as UnixTimestamp
+        {
+            msg!(
+                "Annual aggregate limit can't apply to more than {} policy years",
+                state::MAX_AGGREGATE_YEARS
+            );
+            return Err(InsuranceContractError::InvalidClaimLimits.into());
+        }
+        for (index, peril) in terms.perils.iter().enumerate() {
+            let is_unused = peril.code == state::NO_PERIL;
+            if (is_unused && peril.sub_limit > 0)
+                || peril.sub_limit > terms.sum_insured
+                || (!is_unused && terms.perils[..index].iter().any(|p| p.code == peril.code))
+            {
+                msg!("Invalid peril {} sub-limit", peril.code);
+                return Err(InsuranceContractError::InvalidClaimLimits.into());
+            }
+        }
+        if terms.installment_count > 0 {
+            // Installments add up to the premium and are all due within the coverage period
+            let last_installment_due = terms
+                .installment_interval
+                .checked_mul(terms.installment_count as UnixTimestamp - 1)
+                .and_then(|offset| terms.coverage_start.checked_add(offset));
+            if terms.installment_interval <= 0
+                || terms.grace_period < 0
+                || terms
+                    .installment_amount
+                    .checked_mul(terms.installment_count as u64)
+                    != Some(terms.premium_amount)
+                || !matches!(last_installment_due, Some(due) if due < terms.coverage_end)
+            {
+                msg!("Invalid premium installment schedule");
+                return Err(InsuranceContractError::InvalidPremiumSchedule.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes an initialized InsuranceContract account owned by the program
+    fn load_insurance_contract(
+        program_id: &Pubkey,
+        insurance_contract_account: &AccountInfo,
+    ) -> Result<InsuranceContractData, ProgramError> {
+        if insurance_contract_account.owner != program_id {
+            msg!("Invalid owner for InsuranceContractDccount data account");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_account.data.borrow())?;
+        if !insurance_contract_data.is_initialized() {
+            msg!("Insurance data account is not initialized!");
+            return Err(InsuranceContractError::NotInitialized.into());
+        }
+        Ok(insurance_contract_data)
+    }
+
+    /// Deserializes an initialized InsuranceProduct account
+    fn load_product(
+        program_id: &Pubkey,
+        product_account: &AccountInfo,
+    ) -> Result<InsuranceProductData, ProgramError> {
+        if product_account.owner != program_id {
+            msg!("Invalid owner for InsuranceProductData account");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let product_data = InsuranceProductData::unpack(&product_account.data.borrow())?;
+        if !product_data.is_initialized() {
+            msg!("Insurance product account is not initialized!");
+            return Err(InsuranceContractError::NotInitialized.into());
+        }
+        Ok(product_data)
+    }
+
+    /// Deserializes the program config account, None until it is initialized
+    fn load_config(
+        program_id: &Pubkey,
+        config_info: &AccountInfo,
+    ) -> Result<Option<ConfigData>, ProgramError> {
+        let (config_address, _) = find_config_address(program_id);
+        if config_address != *config_info.key {
+            msg!("Config address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if config_info.owner != program_id {
+            return Ok(None);
+        }
+
+        let config_data = ConfigData::unpack(&config_info.data.borrow())?;
+        Ok(Some(config_data).filter(ConfigData::is_initialized))
+    }
+
+    /// Checks that new business is not stopped by the guardian
+    fn check_not_paused(program_id: &Pubkey, config_info: &AccountInfo) -> ProgramResult {
+        match Self::load_config(program_id, config_info)? {
+            Some(config_data) if config_data.paused => {
+                msg!("Program is paused");
+                Err(InsuranceContractError::ProgramPaused.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Deserializes an initialized Claim account filed against the InsuranceContract
+    fn load_claim(
+        program_id: &Pubkey,
+        claim_account: &AccountInfo,
+        insurance_contract_key: &Pubkey,
+    ) -> Result<ClaimData, ProgramError> {
+        if claim_account.owner != program_id {
+            msg!("Invalid owner for ClaimData account");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let claim_data = ClaimData::unpack(&claim_account.data.borrow())?;
+        if !claim_data.is_initialized() {
+            msg!("Claim data account is not initialized!");
+            return Err(InsuranceContractError::NotInitialized.into());
+        }
+        if claim_data.insurance_contract != *insurance_contract_key {
+            msg!("Claim is filed against another insurance contract");
+            return Err(InsuranceContractError::ClaimContractMismatch.into());
+        }
+        Ok(claim_data)
+    }
+
+    /// Creates the InsuranceContract account at the address derived from the authority
+    /// and the identifier unless the program already owns it
+    fn create_insurance_contract_account<'a>(
+        program_id: &Pubkey,
+        insurance_contract_authority: &AccountInfo<'a>,
+        insurance_contract_account: &AccountInfo<'a>,
+        insurance_contract_id: u32,
+        rent: &Rent,
+        system_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (insurance_contract_address, bump_seed) = find_insurance_contract_address(
+            program_id,
+            insurance_contract_authority.key,
+            insurance_contract_id,
+        );
+        if insurance_contract_address != *insurance_contract_account.key {
+            msg!("Insurance contract address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if insurance_contract_account.owner == program_id {
+            return Ok(());
+        }
+
+        Self::create_pda_account(
+            insurance_contract_authority,
+            rent,
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            program_id,
+            system_program_info,
+            insurance_contract_account,
+            &[
+                INSURANCE_CONTRACT_SEED,
+                insurance_contract_authority.key.as_ref(),
+                &insurance_contract_id.to_le_bytes(),
+                &[bump_seed],
+            ],
+        )
+    }
+
+    /// Creates the Claim account at the address derived from the InsuranceContract
+    /// and the claim index unless the program already owns it
+    fn create_claim_account<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        insurance_contract_account: &AccountInfo<'a>,
+        claim_account: &AccountInfo<'a>,
+        claim_index: u32,
+        rent: &Rent,
+        system_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (claim_address, bump_seed) =
+            find_claim_address(program_id, insurance_contract_account.key, claim_index);
+        if claim_address != *claim_account.key {
+            msg!("Claim address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if claim_account.owner == program_id {
+            return Ok(());
+        }
+
+        Self::create_pda_account(
+            payer,
+            rent,
+            state::CLAIM_DATA_LEN,
+            program_id,
+            system_program_info,
+            claim_account,
+            &[
+                CLAIM_SEED,
+                insurance_contract_account.key.as_ref(),
+                &claim_index.to_le_bytes(),
+                &[bump_seed],
+            ],
+        )
+    }
+
+    /// Creates the token account holding the premiums of the InsuranceContract unless
+    /// the token program already owns it. The vault is its own token account owner.
+    #[allow(clippy::too_many_arguments)]
+    fn create_vault_account<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        insurance_contract_account: &AccountInfo<'a>,
+        vault_account: &AccountInfo<'a>,
+        mint_info: &AccountInfo<'a>,
+        rent_info: &AccountInfo<'a>,
+        rent: &Rent,
+        system_program_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (vault_address, bump_seed) =
+            find_vault_address(program_id, insurance_contract_account.key);
+        if vault_address != *vault_account.key {
+            msg!("Vault address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if vault_account.owner == token_program_info.key {
+            return Ok(());
+        }
+
+        Self::create_pda_account(
+            payer,
+            rent,
+            TokenAccount::LEN,
+            token_program_info.key,
+            system_program_info,
+            vault_account,
+            &[
+                VAULT_SEED,
+                insurance_contract_account.key.as_ref(),
+                &[bump_seed],
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account(
+                token_program_info.key,
+                vault_account.key,
+                mint_info.key,
+                vault_account.key,
+            )?,
+            &[
+                vault_account.clone(),
+                mint_info.clone(),
+                rent_info.clone(),
+                token_program_info.clone(),
+            ],
+        )
+    }
+
+    /// Creates a rent exempt account at a program derived address.
+    /// Lamports already transferred to the address are taken into account.
+    fn create_pda_account<'a>(
+        payer: &AccountInfo<'a>,
+        rent: &Rent,
+        space: usize,
+        owner: &Pubkey,
+        system_program_info: &AccountInfo<'a>,
+        new_pda_account: &AccountInfo<'a>,
+        new_pda_signer_seeds: &[&[u8]],
+    ) -> ProgramResult {
+        // Only the program can sign for the address, an account already allocated to the
+        // owner there was created by an earlier instruction
+        if new_pda_account.owner == owner && new_pda_account.data_len() == space {
+            return Ok(());
+        }
+
+        let required_lamports = rent
+            .minimum_balance(space)
+            .max(1)
+            .saturating_sub(new_pda_account.lamports());
+
+        if new_pda_account.lamports() == 0 {
+            return invoke_signed(
+                &system_instruction::create_account(
+                    payer.key,
+                    new_pda_account.key,
+                    required_lamports,
+                    space as u64,
+                    owner,
+                ),
+                &[
+                    payer.clone(),
+                    new_pda_account.clone(),
+                    system_program_info.clone(),
+                ],
+                &[new_pda_signer_seeds],
+            );
+        }
+
+        if required_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(payer.key, new_pda_account.key, required_lamports),
+                &[
+                    payer.clone(),
+                    new_pda_account.clone(),
+                    system_program_info.clone(),
+                ],
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(new_pda_account.key, space as u64),
+            &[new_pda_account.clone(), system_program_info.clone()],
+            &[new_pda_signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(new_pda_account.key, owner),
+            &[new_pda_account.clone(), system_program_info.clone()],
+            &[new_pda_signer_seeds],
+        )
+    }
+
+    /// Transfers premium tokens from the payer into the InsuranceContract vault,
+    /// the vault is created on the first deposit
+    #[allow(clippy::too_many_arguments)]
+    fn deposit_premium<'a>(
+        program_id: &Pubkey,
+        premium_payer: &AccountInfo<'a>,
+        premium_payer_token_account: &AccountInfo<'a>,
+        insurance_contract_account: &AccountInfo<'a>,
+        vault_account: &AccountInfo<'a>,
+        premium_mint_info: &AccountInfo<'a>,
+        rent_info: &AccountInfo<'a>,
+        system_program_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        amount: u64,
+    ) -> ProgramResult {
+        let rent = Rent::from_account_info(rent_info)?;
+        Self::create_vault_account(
+            program_id,
+            premium_payer,
+            insurance_contract_account,
+            vault_account,
+            premium_mint_info,
+            rent_info,
+            &rent,
+            system_program_info,
+            token_program_info,
+        )?;
+
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program_info.key,
+                premium_payer_token_account.key,
+                vault_account.key,
+                premium_payer.key,
+                &[],
+                amount,
+            )?,
+            &[
+                premium_payer_token_account.clone(),
+                vault_account.clone(),
+                premium_payer.clone(),
+                token_program_info.clone(),
+            ],
+        )
+    }
+
+    /// Checks the premium mint against the program config and transfers the protocol fee
+    /// on top of a premium payment to the treasury, returns the fee paid
+    #[allow(clippy::too_many_arguments)]
+    fn pay_protocol_fee<'a>(
+        program_id: &Pubkey,
+        insurance_contract_data: &mut InsuranceContractData,
+        premium_payer: &AccountInfo<'a>,
+        premium_payer_token_account: &AccountInfo<'a>,
+        premium_mint_info: &AccountInfo<'a>,
+        config_info: &AccountInfo<'a>,
+        treasury_token_account: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        premium_amount: u64,
+    ) -> Result<u64, ProgramError> {
+        let settings = match Self::load_config(program_id, config_info)? {
+            Some(config_data) => config_data.settings,
+            None => return Ok(0),
+        };
+        if !settings.allows_premium_mint(premium_mint_info.key) {
+            msg!("Premium mint is not allowed");
+            return Err(InsuranceContractError::PremiumMintNotAllowed.into());
+        }
+
+        let fee = settings.protocol_fee(premium_amount);
+        if fee == 0 {
+            return Ok(0);
+        }
+        let treasury = Self::load_token_account(treasury_token_account)?;
+        if treasury.owner != settings.treasury || treasury.mint != *premium_mint_info.key {
+            msg!("Token account is not a treasury account of the premium mint");
+            return Err(InsuranceContractError::TreasuryMismatch.into());
+        }
+
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program_info.key,
+                premium_payer_token_account.key,
+                treasury_token_account.key,
+                premium_payer.key,
+                &[],
+                fee,
+            )?,
+            &[
+                premium_payer_token_account.clone(),
+                treasury_token_account.clone(),
+                premium_payer.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+
+        insurance_contract_data.protocol_fees_paid = insurance_contract_data
+            .protocol_fees_paid
+            .checked_add(fee)
+            .ok_or(InsuranceContractError::Overflow)?;
+        Ok(fee)
+    }
+
+    /// Deposits the keeper reward on top of the rent exempt balance of a new InsuranceContract
+    fn reserve_keeper_reward<'a>(
+        insurance_contract_authority: &AccountInfo<'a>,
+        insurance_contract_account: &AccountInfo<'a>,
+        keeper_reward: u64,
+        system_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        if keeper_reward == 0 {
+            return Ok(());
+        }
+        invoke(
+            &system_instruction::transfer(
+                insurance_contract_authority.key,
+                insurance_contract_account.key,
+                keeper_reward,
+            ),
+            &[
+                insurance_contract_authority.clone(),
+                insurance_contract_account.clone(),
+                system_program_info.clone(),
+            ],
+        )
+    }
+
+    /// Checks that the signer is the authority stored on the InsuranceContract account
+    fn check_authority(
+        insurance_contract_authority: &AccountInfo,
+        insurance_contract_data: &InsuranceContractData,
+    ) -> ProgramResult {
+        if insurance_contract_data.authority != *insurance_contract_authority.key {
+            msg!("Insurance contract authority mismatch");
+            return Err(InsuranceContractError::AuthorityMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that the signer is the authority of the InsuranceProduct
+    fn check_product_authority(
+        product_authority: &AccountInfo,
+        product_data: &InsuranceProductData,
+    ) -> ProgramResult {
+        if product_data.authority != *product_authority.key {
+            msg!("Insurance product authority mismatch");
+            return Err(InsuranceContractError::AuthorityMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that new policies can be issued from the InsuranceProduct
+    fn check_product_issuable(product_data: &InsuranceProductData) -> ProgramResult {
+        if product_data.status == ProductStatus::Retired {
+            msg!("Insurance product is retired");
+            return Err(InsuranceContractError::ProductRetired.into());
+        }
+        if product_data.paused {
+            msg!("Insurance product is paused");
+            return Err(InsuranceContractError::ProgramPaused.into());
+        }
+        Ok(())
+    }
+
+    /// Checks that the signer is the policyholder of the InsuranceContract
+    fn check_policyholder(
+        policyholder: &AccountInfo,
+        policy_token_account: &AccountInfo,
+        insurance_contract_data: &InsuranceContractData,
+    ) -> ProgramResult {
+        if Self::load_policyholder(insurance_contract_data, policy_token_account)?
+            != *policyholder.key
+        {
+            msg!("Policyholder mismatch");
+            return Err(InsuranceContractError::HolderMismatch.into());
+        }
+        Ok(())
+    }
+
+    /// Current policyholder of the InsuranceContract: the owner of the token account
+    /// holding the policy token, or the stored holder when the policy is not tokenized
+    fn load_policyholder(
+        insurance_contract_data: &InsuranceContractData,
+        policy_token_account: &AccountInfo,
+    ) -> Result<Pubkey, ProgramError> {
+        if !insurance_contract_data.is_tokenized() {
+            return Ok(insurance_contract_data.policyholder());
+        }
+        let policy_token = Self::load_token_account(policy_token_account)?;
+        if policy_token.mint != insurance_contract_data.policy_mint || policy_token.amount != 1 {
+            msg!("Account doesn't hold the policy token");
+            return Err(InsuranceContractError::HolderMismatch.into());
+        }
+        Ok(policy_token.owner)
+    }
+
+    /// Deserializes a token account owned by the token program
+    fn load_token_account(token_account: &AccountInfo) -> Result<TokenAccount, ProgramError> {
+        if *token_account.owner != spl_token::id() {
+            msg!("Token account is not owned by the token program");
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        TokenAccount::unpack(&token_account.data.borrow())
+    }
+
+    /// Creates the policy token mint and account of a new InsuranceContract and mints
+    /// the single policy token to the policyholder. The mint authority is removed so the
+    /// supply stays fixed, the token is frozen when assignments need consent.
+    #[allow(clippy::too_many_arguments)]
+    fn mint_policy_token<'a>(
+        program_id: &Pubkey,
+        payer: &AccountInfo<'a>,
+        insurance_contract_account: &AccountInfo<'a>,
+        policy_mint_info: &AccountInfo<'a>,
+        policy_token_account: &AccountInfo<'a>,
+        policyholder: &Pubkey,
+        frozen: bool,
+        rent_info: &AccountInfo<'a>,
+        rent: &Rent,
+        system_program_info: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+    ) -> ProgramResult {
+        let (policy_mint_address, mint_bump_seed) =
+            find_policy_mint_address(program_id, insurance_contract_account.key);
+        let (policy_token_address, token_bump_seed) =
+            find_policy_token_address(program_id, insurance_contract_account.key);
+        if policy_mint_address != *policy_mint_info.key
+            || policy_token_address != *policy_token_account.key
+        {
+            msg!("Policy token address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let mint_signer_seeds: &[&[u8]] = &[
+            POLICY_MINT_SEED,
+            insurance_contract_account.key.as_ref(),
+            &[mint_bump_seed],
+        ];
+
+        Self::create_pda_account(
+            payer,
+            rent,
+            Mint::LEN,
+            token_program_info.key,
+            system_program_info,
+            policy_mint_info,
+            mint_signer_seeds,
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_mint(
+                token_program_info.key,
+                policy_mint_info.key,
+                policy_mint_info.key,
+                Some(policy_mint_info.key),
+                0,
+            )?,
+            &[
+                policy_mint_info.clone(),
+                rent_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+
+        Self::create_pda_account(
+            payer,
+            rent,
+            TokenAccount::LEN,
+            token_program_info.key,
+            system_program_info,
+            policy_token_account,
+            &[
+                POLICY_TOKEN_SEED,
+                insurance_contract_account.key.as_ref(),
+                &[token_bump_seed],
+            ],
+        )?;
+        invoke(
+            &spl_token::instruction::initialize_account2(
+                token_program_info.key,
+                policy_token_account.key,
+                policy_mint_info.key,
+                policyholder,
+            )?,
+            &[
+                policy_token_account.clone(),
+                policy_mint_info.clone(),
+                rent_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+
+        invoke_signed(
+            &spl_token::instruction::mint_to(
+                token_program_info.key,
+                policy_mint_info.key,
+                policy_token_account.key,
+                policy_mint_info.key,
+                &[],
+                1,
+            )?,
+            &[
+                policy_mint_info.clone(),
+                policy_token_account.clone(),
+                token_program_info.clone(),
+            ],
+            &[mint_signer_seeds],
+        )?;
+        invoke_signed(
+            &spl_token::instruction::set_authority(
+                token_program_info.key,
+                policy_mint_info.key,
+                None,
+                spl_token::instruction::AuthorityType::MintTokens,
+                policy_mint_info.key,
+                &[],
+            )?,
+            &[policy_mint_info.clone(), token_program_info.clone()],
+            &[mint_signer_seeds],
+        )?;
+
+        if frozen {
+            Self::set_policy_token_frozen(
+                program_id,
+                insurance_contract_account,
+                policy_mint_info,
+                policy_token_account,
+                token_program_info,
+                true,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Moves the policy token to the token account of a new holder, the token stays
+    /// frozen when assignments need consent
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_policy_token<'a>(
+        program_id: &Pubkey,
+        insurance_contract_account: &AccountInfo<'a>,
+        policy_mint_info: &AccountInfo<'a>,
+        policy_token_account: &AccountInfo<'a>,
+        new_holder_token_account: &AccountInfo<'a>,
+        policyholder: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        frozen: bool,
+    ) -> ProgramResult {
+        if TokenAccount::unpack(&policy_token_account.data.borrow())?.is_frozen() {
+            Self::set_policy_token_frozen(
+                program_id,
+                insurance_contract_account,
+                policy_mint_info,
+                policy_token_account,
+                token_program_info,
+                false,
+            )?;
+        }
+        invoke(
+            &spl_token::instruction::transfer(
+                token_program_info.key,
+                policy_token_account.key,
+                new_holder_token_account.key,
+                policyholder.key,
+                &[],
+                1,
+            )?,
+            &[
+                policy_token_account.clone(),
+                new_holder_token_account.clone(),
+                policyholder.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+        if frozen {
+            Self::set_policy_token_frozen(
+                program_id,
+                insurance_contract_account,
+                policy_mint_info,
+                new_holder_token_account,
+                token_program_info,
+                true,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Freezes or thaws a policy token account, the policy mint is its freeze authority
+    fn set_policy_token_frozen<'a>(
+        program_id: &Pubkey,
+        insurance_contract_account: &AccountInfo<'a>,
+        policy_mint_info: &AccountInfo<'a>,
+        policy_token_account: &AccountInfo<'a>,
+        token_program_info: &AccountInfo<'a>,
+        frozen: bool,
+    ) -> ProgramResult {
+        let (policy_mint_address, bump_seed) =
+            find_policy_mint_address(program_id, insurance_contract_account.key);
+        if policy_mint_address != *policy_mint_info.key {
+            msg!("Policy mint address does not match seed derivation");
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let instruction = if frozen {
+            spl_token::instruction::freeze_account(
+                token_program_info.key,
+                policy_token_account.key,
+                policy_mint_info.key,
+                policy_mint_info.key,
+                &[],
+            )?
+        } else {
+            spl_token::instruction::thaw_account(
+                token_program_info.key,
+                policy_token_account.key,
+                policy_mint_info.key,
+                policy_mint_info.key,
+                &[],
+            )?
+        };
+        invoke_signed(
+            &instruction,
+            &[
+                policy_token_account.clone(),
+                policy_mint_info.clone(),
+                token_program_info.clone(),
+            ],
+            &[&[
+                POLICY_MINT_SEED,
+                insurance_contract_account.key.as_ref(),
+                &[bump_seed],
+            ]],
+        )
+    }
+}
+
+// Unit tests
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::events::InsuranceContractEvent;
+    use crate::state::{
+        InsuranceContractDataV0, InsuranceContractDataV1, InsuranceContractDataV2, Peril,
+        MAX_PERILS, NO_PERIL,
+    };
+    use borsh::BorshSerialize;
+    use solana_program::account_info::IntoAccountInfo;
+    use solana_program::{instruction::Instruction, sysvar};
+    use solana_sdk::account::{
+        create_account_for_test, create_is_signer_account_infos, Account as SolanaAccount,
+    };
+
+    fn insurance_contract_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(state::INSURANCE_CONTRACT_DATA_LEN)
+    }
+
+    /// Processes the instruction with the given accounts, the missing trailing ones,
+    /// like the program config of the pausable instructions, are default
+    fn do_process(instruction: Instruction, accounts: Vec<&mut SolanaAccount>) -> ProgramResult {
+        let mut defaults = vec![
+            SolanaAccount::default();
+            instruction.accounts.len().saturating_sub(accounts.len())
+        ];
+        let mut meta = instruction
+            .accounts
+            .iter()
+            .zip(accounts.into_iter().chain(defaults.iter_mut()))
+            .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
+            .collect::<Vec<_>>();
+
+        let account_infos = create_is_signer_account_infos(&mut meta);
+        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+    }
+
+    /// Decodes the events emitted on the current thread since the last call
+    fn take_events() -> Vec<InsuranceContractEvent> {
+        events::take_emitted_logs()
+            .iter()
+            .filter_map(|log| InsuranceContractEvent::from_log(log))
+            .collect()
+    }
+
+    fn test_terms() -> PolicyTerms {
+        PolicyTerms {
+            coverage_start: 1_000,
+            coverage_end: 1_000 + 365 * 24 * 60 * 60,
+            sum_insured: 1_000_000,
+            premium_amount: 10_000,
+            premium_mint: Pubkey::new_from_array([1; 32]),
+            claims_adjuster: Pubkey::new_from_array([2; 32]),
+            keeper_reward: 1_000,
+            cancellation_penalty_bps: 1_000,
+            no_claims_bonus_bps: 1_000,
+            installment_count: 0,
+            installment_amount: 0,
+            installment_interval: 0,
+            grace_period: 0,
+            assignment_consent_required: false,
+            deductible: 0,
+            per_occurrence_limit: 0,
+            annual_aggregate_limit: 0,
+            perils: [Peril::default(); MAX_PERILS],
+            waiting_period: 0,
+        }
+    }
+
+    fn claim_minimum_balance() -> u64 {
+        Rent::default().minimum_balance(state::CLAIM_DATA_LEN)
+    }
+
+    fn test_clock() -> Clock {
+        Clock {
+            unix_timestamp: 5_000,
+            ..Clock::default()
+        }
+    }
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> SolanaAccount {
+        let mut token_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN,
+            &spl_token::id(),
+        );
+        TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..TokenAccount::default()
+        }
+        .pack_into_slice(&mut token_acc.data);
+        token_acc
+    }
+
+    /// Token account holding the policy token of an InsuranceContract saved by the tests
+    fn policy_token_account(insurance_contract_key: &Pubkey, holder: &Pubkey) -> SolanaAccount {
+        let (policy_mint, _) = find_policy_mint_address(&crate::id(), insurance_contract_key);
+        token_account(&policy_mint, holder, 1)
+    }
+
+    /// Program data account of the upgradeable BPF loader storing the upgrade authority
+    fn program_data_account(upgrade_authority: &Pubkey) -> SolanaAccount {
+        let mut program_data_acc = SolanaAccount::new(
+            Rent::default()
+                .minimum_balance(UpgradeableLoaderState::programdata_data_offset().unwrap()),
+            UpgradeableLoaderState::programdata_data_offset().unwrap(),
+            &bpf_loader_upgradeable::id(),
+        );
+        program_data_acc.data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: Some(*upgrade_authority),
+        })
+        .unwrap();
+        program_data_acc
+    }
+
+    fn do_submit_claim(
+        insurance_contract_owner_key: &Pubkey,
+        insurance_contract_data_key: &Pubkey,
+        insurance_contract_data_acc: &mut SolanaAccount,
+        claim_index: u32,
+        amount: u64,
+    ) -> SolanaAccount {
+        let mut claim_data_acc =
+            SolanaAccount::new(claim_minimum_balance(), state::CLAIM_DATA_LEN, &crate::id());
+        do_process(
+            crate::instruction::submit_claim(
+                &crate::id(),
+                insurance_contract_owner_key,
+                insurance_contract_data_key,
+                claim_index,
+                amount,
+                2_000,
+                [5; 32],
+                NO_PERIL,
+                &Pubkey::new_unique(),
+            )
+            .unwrap(),
+            vec![
+                &mut SolanaAccount::default(),
+                insurance_contract_data_acc,
+                &mut claim_data_acc,
+                &mut create_account_for_test(&Rent::default()),
+                &mut create_account_for_test(&test_clock()),
+                &mut SolanaAccount::default(),
+                &mut policy_token_account(
+                    insurance_contract_data_key,
+                    insurance_contract_owner_key,
+                ),
+            ],
+        )
+        .unwrap();
+        claim_data_acc
+    }
+
+    fn do_save_insurance_contract(
+        insurance_contract_owner_key: &Pubkey,
+        insurance_contract_id: u32,
+        terms: PolicyTerms,
+    ) -> SolanaAccount {
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &crate::id(),
+        );
+        do_process(
+            crate::instruction::save_insurance_contract(
+                &crate::id(),
+                insurance_contract_owner_key,
+                insurance_contract_id,
+                terms,
+            )
+            .unwrap(),
+            vec![
+                &mut SolanaAccount::default(),
+                &mut insurance_contract_data_acc,
+                &mut create_account_for_test(&Rent::default()),
+                &mut create_account_for_test(&Clock::default()),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        insurance_contract_data_acc
+    }
+
+    #[test]
+    fn test_save_insurance_contract() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut clock_acc = create_account_for_test(&Clock::default());
+        let mut system_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let insurance_contract_id = 11223344;
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+
+        insurance_contract_data_acc.lamports -= 100;
+
+        // BadCase: Rent exempt
+        assert_eq!(
+            Err(ProgramError::AccountNotRentExempt),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    test_terms(),
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        insurance_contract_data_acc.lamports += 100;
+
+        // BadCase: Coverage period is over
+        assert_eq!(
+            Err(InsuranceContractError::InvalidCoveragePeriod.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    PolicyTerms {
+                        coverage_start: -2_000,
+                        coverage_end: -1_000,
+                        ..test_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Coverage ends before it starts
+        assert_eq!(
+            Err(InsuranceContractError::InvalidCoveragePeriod.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    PolicyTerms {
+                        coverage_start: 2_000,
+                        coverage_end: 1_000,
+                        ..test_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Nothing is insured
+        assert_eq!(
+            Err(InsuranceContractError::InvalidSumInsured.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    PolicyTerms {
+                        sum_insured: 0,
+                        ..test_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Deductible swallows the sum insured
+        assert_eq!(
+            Err(InsuranceContractError::InvalidClaimLimits.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    PolicyTerms {
+                        deductible: test_terms().sum_insured,
+                        ..test_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Aggregate limit exceeds the sum insured
+        assert_eq!(
+            Err(InsuranceContractError::InvalidClaimLimits.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    PolicyTerms {
+                        annual_aggregate_limit: test_terms().sum_insured + 1,
+                        ..test_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Aggregate limit applies to more policy years than are kept
+        assert_eq!(
+            Err(InsuranceContractError::InvalidClaimLimits.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    PolicyTerms {
+                        coverage_end: test_terms().coverage_start
+                            + state::SECONDS_PER_POLICY_YEAR * state::MAX_AGGREGATE_YEARS as i64
+                            + 1,
+                        annual_aggregate_limit: 1_000,
+                        ..test_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Peril is listed twice, sub-limit exceeds the sum insured or is set on an
+        // unused slot
+        let peril = |code, sub_limit| Peril { code, sub_limit };
+        for perils in [
+            [peril(1, 0), peril(1, 0)],
+            [peril(1, test_terms().sum_insured + 1), Peril::default()],
+            [peril(1, 0), peril(NO_PERIL, 1)],
+        ]
+        .iter()
+        {
+            let mut terms = test_terms();
+            terms.perils[..2].copy_from_slice(perils);
+            assert_eq!(
+                Err(InsuranceContractError::InvalidClaimLimits.into()),
+                do_process(
+                    crate::instruction::save_insurance_contract(
+                        &program_id,
+                        &insurance_contract_owner_key,
+                        insurance_contract_id,
+                        terms,
+                    )
+                    .unwrap(),
+                    vec![
+                        &mut insurance_contract_owner_acc,
+                        &mut insurance_contract_data_acc,
+                        &mut rent_acc,
+                        &mut clock_acc,
+                        &mut system_acc,
+                        &mut SolanaAccount::default(),
+                        &mut SolanaAccount::default(),
+                        &mut SolanaAccount::default(),
+                    ],
+                )
+            );
+        }
+
+        // BadCase: Penalty exceeds the premium
+        assert_eq!(
+            Err(InsuranceContractError::InvalidCancellationPenalty.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    PolicyTerms {
+                        cancellation_penalty_bps: state::BASIS_POINTS + 1,
+                        ..test_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Address is not derived from the authority and identifier
+        let mut instruction = crate::instruction::save_insurance_contract(
+            &program_id,
+            &insurance_contract_owner_key,
+            insurance_contract_id,
+            test_terms(),
+        )
+        .unwrap();
+        instruction.accounts[1].pubkey = Pubkey::new_unique();
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            do_process(
+                instruction,
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Policy mint is not derived from the InsuranceContract
+        let mut instruction = crate::instruction::save_insurance_contract(
+            &program_id,
+            &insurance_contract_owner_key,
+            insurance_contract_id,
+            test_terms(),
+        )
+        .unwrap();
+        instruction.accounts[5].pubkey = Pubkey::new_unique();
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            do_process(
+                instruction,
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut SolanaAccount::new(
+                        insurance_contract_minimum_balance(),
+                        state::INSURANCE_CONTRACT_DATA_LEN,
+                        &program_id,
+                    ),
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::save_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                insurance_contract_id,
+                test_terms(),
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Draft
+        );
+        assert_eq!(
+            insurance_contract_data.insurance_contract_id,
+            insurance_contract_id
+        );
+        assert_eq!(
+            insurance_contract_data.authority,
+            insurance_contract_owner_key
+        );
+        let terms = test_terms();
+        assert_eq!(insurance_contract_data.coverage_start, terms.coverage_start);
+        assert_eq!(insurance_contract_data.coverage_end, terms.coverage_end);
+        assert_eq!(insurance_contract_data.sum_insured, terms.sum_insured);
+        assert_eq!(insurance_contract_data.premium_amount, terms.premium_amount);
+        assert_eq!(insurance_contract_data.premium_mint, terms.premium_mint);
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 11223344);
+        assert_eq!(
+            insurance_contract_data.policy_mint,
+            find_policy_mint_address(&program_id, &insurance_contract_data_key).0
+        );
+
+        // BadCase: account already initialized
+        assert_eq!(
+            Err(InsuranceContractError::AlreadyInitialized.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    insurance_contract_id,
+                    test_terms(),
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_close_insurance_contract() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut clock_acc = create_account_for_test(&Clock::default());
+        let mut system_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let insurance_contract_id = 11223344;
+        let (insurance_contract_data_key, _) = find_insurance_contract_address(
+            &program_id,
+            &insurance_contract_owner_key,
+            insurance_contract_id,
+        );
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+        let mut policy_mint_acc = SolanaAccount::default();
+        let policy_token_key = Pubkey::new_unique();
+        let mut policy_token_acc =
+            policy_token_account(&insurance_contract_data_key, &insurance_contract_owner_key);
+        let mut token_program_acc = SolanaAccount::default();
+        let close = |insurance_contract_authority_key: &Pubkey, policyholder_key: &Pubkey| {
+            crate::instruction::close_insurance_contract(
+                &program_id,
+                insurance_contract_authority_key,
+                &insurance_contract_data_key,
+                policyholder_key,
+                &policy_token_key,
+            )
+            .unwrap()
+        };
+
+        // BadCase: Not initialized
+        assert_eq!(
+            Err(InsuranceContractError::NotInitialized.into()),
+            do_process(
+                close(&insurance_contract_owner_key, &insurance_contract_owner_key),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut policy_mint_acc,
+                    &mut policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut token_program_acc,
+                ],
+            )
+        );
+
+        take_events();
+        do_process(
+            crate::instruction::save_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                insurance_contract_id,
+                test_terms(),
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            vec![InsuranceContractEvent::ContractSaved(ContractSaved {
+                insurance_contract: insurance_contract_data_key,
+                authority: insurance_contract_owner_key,
+                insurance_contract_id,
+                product: Pubkey::default(),
+                status: InsuranceContractStatus::Draft,
+            })],
+            take_events()
+        );
+
+        // BadCase: Authority mismatch
+        let attacker_key = Pubkey::new_unique();
+        let mut attacker_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                close(&attacker_key, &insurance_contract_owner_key),
+                vec![
+                    &mut attacker_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut policy_mint_acc,
+                    &mut policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut token_program_acc,
+                ],
+            )
+        );
+
+        // BadCase: Policy token is held by somebody else
+        let mut attacker_token_acc =
+            policy_token_account(&insurance_contract_data_key, &attacker_key);
+        assert_eq!(
+            Err(InsuranceContractError::HolderMismatch.into()),
+            do_process(
+                close(&insurance_contract_owner_key, &insurance_contract_owner_key),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut policy_mint_acc,
+                    &mut attacker_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut token_program_acc,
+                ],
+            )
+        );
+
+        // BadCase: Policyholder doesn't sign the burn of the policy token
+        let mut instruction = close(&insurance_contract_owner_key, &insurance_contract_owner_key);
+        instruction.accounts[4].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process(
+                instruction,
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut policy_mint_acc,
+                    &mut policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut token_program_acc,
+                ],
+            )
+        );
+
+        do_process(
+            close(&insurance_contract_owner_key, &insurance_contract_owner_key),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut policy_mint_acc,
+                &mut policy_token_acc,
+                &mut SolanaAccount::default(),
+                &mut token_program_acc,
+            ],
+        )
+        .unwrap();
+
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Closed
+        );
+        assert_eq!(
+            insurance_contract_data.insurance_contract_id,
+            insurance_contract_id
+        );
+        assert_eq!(
+            vec![InsuranceContractEvent::ContractClosed(ContractClosed {
+                insurance_contract: insurance_contract_data_key,
+            })],
+            take_events()
+        );
+
+        // BadCase: account already closed
+        assert_eq!(
+            Err(InsuranceContractError::AlreadyClosed.into()),
+            do_process(
+                close(&insurance_contract_owner_key, &insurance_contract_owner_key),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut policy_mint_acc,
+                    &mut policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut token_program_acc,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_migrate_insurance_contract() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut system_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let legacy_insurance_contract_data_key = Pubkey::new_unique();
+        let legacy_data = InsuranceContractDataV1 {
+            is_initialized: true,
+            is_closed: true,
+            insurance_contract_id: 11223344,
+            authority: insurance_contract_owner_key,
+        }
+        .try_to_vec()
+        .unwrap();
+        let legacy_lamports = Rent::default().minimum_balance(legacy_data.len());
+        let mut legacy_insurance_contract_data_acc =
+            SolanaAccount::new(legacy_lamports, legacy_data.len(), &program_id);
+        legacy_insurance_contract_data_acc.data = legacy_data;
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 11223344);
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+
+        // BadCase: Authority mismatch
+        let attacker_key = Pubkey::new_unique();
+        let mut attacker_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                crate::instruction::migrate_insurance_contract(
+                    &program_id,
+                    &attacker_key,
+                    &legacy_insurance_contract_data_key,
+                    11223344,
+                )
+                .unwrap(),
+                vec![
+                    &mut attacker_acc,
+                    &mut legacy_insurance_contract_data_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::migrate_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &legacy_insurance_contract_data_key,
+                11223344,
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut legacy_insurance_contract_data_acc,
+                &mut insurance_contract_data_acc,
+                &mut rent_acc,
+                &mut system_acc,
+            ],
+        )
+        .unwrap();
+
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(
+            insurance_contract_data.account_type,
+            AccountType::InsuranceContract
+        );
+        assert_eq!(
+            insurance_contract_data.version,
+            state::INSURANCE_CONTRACT_VERSION
+        );
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Closed
+        );
+        assert_eq!(insurance_contract_data.insurance_contract_id, 11223344);
+        assert_eq!(
+            insurance_contract_data.authority,
+            insurance_contract_owner_key
+        );
+        assert_eq!(legacy_insurance_contract_data_acc.lamports, 0);
+        assert!(legacy_insurance_contract_data_acc
+            .data
+            .iter()
+            .all(|byte| *byte == 0));
+        assert_eq!(insurance_contract_owner_acc.lamports, legacy_lamports);
+
+        // BadCase: account already migrated
+        let mut other_insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+        assert_eq!(
+            Err(InsuranceContractError::AlreadyMigrated.into()),
+            do_process(
+                crate::instruction::migrate_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &insurance_contract_data_key,
+                    11223344,
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut other_insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_migrate_insurance_contract_without_authority() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut system_acc = SolanaAccount::default();
+
+        let admin_key = Pubkey::new_unique();
+        let mut admin_acc = SolanaAccount::default();
+        let mut program_data_acc = program_data_account(&admin_key);
+        let mut config_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(state::CONFIG_DATA_LEN),
+            state::CONFIG_DATA_LEN,
+            &program_id,
+        );
+        let legacy_data = InsuranceContractDataV0 {
+            is_initialized: true,
+            is_closed: false,
+            insurance_contract_id: 11223344,
+        }
+        .try_to_vec()
+        .unwrap();
+        let mut legacy_insurance_contract_data_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(legacy_data.len()),
+            legacy_data.len(),
+            &program_id,
+        );
+        legacy_insurance_contract_data_acc.data = legacy_data;
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+        let legacy_insurance_contract_data_key = Pubkey::new_unique();
+        let migrate = |authority_key: &Pubkey| {
+            crate::instruction::migrate_insurance_contract(
+                &program_id,
+                authority_key,
+                &legacy_insurance_contract_data_key,
+                11223344,
+            )
+            .unwrap()
+        };
+
+        // BadCase: Config is not initialized
+        let stranger_key = Pubkey::new_unique();
+        let mut stranger_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::AdminMismatch.into()),
+            do_process(
+                migrate(&stranger_key),
+                vec![
+                    &mut stranger_acc,
+                    &mut legacy_insurance_contract_data_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::initialize_config(
+                &program_id,
+                &admin_key,
+                ConfigSettings::default(),
+            )
+            .unwrap(),
+            vec![
+                &mut admin_acc,
+                &mut config_acc,
+                &mut rent_acc,
+                &mut system_acc,
+                &mut program_data_acc,
+            ],
+        )
+        .unwrap();
+
+        // BadCase: Stranger takes the legacy account over
+        assert_eq!(
+            Err(InsuranceContractError::AdminMismatch.into()),
+            do_process(
+                migrate(&stranger_key),
+                vec![
+                    &mut stranger_acc,
+                    &mut legacy_insurance_contract_data_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut config_acc,
+                ],
+            )
+        );
+        assert_eq!(stranger_acc.lamports, 0);
+
+        do_process(
+            migrate(&admin_key),
+            vec![
+                &mut admin_acc,
+                &mut legacy_insurance_contract_data_acc,
+                &mut insurance_contract_data_acc,
+                &mut rent_acc,
+                &mut system_acc,
+                &mut config_acc,
+            ],
+        )
+        .unwrap();
+
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Active
+        );
+        assert_eq!(insurance_contract_data.insurance_contract_id, 11223344);
+        assert_eq!(insurance_contract_data.authority, admin_key);
+    }
+
+    #[test]
+    fn test_migrate_insurance_contract_in_place() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut system_acc = SolanaAccount::default();
+        let mut config_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 11223344);
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+        let legacy_data = InsuranceContractDataV2 {
+            account_type: AccountType::InsuranceContract,
+            version: 2,
+            is_initialized: true,
+            is_closed: false,
+            insurance_contract_id: 11223344,
+            authority: insurance_contract_owner_key,
+        }
+        .try_to_vec()
+        .unwrap();
+        insurance_contract_data_acc.data[..legacy_data.len()].copy_from_slice(&legacy_data);
+
+        let instruction = crate::instruction::migrate_insurance_contract(
+            &program_id,
+            &insurance_contract_owner_key,
+            &insurance_contract_data_key,
+            11223344,
+        )
+        .unwrap();
+        let rent_key = sysvar::rent::id();
+        let system_key = Pubkey::default();
+        let (config_key, _) = find_config_address(&program_id);
+        let insurance_contract_data_info = (
+            &insurance_contract_data_key,
+            false,
+            &mut insurance_contract_data_acc,
+        )
+            .into_account_info();
+        let account_infos = vec![
+            (
+                &insurance_contract_owner_key,
+                true,
+                &mut insurance_contract_owner_acc,
+            )
+                .into_account_info(),
+            insurance_contract_data_info.clone(),
+            insurance_contract_data_info,
+            (&rent_key, false, &mut rent_acc).into_account_info(),
+            (&system_key, false, &mut system_acc).into_account_info(),
+            (&config_key, false, &mut config_acc).into_account_info(),
+        ];
+        Processor::process(&program_id, &account_infos, &instruction.data).unwrap();
+        drop(account_infos);
+
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(
+            insurance_contract_data.version,
+            state::INSURANCE_CONTRACT_VERSION
+        );
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Active
+        );
+        assert_eq!(insurance_contract_data.insurance_contract_id, 11223344);
+        assert_eq!(
+            insurance_contract_data.authority,
+            insurance_contract_owner_key
+        );
+        assert_eq!(
+            insurance_contract_data_acc.lamports,
+            insurance_contract_minimum_balance()
+        );
+    }
+
+    #[test]
+    fn test_status_transitions() {
+        use InsuranceContractStatus::*;
+
+        let statuses = [Draft, Active, Suspended, Lapsed, Expired, Cancelled, Closed];
+        let allowed = [
+            (Draft, Active),
+            (Draft, Cancelled),
+            (Draft, Closed),
+            (Active, Suspended),
+            (Active, Lapsed),
+            (Active, Expired),
+            (Active, Cancelled),
+            (Active, Closed),
+            (Suspended, Active),
+            (Suspended, Expired),
+            (Suspended, Cancelled),
+            (Suspended, Closed),
+            (Lapsed, Active),
+            (Lapsed, Expired),
+            (Lapsed, Cancelled),
+            (Lapsed, Closed),
+            (Expired, Closed),
+            (Cancelled, Closed),
+        ];
+
+        for from in statuses.iter() {
+            for to in statuses.iter() {
+                let expected = if allowed.contains(&(*from, *to)) {
+                    Ok(())
+                } else if *from == Closed {
+                    Err(InsuranceContractError::AlreadyClosed.into())
+                } else if from == to {
+                    Err(InsuranceContractError::StatusUnchanged.into())
+                } else {
+                    Err(InsuranceContractError::InvalidStatusTransition.into())
+                };
+                assert_eq!(
+                    Processor::check_status_transition(*from, *to),
+                    expected,
+                    "{:?} -> {:?}",
+                    from,
+                    to
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_suspend_reinstate_cancel_insurance_contract() {
+        let program_id = crate::id();
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 11223344);
+        let mut insurance_contract_data_acc = do_save_insurance_contract(
+            &insurance_contract_owner_key,
+            11223344,
+            PolicyTerms {
+                premium_amount: 0,
+                ..test_terms()
+            },
+        );
+        let status = |insurance_contract_data_acc: &SolanaAccount| {
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data)
+                .unwrap()
+                .status
+        };
+
+        // BadCase: Active contract can't be reinstated
+        assert_eq!(
+            Err(InsuranceContractError::StatusUnchanged.into()),
+            do_process(
+                crate::instruction::reinstate_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&test_clock()),
+                ],
+            )
+        );
+
+        // BadCase: Authority mismatch
+        let attacker_key = Pubkey::new_unique();
+        let mut attacker_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                crate::instruction::suspend_insurance_contract(
+                    &program_id,
+                    &attacker_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut attacker_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&test_clock()),
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::suspend_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut create_account_for_test(&test_clock()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            status(&insurance_contract_data_acc),
+            InsuranceContractStatus::Suspended
+        );
+
+        do_process(
+            crate::instruction::reinstate_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut create_account_for_test(&test_clock()),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            status(&insurance_contract_data_acc),
+            InsuranceContractStatus::Active
+        );
+
+        // Nothing to refund without a premium
+        do_process(
+            crate::instruction::cancel_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                1,
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut create_account_for_test(&test_clock()),
+                &mut SolanaAccount::default(),
+                &mut policy_token_account(
+                    &insurance_contract_data_key,
+                    &insurance_contract_owner_key,
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            status(&insurance_contract_data_acc),
+            InsuranceContractStatus::Cancelled
+        );
+
+        // BadCase: Cancelled contract can't be suspended
+        assert_eq!(
+            Err(InsuranceContractError::InvalidStatusTransition.into()),
+            do_process(
+                crate::instruction::suspend_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&test_clock()),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_authority_handover() {
+        let program_id = crate::id();
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc = do_save_insurance_contract(
+            &insurance_contract_owner_key,
+            1,
+            PolicyTerms {
+                premium_amount: 0,
+                ..test_terms()
+            },
+        );
+        let new_authority_key = Pubkey::new_unique();
+        let mut new_authority_acc = SolanaAccount::default();
+        let other_key = Pubkey::new_unique();
+        let mut other_acc = SolanaAccount::default();
+
+        // BadCase: Nothing to accept
+        assert_eq!(
+            Err(InsuranceContractError::NoPendingAuthority.into()),
+            do_process(
+                crate::instruction::accept_authority(
+                    &program_id,
+                    &new_authority_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![&mut new_authority_acc, &mut insurance_contract_data_acc],
+            )
+        );
+
+        // BadCase: Nothing to cancel
+        assert_eq!(
+            Err(InsuranceContractError::NoPendingAuthority.into()),
+            do_process(
+                crate::instruction::cancel_authority_proposal(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                ],
+            )
+        );
+
+        // BadCase: Only the current authority can propose
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                crate::instruction::propose_authority(
+                    &program_id,
+                    &other_key,
+                    &insurance_contract_data_key,
+                    &other_key,
+                )
+                .unwrap(),
+                vec![&mut other_acc, &mut insurance_contract_data_acc],
+            )
+        );
+
+        do_process(
+            crate::instruction::propose_authority(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+                &new_authority_key,
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(
+            insurance_contract_data.authority,
+            insurance_contract_owner_key
+        );
+        assert_eq!(insurance_contract_data.pending_authority, new_authority_key);
+
+        // BadCase: Only the proposed authority can accept
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                crate::instruction::accept_authority(
+                    &program_id,
+                    &other_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![&mut other_acc, &mut insurance_contract_data_acc],
+            )
+        );
+
+        do_process(
+            crate::instruction::cancel_authority_proposal(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+            ],
+        )
+        .unwrap();
+
+        // BadCase: Cancelled proposal can't be accepted
+        assert_eq!(
+            Err(InsuranceContractError::NoPendingAuthority.into()),
+            do_process(
+                crate::instruction::accept_authority(
+                    &program_id,
+                    &new_authority_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![&mut new_authority_acc, &mut insurance_contract_data_acc],
+            )
+        );
+
+        do_process(
+            crate::instruction::propose_authority(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+                &new_authority_key,
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+            ],
+        )
+        .unwrap();
+        do_process(
+            crate::instruction::accept_authority(
+                &program_id,
+                &new_authority_key,
+                &insurance_contract_data_key,
+            )
+            .unwrap(),
+            vec![&mut new_authority_acc, &mut insurance_contract_data_acc],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.authority, new_authority_key);
+        assert_eq!(insurance_contract_data.pending_authority, Pubkey::default());
+
+        // BadCase: Previous authority lost control
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                crate::instruction::suspend_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &insurance_contract_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&test_clock()),
+                ],
+            )
+        );
+        do_process(
+            crate::instruction::suspend_insurance_contract(
+                &program_id,
+                &new_authority_key,
+                &insurance_contract_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut new_authority_acc,
+                &mut insurance_contract_data_acc,
+                &mut create_account_for_test(&test_clock()),
+            ],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assign_policy() {
+        let program_id = crate::id();
+        let mut clock_acc = create_account_for_test(&test_clock());
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc = do_save_insurance_contract(
+            &insurance_contract_owner_key,
+            1,
+            PolicyTerms {
+                premium_amount: 0,
+                assignment_consent_required: true,
+                ..test_terms()
+            },
+        );
+        let (policy_mint_key, _) =
+            find_policy_mint_address(&program_id, &insurance_contract_data_key);
+        let mut policy_mint_acc = SolanaAccount::default();
+        let mut token_program_acc = SolanaAccount::default();
+        let lender_key = Pubkey::new_unique();
+        let mut lender_acc = SolanaAccount::default();
+        let buyer_key = Pubkey::new_unique();
+        let mut buyer_acc = SolanaAccount::default();
+        let source_key = Pubkey::new_unique();
+        let destination_key = Pubkey::new_unique();
+        let assign_policy =
+            |holder_key: &Pubkey, new_holder_key: &Pubkey, authority_key: Option<&Pubkey>| {
+                crate::instruction::assign_policy(
+                    &program_id,
+                    holder_key,
+                    &insurance_contract_data_key,
+                    new_holder_key,
+                    Some((&source_key, &destination_key)),
+                    authority_key,
+                )
+                .unwrap()
+            };
+
+        // BadCase: Policy is assigned to its holder
+        assert_eq!(
+            Err(InsuranceContractError::InvalidAssignment.into()),
+            do_process(
+                assign_policy(
+                    &insurance_contract_owner_key,
+                    &insurance_contract_owner_key,
+                    Some(&insurance_contract_owner_key),
+                ),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut clock_acc,
+                    &mut policy_token_account(
+                        &insurance_contract_data_key,
+                        &insurance_contract_owner_key
+                    ),
+                    &mut token_account(&policy_mint_key, &insurance_contract_owner_key, 0),
+                    &mut policy_mint_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Policy token is moved to a token account of somebody else
+        assert_eq!(
+            Err(InsuranceContractError::InvalidAssignment.into()),
+            do_process(
+                assign_policy(
+                    &insurance_contract_owner_key,
+                    &lender_key,
+                    Some(&insurance_contract_owner_key),
+                ),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut clock_acc,
+                    &mut policy_token_account(
+                        &insurance_contract_data_key,
+                        &insurance_contract_owner_key
+                    ),
+                    &mut token_account(&policy_mint_key, &buyer_key, 0),
+                    &mut policy_mint_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // The authority holds the policy token until it is assigned
+        do_process(
+            assign_policy(
+                &insurance_contract_owner_key,
+                &lender_key,
+                Some(&insurance_contract_owner_key),
+            ),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut clock_acc,
+                &mut policy_token_account(
+                    &insurance_contract_data_key,
+                    &insurance_contract_owner_key,
+                ),
+                &mut token_account(&policy_mint_key, &lender_key, 0),
+                &mut policy_mint_acc,
+                &mut token_program_acc,
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.holder, Pubkey::default());
+        assert_eq!(
+            insurance_contract_data.authority,
+            insurance_contract_owner_key
+        );
+        assert_eq!(
+            insurance_contract_data.assignment_history(),
+            vec![state::Assignment {
+                previous_holder: insurance_contract_owner_key,
+                assigned_at: test_clock().unix_timestamp,
+            }]
+        );
+
+        // BadCase: Policy is assigned by somebody else
+        assert_eq!(
+            Err(InsuranceContractError::HolderMismatch.into()),
+            do_process(
+                assign_policy(
+                    &insurance_contract_owner_key,
+                    &buyer_key,
+                    Some(&insurance_contract_owner_key),
+                ),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut clock_acc,
+                    &mut token_account(&policy_mint_key, &insurance_contract_owner_key, 0),
+                    &mut token_account(&policy_mint_key, &buyer_key, 0),
+                    &mut policy_mint_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Consent of the authority is missing
+        let mut lender_token_acc = policy_token_account(&insurance_contract_data_key, &lender_key);
+        let mut buyer_token_acc = token_account(&policy_mint_key, &buyer_key, 0);
+        assert_eq!(
+            Err(ProgramError::NotEnoughAccountKeys),
+            do_process(
+                assign_policy(&lender_key, &buyer_key, None),
+                vec![
+                    &mut lender_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut clock_acc,
+                    &mut lender_token_acc,
+                    &mut buyer_token_acc,
+                    &mut policy_mint_acc,
+                    &mut token_program_acc,
+                ],
+            )
+        );
+        let mut instruction =
+            assign_policy(&lender_key, &buyer_key, Some(&insurance_contract_owner_key));
+        instruction.accounts[7].is_signer = false;
+        assert_eq!(
+            Err(ProgramError::MissingRequiredSignature),
+            do_process(
+                instruction,
+                vec![
+                    &mut lender_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut clock_acc,
+                    &mut lender_token_acc,
+                    &mut buyer_token_acc,
+                    &mut policy_mint_acc,
+                    &mut token_program_acc,
+                    &mut insurance_contract_owner_acc,
+                ],
+            )
+        );
+
+        // BadCase: Consent is given by somebody else
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                assign_policy(&lender_key, &buyer_key, Some(&buyer_key)),
+                vec![
+                    &mut lender_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut clock_acc,
+                    &mut lender_token_acc,
+                    &mut buyer_token_acc,
+                    &mut policy_mint_acc,
+                    &mut token_program_acc,
+                    &mut buyer_acc,
+                ],
+            )
+        );
+
+        do_process(
+            assign_policy(&lender_key, &buyer_key, Some(&insurance_contract_owner_key)),
+            vec![
+                &mut lender_acc,
+                &mut insurance_contract_data_acc,
+                &mut clock_acc,
+                &mut lender_token_acc,
+                &mut buyer_token_acc,
+                &mut policy_mint_acc,
+                &mut token_program_acc,
+                &mut insurance_contract_owner_acc,
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.assignment_count, 2);
+        assert_eq!(
+            insurance_contract_data.assignment_history()[1].previous_holder,
+            lender_key
+        );
+    }
+
+    #[test]
+    fn test_amend_document() {
+        let program_id = crate::id();
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, test_terms());
+        let document_uri = DocumentUri::new("ipfs://wording").unwrap();
+        let amend_document = |insurance_contract_authority_key: &Pubkey,
+                              document_hash: [u8; 32],
+                              document_uri: DocumentUri| {
+            crate::instruction::amend_document(
+                &program_id,
+                insurance_contract_authority_key,
+                &insurance_contract_data_key,
+                document_hash,
+                document_uri,
+            )
+            .unwrap()
+        };
+
+        // BadCase: Document is amended by somebody else
+        let attacker_key = Pubkey::new_unique();
+        let mut attacker_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                amend_document(&attacker_key, [1; 32], document_uri),
+                vec![&mut attacker_acc, &mut insurance_contract_data_acc],
+            )
+        );
+
+        // BadCase: Hash is missing or the URI isn't padded
+        let mut unpadded_uri = document_uri;
+        unpadded_uri.0[state::MAX_DOCUMENT_URI_LEN - 1] = b'x';
+        for (document_hash, document_uri) in
+            [([0; 32], document_uri), ([1; 32], unpadded_uri)].iter()
+        {
+            assert_eq!(
+                Err(InsuranceContractError::InvalidDocument.into()),
+                do_process(
+                    amend_document(&insurance_contract_owner_key, *document_hash, *document_uri),
+                    vec![
+                        &mut insurance_contract_owner_acc,
+                        &mut insurance_contract_data_acc,
+                    ],
+                )
+            );
+        }
+
+        for document_hash in [[1; 32], [2; 32]].iter() {
+            do_process(
+                amend_document(&insurance_contract_owner_key, *document_hash, document_uri),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                ],
+            )
+            .unwrap();
+        }
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.document_hash, [2; 32]);
+        assert_eq!(
+            insurance_contract_data.document_uri.as_str(),
+            "ipfs://wording"
+        );
+        assert_eq!(insurance_contract_data.document_history(), vec![[1; 32]]);
+        assert_eq!(
+            Some(&InsuranceContractEvent::DocumentAmended(DocumentAmended {
+                insurance_contract: insurance_contract_data_key,
+                document_hash: [2; 32],
+                previous_document_hash: [1; 32],
+            })),
+            take_events().last()
+        );
+
+        // BadCase: Contract is already closed
+        let mut insurance_contract_data = insurance_contract_data;
+        insurance_contract_data.status = InsuranceContractStatus::Closed;
+        insurance_contract_data
+            .pack(&mut insurance_contract_data_acc.data)
+            .unwrap();
+        assert_eq!(
+            Err(InsuranceContractError::AlreadyClosed.into()),
+            do_process(
+                amend_document(&insurance_contract_owner_key, [3; 32], document_uri),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                ],
+            )
+        );
+    }
+
+    fn test_product_terms() -> ProductTerms {
+        let terms = test_terms();
+        ProductTerms {
+            coverage_period: terms.coverage_end - terms.coverage_start,
+            waiting_period: 2_000,
+            sum_insured: terms.sum_insured,
+            premium_amount: 0,
+            premium_mint: terms.premium_mint,
+            claims_adjuster: terms.claims_adjuster,
+            deductible: 1_000,
+            ..ProductTerms::default()
+        }
+    }
+
+    #[test]
+    fn test_insurance_product() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut clock_acc = create_account_for_test(&test_clock());
+        let mut system_acc = SolanaAccount::default();
+
+        let product_owner_key = Pubkey::new_unique();
+        let mut product_owner_acc = SolanaAccount::default();
+        let (product_key, _) = find_insurance_product_address(&program_id, &product_owner_key, 1);
+        let mut product_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(state::INSURANCE_PRODUCT_DATA_LEN),
+            state::INSURANCE_PRODUCT_DATA_LEN,
+            &program_id,
+        );
+        let other_key = Pubkey::new_unique();
+        let mut other_acc = SolanaAccount::default();
+
+        // BadCase: Address is not derived from the authority and the identifier
+        let mut instruction = crate::instruction::create_product(
+            &program_id,
+            &product_owner_key,
+            1,
+            test_product_terms(),
+        )
+        .unwrap();
+        instruction.accounts[1].pubkey = Pubkey::new_unique();
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            do_process(
+                instruction,
+                vec![
+                    &mut product_owner_acc,
+                    &mut product_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                ],
+            )
+        );
+
+        // BadCase: Waiting period outlasts the coverage
+        assert_eq!(
+            Err(InsuranceContractError::InvalidCoveragePeriod.into()),
+            do_process(
+                crate::instruction::create_product(
+                    &program_id,
+                    &product_owner_key,
+                    1,
+                    ProductTerms {
+                        waiting_period: test_product_terms().coverage_period,
+                        ..test_product_terms()
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut product_owner_acc,
+                    &mut product_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::create_product(
+                &program_id,
+                &product_owner_key,
+                1,
+                test_product_terms(),
+            )
+            .unwrap(),
+            vec![
+                &mut product_owner_acc,
+                &mut product_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+            ],
+        )
+        .unwrap();
+        let product_data = InsuranceProductData::unpack(&product_acc.data).unwrap();
+        assert_eq!(product_data.account_type, AccountType::Product);
+        assert_eq!(product_data.status, ProductStatus::Active);
+        assert_eq!(product_data.product_id, 1);
+        assert_eq!(product_data.authority, product_owner_key);
+        assert_eq!(product_data.terms, test_product_terms());
+
+        // BadCase: Product is created twice
+        assert_eq!(
+            Err(InsuranceContractError::AlreadyInitialized.into()),
+            do_process(
+                crate::instruction::create_product(
+                    &program_id,
+                    &product_owner_key,
+                    1,
+                    test_product_terms(),
+                )
+                .unwrap(),
+                vec![
+                    &mut product_owner_acc,
+                    &mut product_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                ],
+            )
+        );
+
+        // BadCase: Product is updated by somebody else
+        let updated_terms = ProductTerms {
+            deductible: 2_000,
+            ..test_product_terms()
+        };
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                crate::instruction::update_product(
+                    &program_id,
+                    &other_key,
+                    &product_key,
+                    updated_terms,
+                )
+                .unwrap(),
+                vec![&mut other_acc, &mut product_acc, &mut clock_acc],
+            )
+        );
+
+        do_process(
+            crate::instruction::update_product(
+                &program_id,
+                &product_owner_key,
+                &product_key,
+                updated_terms,
+            )
+            .unwrap(),
+            vec![&mut product_owner_acc, &mut product_acc, &mut clock_acc],
+        )
+        .unwrap();
+        let product_data = InsuranceProductData::unpack(&product_acc.data).unwrap();
+        assert_eq!(product_data.terms, updated_terms);
+
+        // Issuing from the product
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &product_owner_key, 1);
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+        let save_from_product = |authority_key: &Pubkey, product_terms: &ProductTerms| {
+            crate::instruction::save_insurance_contract_from_product(
+                &program_id,
+                authority_key,
+                1,
+                &product_key,
+                product_terms,
+                1_000,
+            )
+            .unwrap()
+        };
+
+        // BadCase: Terms differ from the product ones
+        assert_eq!(
+            Err(InsuranceContractError::ProductTermsMismatch.into()),
+            do_process(
+                save_from_product(&product_owner_key, &test_product_terms()),
+                vec![
+                    &mut product_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut product_acc,
+                ],
+            )
+        );
+
+        // BadCase: Contract is issued by somebody else
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                save_from_product(&other_key, &updated_terms),
+                vec![
+                    &mut other_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut product_acc,
+                ],
+            )
+        );
+
+        do_process(
+            save_from_product(&product_owner_key, &updated_terms),
+            vec![
+                &mut product_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut product_acc,
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.product, product_key);
+        assert_eq!(
+            insurance_contract_data.terms(),
+            updated_terms.policy_terms(1_000)
+        );
+        let product_data = InsuranceProductData::unpack(&product_acc.data).unwrap();
+        assert_eq!(product_data.policy_count, 1);
+
+        // BadCase: Incident happened within the waiting period
+        assert_eq!(
+            Err(InsuranceContractError::InvalidIncidentTime.into()),
+            do_process(
+                crate::instruction::submit_claim(
+                    &program_id,
+                    &product_owner_key,
+                    &insurance_contract_data_key,
+                    0,
+                    5_000,
+                    2_000,
+                    [5; 32],
+                    NO_PERIL,
+                    &Pubkey::new_unique(),
+                )
+                .unwrap(),
+                vec![
+                    &mut product_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut SolanaAccount::new(
+                        claim_minimum_balance(),
+                        state::CLAIM_DATA_LEN,
+                        &program_id,
+                    ),
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut policy_token_account(&insurance_contract_data_key, &product_owner_key),
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::retire_product(&program_id, &product_owner_key, &product_key)
+                .unwrap(),
+            vec![&mut product_owner_acc, &mut product_acc],
+        )
+        .unwrap();
+        let product_data = InsuranceProductData::unpack(&product_acc.data).unwrap();
+        assert_eq!(product_data.status, ProductStatus::Retired);
+
+        // BadCase: Retired product is retired again, updated or issued from
+        assert_eq!(
+            Err(InsuranceContractError::ProductRetired.into()),
+            do_process(
+                crate::instruction::retire_product(&program_id, &product_owner_key, &product_key)
+                    .unwrap(),
+                vec![&mut product_owner_acc, &mut product_acc],
+            )
+        );
+        assert_eq!(
+            Err(InsuranceContractError::ProductRetired.into()),
+            do_process(
+                crate::instruction::update_product(
+                    &program_id,
+                    &product_owner_key,
+                    &product_key,
+                    updated_terms,
+                )
+                .unwrap(),
+                vec![&mut product_owner_acc, &mut product_acc, &mut clock_acc],
+            )
+        );
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+        assert_eq!(
+            Err(InsuranceContractError::ProductRetired.into()),
+            do_process(
+                save_from_product(&product_owner_key, &updated_terms),
+                vec![
+                    &mut product_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut product_acc,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_cancel_insurance_contract() {
+        let program_id = crate::id();
+        let mut clock_acc = create_account_for_test(&test_clock());
+        let mut token_program_acc = SolanaAccount::default();
+        let terms = test_terms();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, terms);
+        let mut insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        insurance_contract_data.status = InsuranceContractStatus::Active;
+        insurance_contract_data.premium_paid = terms.premium_amount;
+        insurance_contract_data
+            .pack(&mut insurance_contract_data_acc.data)
+            .unwrap();
+        let (vault_key, _) = find_vault_address(&program_id, &insurance_contract_data_key);
+        let mut vault_acc = token_account(&terms.premium_mint, &vault_key, terms.premium_amount);
+        let mut refund_acc = token_account(&terms.premium_mint, &insurance_contract_owner_key, 0);
+        let mut policy_token_acc =
+            policy_token_account(&insurance_contract_data_key, &insurance_contract_owner_key);
+        let cancel = |reason: u8| {
+            crate::instruction::cancel_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                reason,
+            )
+            .unwrap()
+        };
+
+        // BadCase: Refund is sent to someone else
+        let mut other_refund_acc = token_account(&terms.premium_mint, &Pubkey::new_unique(), 0);
+        assert_eq!(
+            Err(InsuranceContractError::BeneficiaryMismatch.into()),
+            do_process(
+                cancel(1),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut other_refund_acc,
+                    &mut clock_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Refund token account holds another currency
+        let mut other_mint_refund_acc =
+            token_account(&Pubkey::new_unique(), &insurance_contract_owner_key, 0);
+        assert_eq!(
+            Err(InsuranceContractError::InvalidMint.into()),
+            do_process(
+                cancel(1),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut other_mint_refund_acc,
+                    &mut clock_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Policy token was assigned to somebody else
+        assert_eq!(
+            Err(InsuranceContractError::BeneficiaryMismatch.into()),
+            do_process(
+                cancel(1),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut refund_acc,
+                    &mut clock_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_account(&insurance_contract_data_key, &Pubkey::new_unique()),
+                ],
+            )
+        );
+
+        do_process(
+            cancel(7),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut vault_acc,
+                &mut refund_acc,
+                &mut clock_acc,
+                &mut token_program_acc,
+                &mut policy_token_acc,
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Cancelled
+        );
+        assert_eq!(insurance_contract_data.cancellation_reason, 7);
+        // 4_000 seconds of a year are earned, 10% of the rest is kept as a penalty
+        assert_eq!(insurance_contract_data.premium_refunded, 8_999);
+
+        // BadCase: Contract is already cancelled
+        assert_eq!(
+            Err(InsuranceContractError::StatusUnchanged.into()),
+            do_process(
+                cancel(7),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut refund_acc,
+                    &mut clock_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_pay_premium() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut system_acc = SolanaAccount::default();
+        let mut token_program_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 11223344);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 11223344, test_terms());
+        let premium_mint_key = test_terms().premium_mint;
+        let mut premium_mint_acc = SolanaAccount::default();
+        let premium_payer_key = Pubkey::new_unique();
+        let mut premium_payer_acc = SolanaAccount::default();
+        let premium_payer_token_key = Pubkey::new_unique();
+        let mut premium_payer_token_acc = SolanaAccount::default();
+        let mut vault_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN,
+            &spl_token::id(),
+        );
+        let pay_premium = |premium_mint_key: &Pubkey, amount: u64| {
+            crate::instruction::pay_premium(
+                &program_id,
+                &premium_payer_key,
+                &premium_payer_token_key,
+                &insurance_contract_data_key,
+                premium_mint_key,
+                amount,
+                &Pubkey::new_unique(),
+            )
+            .unwrap()
+        };
+
+        // BadCase: Premium mint mismatch
+        assert_eq!(
+            Err(InsuranceContractError::InvalidMint.into()),
+            do_process(
+                pay_premium(&Pubkey::new_unique(), 4_000),
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Nothing is paid
+        assert_eq!(
+            Err(InsuranceContractError::InvalidPremiumAmount.into()),
+            do_process(
+                pay_premium(&premium_mint_key, 0),
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // BadCase: Vault address is not derived from the contract
+        let mut instruction = pay_premium(&premium_mint_key, 4_000);
+        instruction.accounts[3].pubkey = Pubkey::new_unique();
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            do_process(
+                instruction,
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        do_process(
+            pay_premium(&premium_mint_key, 4_000),
+            vec![
+                &mut premium_payer_acc,
+                &mut premium_payer_token_acc,
+                &mut insurance_contract_data_acc,
+                &mut vault_acc,
+                &mut premium_mint_acc,
+                &mut rent_acc,
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.premium_paid, 4_000);
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Draft
+        );
+        assert_eq!(
+            Some(&InsuranceContractEvent::PremiumPaid(PremiumPaid {
+                insurance_contract: insurance_contract_data_key,
+                payer: premium_payer_key,
+                amount: 4_000,
+                protocol_fee: 0,
+                premium_paid: 4_000,
+            })),
+            take_events().last()
+        );
+
+        // BadCase: Premium is overpaid
+        assert_eq!(
+            Err(InsuranceContractError::InvalidPremiumAmount.into()),
+            do_process(
+                pay_premium(&premium_mint_key, 6_001),
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        do_process(
+            pay_premium(&premium_mint_key, 6_000),
+            vec![
+                &mut premium_payer_acc,
+                &mut premium_payer_token_acc,
+                &mut insurance_contract_data_acc,
+                &mut vault_acc,
+                &mut premium_mint_acc,
+                &mut rent_acc,
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.premium_paid, 10_000);
+        assert_eq!(
+            insurance_contract_data.status,
+            InsuranceContractStatus::Active
+        );
+        assert_eq!(
+            vec![
+                InsuranceContractEvent::PremiumPaid(PremiumPaid {
+                    insurance_contract: insurance_contract_data_key,
+                    payer: premium_payer_key,
+                    amount: 6_000,
+                    protocol_fee: 0,
+                    premium_paid: 10_000,
+                }),
+                InsuranceContractEvent::ContractStatusChanged(ContractStatusChanged {
+                    insurance_contract: insurance_contract_data_key,
+                    status: InsuranceContractStatus::Active,
+                }),
+            ],
+            take_events()
+        );
+
+        // BadCase: Premium is already paid
+        assert_eq!(
+            Err(InsuranceContractError::InvalidContractStatus.into()),
+            do_process(
+                pay_premium(&premium_mint_key, 1),
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_program_config() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut system_acc = SolanaAccount::default();
+        let mut token_program_acc = SolanaAccount::default();
+
+        let admin_key = Pubkey::new_unique();
+        let mut admin_acc = SolanaAccount::default();
+        let mut program_data_acc = program_data_account(&admin_key);
+        let mut config_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(state::CONFIG_DATA_LEN),
+            state::CONFIG_DATA_LEN,
+            &program_id,
+        );
+        let other_key = Pubkey::new_unique();
+        let mut other_acc = SolanaAccount::default();
+        let premium_mint_key = test_terms().premium_mint;
+        let treasury_key = Pubkey::new_unique();
+        let mut allowed_premium_mints = [Pubkey::default(); state::MAX_ALLOWED_MINTS];
+        allowed_premium_mints[0] = premium_mint_key;
+        let settings = ConfigSettings {
+            protocol_fee_bps: 100,
+            treasury: treasury_key,
+            allowed_premium_mints,
+            guardian: Pubkey::new_unique(),
+        };
+
+        // BadCase: Protocol fee is paid to nobody
+        assert_eq!(
+            Err(InsuranceContractError::InvalidProtocolFee.into()),
+            do_process(
+                crate::instruction::initialize_config(
+                    &program_id,
+                    &admin_key,
+                    ConfigSettings {
+                        treasury: Pubkey::default(),
+                        ..settings
+                    },
+                )
+                .unwrap(),
+                vec![
+                    &mut admin_acc,
+                    &mut config_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut program_data_acc,
+                ],
+            )
+        );
+
+        // BadCase: Config is initialized by somebody else than the upgrade authority
+        assert_eq!(
+            Err(InsuranceContractError::AdminMismatch.into()),
+            do_process(
+                crate::instruction::initialize_config(&program_id, &other_key, settings).unwrap(),
+                vec![
+                    &mut other_acc,
+                    &mut config_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut program_data_acc,
+                ],
+            )
+        );
+
+        // BadCase: Program data account is not the one of the program
+        let mut instruction =
+            crate::instruction::initialize_config(&program_id, &other_key, settings).unwrap();
+        instruction.accounts[4].pubkey = Pubkey::new_unique();
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            do_process(
+                instruction,
+                vec![
+                    &mut other_acc,
+                    &mut config_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut program_data_account(&other_key),
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::initialize_config(&program_id, &admin_key, settings).unwrap(),
+            vec![
+                &mut admin_acc,
+                &mut config_acc,
+                &mut rent_acc,
+                &mut system_acc,
+                &mut program_data_acc,
+            ],
+        )
+        .unwrap();
+        let config_data = ConfigData::unpack(&config_acc.data).unwrap();
+        assert_eq!(config_data.account_type, AccountType::Config);
+        assert_eq!(config_data.admin, admin_key);
+        assert_eq!(config_data.settings, settings);
+
+        // BadCase: Config is initialized twice
+        assert_eq!(
+            Err(InsuranceContractError::AlreadyInitialized.into()),
+            do_process(
+                crate::instruction::initialize_config(&program_id, &admin_key, settings).unwrap(),
+                vec![
+                    &mut admin_acc,
+                    &mut config_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut program_data_acc,
+                ],
+            )
+        );
+
+        // BadCase: Config is updated by somebody else than the admin
+        let updated_settings = ConfigSettings {
+            protocol_fee_bps: 250,
+            ..settings
+        };
+        assert_eq!(
+            Err(InsuranceContractError::AdminMismatch.into()),
+            do_process(
+                crate::instruction::update_config(&program_id, &other_key, updated_settings)
+                    .unwrap(),
+                vec![&mut other_acc, &mut config_acc],
+            )
+        );
+
+        // BadCase: Protocol fee exceeds the maximum
+        assert_eq!(
+            Err(InsuranceContractError::InvalidProtocolFee.into()),
+            do_process(
+                crate::instruction::update_config(
+                    &program_id,
+                    &admin_key,
+                    ConfigSettings {
+                        protocol_fee_bps: state::MAX_PROTOCOL_FEE_BPS + 1,
+                        ..settings
+                    },
+                )
+                .unwrap(),
+                vec![&mut admin_acc, &mut config_acc],
+            )
+        );
+
+        do_process(
+            crate::instruction::update_config(&program_id, &admin_key, updated_settings).unwrap(),
+            vec![&mut admin_acc, &mut config_acc],
+        )
+        .unwrap();
+        let config_data = ConfigData::unpack(&config_acc.data).unwrap();
+        assert_eq!(config_data.settings, updated_settings);
+
+        // Premiums pay the protocol fee to the treasury
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, test_terms());
+        let premium_payer_key = Pubkey::new_unique();
+        let mut premium_payer_acc = SolanaAccount::default();
+        let mut premium_payer_token_acc = SolanaAccount::default();
+        let mut vault_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN,
+            &spl_token::id(),
+        );
+        let mut premium_mint_acc = SolanaAccount::default();
+        let pay_premium = |premium_mint_key: &Pubkey| {
+            crate::instruction::pay_premium(
+                &program_id,
+                &premium_payer_key,
+                &Pubkey::new_unique(),
+                &insurance_contract_data_key,
+                premium_mint_key,
+                4_000,
+                &Pubkey::new_unique(),
+            )
+            .unwrap()
+        };
+
+        // BadCase: Premium is paid in a mint that isn't allowed
+        let other_mint_key = Pubkey::new_unique();
+        let mut other_contract_acc = do_save_insurance_contract(
+            &insurance_contract_owner_key,
+            1,
+            PolicyTerms {
+                premium_mint: other_mint_key,
+                ..test_terms()
+            },
+        );
+        assert_eq!(
+            Err(InsuranceContractError::PremiumMintNotAllowed.into()),
+            do_process(
+                pay_premium(&other_mint_key),
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut other_contract_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut config_acc,
+                    &mut token_account(&other_mint_key, &treasury_key, 0),
+                ],
+            )
+        );
+
+        // BadCase: Fee is paid to somebody else than the treasury
+        assert_eq!(
+            Err(InsuranceContractError::TreasuryMismatch.into()),
+            do_process(
+                pay_premium(&premium_mint_key),
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut config_acc,
+                    &mut token_account(&premium_mint_key, &other_key, 0),
+                ],
+            )
+        );
+
+        // BadCase: Config account is not the derived one
+        let mut instruction = pay_premium(&premium_mint_key);
+        instruction.accounts[8].pubkey = Pubkey::new_unique();
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            do_process(
+                instruction,
+                vec![
+                    &mut premium_payer_acc,
+                    &mut premium_payer_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut config_acc,
+                    &mut token_account(&premium_mint_key, &treasury_key, 0),
+                ],
+            )
+        );
+
+        do_process(
+            pay_premium(&premium_mint_key),
+            vec![
+                &mut premium_payer_acc,
+                &mut premium_payer_token_acc,
+                &mut insurance_contract_data_acc,
+                &mut vault_acc,
+                &mut premium_mint_acc,
+                &mut rent_acc,
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut config_acc,
+                &mut token_account(&premium_mint_key, &treasury_key, 0),
+            ],
+        )
+        .unwrap();
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.premium_paid, 4_000);
+        assert_eq!(insurance_contract_data.protocol_fees_paid, 100);
+    }
+
+    #[test]
+    fn test_pause() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut clock_acc = create_account_for_test(&test_clock());
+        let mut system_acc = SolanaAccount::default();
+
+        let admin_key = Pubkey::new_unique();
+        let mut admin_acc = SolanaAccount::default();
+        let mut program_data_acc = program_data_account(&admin_key);
+        let guardian_key = Pubkey::new_unique();
+        let mut guardian_acc = SolanaAccount::default();
+        let mut config_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(state::CONFIG_DATA_LEN),
+            state::CONFIG_DATA_LEN,
+            &program_id,
+        );
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc = do_save_insurance_contract(
+            &insurance_contract_owner_key,
+            1,
+            PolicyTerms {
+                premium_amount: 0,
+                ..test_terms()
+            },
+        );
+
+        // BadCase: Config is not initialized
+        assert_eq!(
+            Err(InsuranceContractError::NotInitialized.into()),
+            do_process(
+                crate::instruction::pause(&program_id, &guardian_key, None).unwrap(),
+                vec![&mut guardian_acc, &mut SolanaAccount::default()],
+            )
+        );
+
+        do_process(
+            crate::instruction::initialize_config(
+                &program_id,
+                &admin_key,
+                ConfigSettings {
+                    guardian: guardian_key,
+                    ..ConfigSettings::default()
+                },
+            )
+            .unwrap(),
+            vec![
+                &mut admin_acc,
+                &mut config_acc,
+                &mut rent_acc,
+                &mut system_acc,
+                &mut program_data_acc,
+            ],
+        )
+        .unwrap();
+
+        // BadCase: Program is paused by the admin
+        assert_eq!(
+            Err(InsuranceContractError::GuardianMismatch.into()),
+            do_process(
+                crate::instruction::pause(&program_id, &admin_key, None).unwrap(),
+                vec![&mut admin_acc, &mut config_acc],
+            )
+        );
+
+        do_process(
+            crate::instruction::pause(&program_id, &guardian_key, None).unwrap(),
+            vec![&mut guardian_acc, &mut config_acc],
+        )
+        .unwrap();
+        assert!(ConfigData::unpack(&config_acc.data).unwrap().paused);
+        assert_eq!(
+            Some(&InsuranceContractEvent::PauseChanged(PauseChanged {
+                account: find_config_address(&program_id).0,
+                paused: true,
+            })),
+            take_events().last()
+        );
+
+        // BadCase: New business is stopped
+        let suspend = || {
+            crate::instruction::suspend_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+            )
+            .unwrap()
+        };
+        assert_eq!(
+            Err(InsuranceContractError::ProgramPaused.into()),
+            do_process(
+                suspend(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&test_clock()),
+                    &mut config_acc,
+                ],
+            )
+        );
+
+        // BadCase: Config account is left out
+        let mut instruction = suspend();
+        instruction.accounts.pop();
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            do_process(
+                instruction,
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&test_clock()),
+                ],
+            )
+        );
+
+        // BadCase: Premium payment reuses its config account for the pause check
+        let pay_premium = crate::instruction::pay_premium(
+            &program_id,
+            &insurance_contract_owner_key,
+            &Pubkey::new_unique(),
+            &insurance_contract_data_key,
+            &test_terms().premium_mint,
+            1,
+            &Pubkey::new_unique(),
+        )
+        .unwrap();
+        let (config_key, _) = find_config_address(&program_id);
+        assert_eq!(
+            1,
+            pay_premium
+                .accounts
+                .iter()
+                .filter(|account_meta| account_meta.pubkey == config_key)
+                .count()
+        );
+        let mut unused_accs = vec![SolanaAccount::default(); 8];
+        let mut accounts = unused_accs.iter_mut().collect::<Vec<_>>();
+        accounts.push(&mut config_acc);
+        assert_eq!(
+            Err(InsuranceContractError::ProgramPaused.into()),
+            do_process(pay_premium, accounts)
+        );
+
+        // Administration goes on
+        assert!(!InsuranceContractInstruction::PayClaim.is_pausable());
+        assert!(!InsuranceContractInstruction::CancelInsuranceContract { reason: 0 }.is_pausable());
+        do_process(
+            crate::instruction::unpause(&program_id, &guardian_key, None).unwrap(),
+            vec![&mut guardian_acc, &mut config_acc],
+        )
+        .unwrap();
+        assert!(!ConfigData::unpack(&config_acc.data).unwrap().paused);
+        do_process(
+            suspend(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut create_account_for_test(&test_clock()),
+                &mut config_acc,
+            ],
+        )
+        .unwrap();
+
+        // Paused product stops issuance from it only
+        let (product_key, _) =
+            find_insurance_product_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut product_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(state::INSURANCE_PRODUCT_DATA_LEN),
+            state::INSURANCE_PRODUCT_DATA_LEN,
+            &program_id,
+        );
+        do_process(
+            crate::instruction::create_product(
+                &program_id,
+                &insurance_contract_owner_key,
+                1,
+                test_product_terms(),
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut product_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut config_acc,
+            ],
+        )
+        .unwrap();
+        do_process(
+            crate::instruction::pause(&program_id, &guardian_key, Some(&product_key)).unwrap(),
+            vec![&mut guardian_acc, &mut config_acc, &mut product_acc],
+        )
+        .unwrap();
+        assert!(
+            InsuranceProductData::unpack(&product_acc.data)
+                .unwrap()
+                .paused
+        );
+        assert!(!ConfigData::unpack(&config_acc.data).unwrap().paused);
+
+        let mut insurance_contract_data_acc = SolanaAccount::new(
+            insurance_contract_minimum_balance(),
+            state::INSURANCE_CONTRACT_DATA_LEN,
+            &program_id,
+        );
+        assert_eq!(
+            Err(InsuranceContractError::ProgramPaused.into()),
+            do_process(
+                crate::instruction::save_insurance_contract_from_product(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    2,
+                    &product_key,
+                    &test_product_terms(),
+                    1_000,
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut product_acc,
+                    &mut config_acc,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_submit_claim() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut clock_acc = create_account_for_test(&test_clock());
+        let mut system_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc = do_save_insurance_contract(
+            &insurance_contract_owner_key,
+            1,
+            PolicyTerms {
+                premium_amount: 0,
+                perils: [
+                    Peril {
+                        code: 1,
+                        sub_limit: 100_000,
+                    },
+                    Peril {
+                        code: 2,
+                        sub_limit: 0,
+                    },
+                    Peril::default(),
+                    Peril::default(),
+                    Peril::default(),
+                    Peril::default(),
+                ],
+                ..test_terms()
+            },
+        );
+        let mut claim_data_acc =
+            SolanaAccount::new(claim_minimum_balance(), state::CLAIM_DATA_LEN, &program_id);
+        let mut policy_token_acc =
+            policy_token_account(&insurance_contract_data_key, &insurance_contract_owner_key);
+        let submit_claim =
+            |policyholder_key: &Pubkey, amount: u64, incident_time: i64, peril: u16| {
+                crate::instruction::submit_claim(
+                    &program_id,
+                    policyholder_key,
+                    &insurance_contract_data_key,
+                    0,
+                    amount,
+                    incident_time,
+                    [5; 32],
+                    peril,
+                    &Pubkey::new_unique(),
+                )
+                .unwrap()
+            };
+
+        // BadCase: Claim is filed by somebody else
+        let attacker_key = Pubkey::new_unique();
+        let mut attacker_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::HolderMismatch.into()),
+            do_process(
+                submit_claim(&attacker_key, 5_000, 2_000, 1),
+                vec![
+                    &mut attacker_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Policy token of another InsuranceContract is presented
+        assert_eq!(
+            Err(InsuranceContractError::HolderMismatch.into()),
+            do_process(
+                submit_claim(&attacker_key, 5_000, 2_000, 1),
+                vec![
+                    &mut attacker_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut token_account(&Pubkey::new_unique(), &attacker_key, 1),
+                ],
+            )
+        );
+
+        // BadCase: Incident happened before the coverage started or in the future
+        for incident_time in [500, 6_000].iter() {
+            assert_eq!(
+                Err(InsuranceContractError::InvalidIncidentTime.into()),
+                do_process(
+                    submit_claim(&insurance_contract_owner_key, 5_000, *incident_time, 1),
+                    vec![
+                        &mut insurance_contract_owner_acc,
+                        &mut insurance_contract_data_acc,
+                        &mut claim_data_acc,
+                        &mut rent_acc,
+                        &mut clock_acc,
+                        &mut system_acc,
+                        &mut policy_token_acc,
+                    ],
+                )
+            );
+        }
+
+        // BadCase: Incident happened while the coverage was suspended
+        let suspend = crate::instruction::suspend_insurance_contract(
+            &program_id,
+            &insurance_contract_owner_key,
+            &insurance_contract_data_key,
+        )
+        .unwrap();
+        let reinstate = crate::instruction::reinstate_insurance_contract(
+            &program_id,
+            &insurance_contract_owner_key,
+            &insurance_contract_data_key,
+        )
+        .unwrap();
+        for (instruction, unix_timestamp) in vec![(suspend, 2_500), (reinstate, 3_000)] {
+            do_process(
+                instruction,
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&Clock {
+                        unix_timestamp,
+                        ..Clock::default()
+                    }),
+                ],
+            )
+            .unwrap();
+        }
+        assert_eq!(
+            Err(InsuranceContractError::InvalidIncidentTime.into()),
+            do_process(
+                submit_claim(&insurance_contract_owner_key, 5_000, 2_700, 1),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Claim exceeds the sum insured
+        assert_eq!(
+            Err(InsuranceContractError::InvalidClaimAmount.into()),
+            do_process(
+                submit_claim(
+                    &insurance_contract_owner_key,
+                    test_terms().sum_insured + 1,
+                    2_000,
+                    1
+                ),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Peril is not covered, all-risk claims are rejected too
+        for peril in [NO_PERIL, 3].iter() {
+            assert_eq!(
+                Err(InsuranceContractError::UncoveredPeril.into()),
+                do_process(
+                    submit_claim(&insurance_contract_owner_key, 5_000, 2_000, *peril),
+                    vec![
+                        &mut insurance_contract_owner_acc,
+                        &mut insurance_contract_data_acc,
+                        &mut claim_data_acc,
+                        &mut rent_acc,
+                        &mut clock_acc,
+                        &mut system_acc,
+                        &mut policy_token_acc,
+                    ],
+                )
+            );
+        }
+
+        // BadCase: Address is not derived from the next claim index
+        let mut instruction = submit_claim(&insurance_contract_owner_key, 5_000, 2_000, 1);
+        instruction.accounts[2].pubkey =
+            find_claim_address(&program_id, &insurance_contract_data_key, 1).0;
+        assert_eq!(
+            Err(ProgramError::InvalidSeeds),
+            do_process(
+                instruction,
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        do_process(
+            submit_claim(&insurance_contract_owner_key, 5_000, 2_000, 1),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut claim_data_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut policy_token_acc,
+            ],
+        )
+        .unwrap();
+
+        let claim_data = ClaimData::unpack(&claim_data_acc.data).unwrap();
+        assert_eq!(claim_data.account_type, AccountType::Claim);
+        assert_eq!(claim_data.status, ClaimStatus::Submitted);
+        assert_eq!(claim_data.insurance_contract, insurance_contract_data_key);
+        assert_eq!(claim_data.claim_index, 0);
+        assert_eq!(claim_data.claimant, insurance_contract_owner_key);
+        assert_eq!(claim_data.amount, 5_000);
+        assert_eq!(claim_data.incident_time, 2_000);
+        assert_eq!(claim_data.submitted_at, test_clock().unix_timestamp);
+        assert_eq!(claim_data.evidence_hash, [5; 32]);
+        assert_eq!(claim_data.peril, 1);
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.claim_count, 1);
+        assert_eq!(
+            Some(&InsuranceContractEvent::ClaimSubmitted(ClaimSubmitted {
+                insurance_contract: insurance_contract_data_key,
+                claim: find_claim_address(&program_id, &insurance_contract_data_key, 0).0,
+                claim_index: 0,
+                amount: 5_000,
+                peril: 1,
+            })),
+            take_events().last()
+        );
+
+        // BadCase: Premium is not paid
+        let (draft_insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 2);
+        let mut draft_insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 2, test_terms());
+        assert_eq!(
+            Err(InsuranceContractError::InvalidContractStatus.into()),
+            do_process(
+                crate::instruction::submit_claim(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &draft_insurance_contract_data_key,
+                    0,
+                    5_000,
+                    2_000,
+                    [5; 32],
+                    NO_PERIL,
+                    &Pubkey::new_unique(),
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut draft_insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut policy_token_account(
+                        &draft_insurance_contract_data_key,
+                        &insurance_contract_owner_key,
+                    ),
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_approve_reject_claim() {
+        let program_id = crate::id();
+        let claims_adjuster_key = test_terms().claims_adjuster;
+        let mut claims_adjuster_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let terms = PolicyTerms {
+            premium_amount: 0,
+            ..test_terms()
+        };
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, terms);
+        let (first_claim_data_key, _) =
+            find_claim_address(&program_id, &insurance_contract_data_key, 0);
+        let mut first_claim_data_acc = do_submit_claim(
+            &insurance_contract_owner_key,
+            &insurance_contract_data_key,
+            &mut insurance_contract_data_acc,
+            0,
+            5_000,
+        );
+        let (second_claim_data_key, _) =
+            find_claim_address(&program_id, &insurance_contract_data_key, 1);
+        let mut second_claim_data_acc = do_submit_claim(
+            &insurance_contract_owner_key,
+            &insurance_contract_data_key,
+            &mut insurance_contract_data_acc,
+            1,
+            7_000,
+        );
+        let claim_status = |claim_data_acc: &SolanaAccount| {
+            ClaimData::unpack(&claim_data_acc.data).unwrap().status
+        };
+
+        // BadCase: Claim is adjusted by the policyholder
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                crate::instruction::approve_claim(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &insurance_contract_data_key,
+                    &first_claim_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut first_claim_data_acc,
+                ],
+            )
+        );
+
+        // BadCase: Claim is filed against another contract
+        let (other_insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 2);
+        let mut other_insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 2, terms);
+        assert_eq!(
+            Err(InsuranceContractError::ClaimContractMismatch.into()),
+            do_process(
+                crate::instruction::approve_claim(
+                    &program_id,
+                    &claims_adjuster_key,
+                    &other_insurance_contract_data_key,
+                    &first_claim_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut other_insurance_contract_data_acc,
+                    &mut first_claim_data_acc,
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::approve_claim(
+                &program_id,
+                &claims_adjuster_key,
+                &insurance_contract_data_key,
+                &first_claim_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut claims_adjuster_acc,
+                &mut insurance_contract_data_acc,
+                &mut first_claim_data_acc,
+            ],
+        )
+        .unwrap();
+        assert_eq!(claim_status(&first_claim_data_acc), ClaimStatus::Approved);
+
+        do_process(
+            crate::instruction::reject_claim(
+                &program_id,
+                &claims_adjuster_key,
+                &insurance_contract_data_key,
+                &second_claim_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut claims_adjuster_acc,
+                &mut insurance_contract_data_acc,
+                &mut second_claim_data_acc,
+            ],
+        )
+        .unwrap();
+        assert_eq!(claim_status(&second_claim_data_acc), ClaimStatus::Rejected);
+
+        // BadCase: Claim is already adjusted
+        assert_eq!(
+            Err(InsuranceContractError::InvalidClaimStatus.into()),
+            do_process(
+                crate::instruction::reject_claim(
+                    &program_id,
+                    &claims_adjuster_key,
+                    &insurance_contract_data_key,
+                    &first_claim_data_key,
+                )
+                .unwrap(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut first_claim_data_acc,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_pay_claim() {
+        let program_id = crate::id();
+        let terms = PolicyTerms {
+            premium_amount: 0,
+            deductible: 500,
+            ..test_terms()
+        };
+        let claims_adjuster_key = terms.claims_adjuster;
+        let mut claims_adjuster_acc = SolanaAccount::default();
+        let mut token_program_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, terms);
+        let (vault_key, _) = find_vault_address(&program_id, &insurance_contract_data_key);
+        let (claim_data_key, _) = find_claim_address(&program_id, &insurance_contract_data_key, 0);
+        let mut claim_data_acc = do_submit_claim(
+            &insurance_contract_owner_key,
+            &insurance_contract_data_key,
+            &mut insurance_contract_data_acc,
+            0,
+            5_000,
+        );
+        let mut beneficiary_acc =
+            token_account(&terms.premium_mint, &insurance_contract_owner_key, 0);
+        let mut policy_token_acc =
+            policy_token_account(&insurance_contract_data_key, &insurance_contract_owner_key);
+        let pay_claim = || {
+            crate::instruction::pay_claim(
+                &program_id,
+                &claims_adjuster_key,
+                &insurance_contract_data_key,
+                &claim_data_key,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+            )
+            .unwrap()
+        };
+
+        // BadCase: Claim is not approved
+        let mut vault_acc = token_account(&terms.premium_mint, &vault_key, 10_000);
+        assert_eq!(
+            Err(InsuranceContractError::InvalidClaimStatus.into()),
+            do_process(
+                pay_claim(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut vault_acc,
+                    &mut beneficiary_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        do_process(
+            crate::instruction::approve_claim(
+                &program_id,
+                &claims_adjuster_key,
+                &insurance_contract_data_key,
+                &claim_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut claims_adjuster_acc,
+                &mut insurance_contract_data_acc,
+                &mut claim_data_acc,
+            ],
+        )
+        .unwrap();
+
+        // BadCase: Vault can't cover the claim payout
+        let mut poor_vault_acc = token_account(&terms.premium_mint, &vault_key, 4_499);
+        assert_eq!(
+            Err(InsuranceContractError::InsufficientVaultFunds.into()),
+            do_process(
+                pay_claim(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut poor_vault_acc,
+                    &mut beneficiary_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Beneficiary is not the claimant
+        let mut other_beneficiary_acc =
+            token_account(&terms.premium_mint, &Pubkey::new_unique(), 0);
+        assert_eq!(
+            Err(InsuranceContractError::BeneficiaryMismatch.into()),
+            do_process(
+                pay_claim(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut vault_acc,
+                    &mut other_beneficiary_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Policy token was assigned after the claim was filed
+        assert_eq!(
+            Err(InsuranceContractError::BeneficiaryMismatch.into()),
+            do_process(
+                pay_claim(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut vault_acc,
+                    &mut beneficiary_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_account(&insurance_contract_data_key, &Pubkey::new_unique()),
+                ],
+            )
+        );
+
+        // BadCase: Beneficiary holds another currency
+        let mut other_mint_beneficiary_acc =
+            token_account(&Pubkey::new_unique(), &insurance_contract_owner_key, 0);
+        assert_eq!(
+            Err(InsuranceContractError::InvalidMint.into()),
+            do_process(
+                pay_claim(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut vault_acc,
+                    &mut other_mint_beneficiary_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+
+        do_process(
+            pay_claim(),
+            vec![
+                &mut claims_adjuster_acc,
+                &mut insurance_contract_data_acc,
+                &mut claim_data_acc,
+                &mut vault_acc,
+                &mut beneficiary_acc,
+                &mut token_program_acc,
+                &mut policy_token_acc,
+            ],
+        )
+        .unwrap();
+        // The deductible is kept from the claimed amount
+        let claim_data = ClaimData::unpack(&claim_data_acc.data).unwrap();
+        assert_eq!(claim_data.status, ClaimStatus::Paid);
+        assert_eq!(claim_data.payout, 4_500);
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap();
+        assert_eq!(insurance_contract_data.claims_paid, 4_500);
+        assert_eq!(insurance_contract_data.aggregate_paid, [4_500, 0, 0]);
+        assert_eq!(
+            insurance_contract_data.remaining_coverage(),
+            terms.sum_insured - 4_500
+        );
+
+        // BadCase: Claim is already paid
+        assert_eq!(
+            Err(InsuranceContractError::InvalidClaimStatus.into()),
+            do_process(
+                pay_claim(),
+                vec![
+                    &mut claims_adjuster_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut claim_data_acc,
+                    &mut vault_acc,
+                    &mut beneficiary_acc,
+                    &mut token_program_acc,
+                    &mut policy_token_acc,
+                ],
+            )
+        );
+    }
+
+    #[test]
+    fn test_reclaim_insurance_contract() {
+        let program_id = crate::id();
+        let mut token_program_acc = SolanaAccount::default();
+
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let mut insurance_contract_owner_acc = SolanaAccount::default();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc = do_save_insurance_contract(
+            &insurance_contract_owner_key,
+            1,
+            PolicyTerms {
+                premium_amount: 0,
+                ..test_terms()
+            },
+        );
+        let (vault_key, _) = find_vault_address(&program_id, &insurance_contract_data_key);
+        let mut vault_acc = token_account(&test_terms().premium_mint, &vault_key, 10_000);
+        let destination_key = Pubkey::new_unique();
+        let mut destination_acc = SolanaAccount::default();
+        let premium_token_key = Pubkey::new_unique();
+        let mut premium_token_acc =
+            token_account(&test_terms().premium_mint, &insurance_contract_owner_key, 0);
+        let reclaim = |insurance_contract_authority_key: &Pubkey, premium_token_key: &Pubkey| {
+            crate::instruction::reclaim_insurance_contract(
+                &program_id,
+                insurance_contract_authority_key,
+                &insurance_contract_data_key,
+                &destination_key,
+                premium_token_key,
+            )
+            .unwrap()
+        };
+
+        // BadCase: Contract is not closed
+        assert_eq!(
+            Err(InsuranceContractError::InvalidContractStatus.into()),
+            do_process(
+                reclaim(&insurance_contract_owner_key, &premium_token_key),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut destination_acc,
+                    &mut token_program_acc,
+                    &mut premium_token_acc,
+                ],
+            )
+        );
+
+        // An approved claim is waiting for its payout
+        let (claim_data_key, _) = find_claim_address(&program_id, &insurance_contract_data_key, 0);
+        let mut claim_data_acc = do_submit_claim(
+            &insurance_contract_owner_key,
+            &insurance_contract_data_key,
+            &mut insurance_contract_data_acc,
+            0,
+            5_000,
+        );
+        do_process(
+            crate::instruction::approve_claim(
+                &program_id,
+                &test_terms().claims_adjuster,
+                &insurance_contract_data_key,
+                &claim_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut SolanaAccount::default(),
+                &mut insurance_contract_data_acc,
+                &mut claim_data_acc,
+            ],
+        )
+        .unwrap();
+
+        do_process(
+            crate::instruction::close_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &insurance_contract_data_key,
+                &insurance_contract_owner_key,
+                &Pubkey::new_unique(),
+            )
+            .unwrap(),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut SolanaAccount::default(),
+                &mut policy_token_account(
+                    &insurance_contract_data_key,
+                    &insurance_contract_owner_key,
+                ),
+                &mut SolanaAccount::default(),
+                &mut token_program_acc,
+            ],
+        )
+        .unwrap();
+
+        // BadCase: Authority mismatch
+        let attacker_key = Pubkey::new_unique();
+        let mut attacker_acc = SolanaAccount::default();
+        assert_eq!(
+            Err(InsuranceContractError::AuthorityMismatch.into()),
+            do_process(
+                reclaim(&attacker_key, &premium_token_key),
+                vec![
+                    &mut attacker_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut destination_acc,
+                    &mut token_program_acc,
+                    &mut premium_token_acc,
+                ],
+            )
+        );
+
+        // BadCase: Approved claim is not paid
+        assert_eq!(
+            Err(InsuranceContractError::VaultNotEmpty.into()),
+            do_process(
+                reclaim(&insurance_contract_owner_key, &premium_token_key),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut destination_acc,
+                    &mut token_program_acc,
+                    &mut premium_token_acc,
+                ],
+            )
+        );
+        do_process(
+            crate::instruction::pay_claim(
+                &program_id,
+                &test_terms().claims_adjuster,
+                &insurance_contract_data_key,
+                &claim_data_key,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+            )
+            .unwrap(),
+            vec![
+                &mut SolanaAccount::default(),
+                &mut insurance_contract_data_acc,
+                &mut claim_data_acc,
+                &mut vault_acc,
+                &mut token_account(&test_terms().premium_mint, &insurance_contract_owner_key, 0),
+                &mut token_program_acc,
+                &mut policy_token_account(
+                    &insurance_contract_data_key,
+                    &insurance_contract_owner_key,
+                ),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data)
+                .unwrap()
+                .unpaid_claim_count,
+            0
+        );
+
+        // BadCase: Premium left in the vault is sent to the vault itself
+        let mut vault_as_premium_acc = vault_acc.clone();
+        assert_eq!(
+            Err(ProgramError::InvalidArgument),
+            do_process(
+                reclaim(&insurance_contract_owner_key, &vault_key),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut destination_acc,
+                    &mut token_program_acc,
+                    &mut vault_as_premium_acc,
+                ],
+            )
+        );
+
+        // Premium left in the vault is swept to the authority token account
+        do_process(
+            reclaim(&insurance_contract_owner_key, &premium_token_key),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut insurance_contract_data_acc,
+                &mut vault_acc,
+                &mut destination_acc,
+                &mut token_program_acc,
+                &mut premium_token_acc,
+            ],
+        )
+        .unwrap();
+        assert_eq!(insurance_contract_data_acc.lamports, 0);
+        assert_eq!(
+            destination_acc.lamports,
+            insurance_contract_minimum_balance()
+        );
+        assert_eq!(
+            insurance_contract_data_acc.data[0],
+            AccountType::Reclaimed as u8
+        );
+        assert!(insurance_contract_data_acc.data[1..]
+            .iter()
+            .all(|byte| *byte == 0));
+
+        // BadCase: Reclaimed account is funded again within the same transaction
+        insurance_contract_data_acc.lamports = insurance_contract_minimum_balance();
+        assert_eq!(
+            Err(InsuranceContractError::AlreadyClosed.into()),
+            do_process(
+                crate::instruction::save_insurance_contract(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    1,
+                    test_terms(),
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut create_account_for_test(&Rent::default()),
+                    &mut create_account_for_test(&Clock::default()),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+    }
 
-        insurance_contract_data
-            .serialize(&mut &mut insurance_contract_account.data.borrow_mut()[..])?;
+    #[test]
+    fn test_expire_insurance_contract() {
+        let program_id = crate::id();
+        let mut rent_acc = create_account_for_test(&Rent::default());
+        let terms = PolicyTerms {
+            premium_amount: 0,
+            ..test_terms()
+        };
+        let keeper_key = Pubkey::new_unique();
+        let mut keeper_acc = SolanaAccount::default();
 
-        Ok(())
-    }
-}
+        let insurance_contract_owner_key = Pubkey::new_unique();
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, terms);
+        // Keeper reward transferred by the authority on save
+        insurance_contract_data_acc.lamports += terms.keeper_reward;
+        let expire = || {
+            crate::instruction::expire_insurance_contract(
+                &program_id,
+                &keeper_key,
+                &insurance_contract_data_key,
+            )
+            .unwrap()
+        };
 
-// Unit tests
-#[cfg(test)]
-mod test {
-    use super::*;
-    use solana_program::instruction::Instruction;
-    use solana_sdk::account::{
-        create_account_for_test, create_is_signer_account_infos, Account as SolanaAccount,
-    };
+        // BadCase: Coverage period is not over
+        let mut clock_acc = create_account_for_test(&Clock {
+            unix_timestamp: terms.coverage_end - 1,
+            ..Clock::default()
+        });
+        assert_eq!(
+            Err(InsuranceContractError::CoverageNotEnded.into()),
+            do_process(
+                expire(),
+                vec![
+                    &mut keeper_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                ],
+            )
+        );
 
-    fn insurance_contract_minimum_balance() -> u64 {
-        Rent::default().minimum_balance(state::INSURANCE_CONTRACT_DATA_LEN)
-    }
+        let mut clock_acc = create_account_for_test(&Clock {
+            unix_timestamp: terms.coverage_end,
+            ..Clock::default()
+        });
+        do_process(
+            expire(),
+            vec![
+                &mut keeper_acc,
+                &mut insurance_contract_data_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data)
+                .unwrap()
+                .status,
+            InsuranceContractStatus::Expired
+        );
+        assert_eq!(keeper_acc.lamports, terms.keeper_reward);
+        assert_eq!(
+            insurance_contract_data_acc.lamports,
+            insurance_contract_minimum_balance()
+        );
 
-    fn do_process(instruction: Instruction, accounts: Vec<&mut SolanaAccount>) -> ProgramResult {
-        let mut meta = instruction
-            .accounts
-            .iter()
-            .zip(accounts)
-            .map(|(account_meta, account)| (&account_meta.pubkey, account_meta.is_signer, account))
-            .collect::<Vec<_>>();
+        // BadCase: Contract is already expired
+        assert_eq!(
+            Err(InsuranceContractError::StatusUnchanged.into()),
+            do_process(
+                expire(),
+                vec![
+                    &mut keeper_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                ],
+            )
+        );
 
-        let account_infos = create_is_signer_account_infos(&mut meta);
-        Processor::process(&instruction.program_id, &account_infos, &instruction.data)
+        // Rent exempt balance is never paid out
+        let (other_insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 2);
+        let mut other_insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 2, terms);
+        let mut other_keeper_acc = SolanaAccount::default();
+        do_process(
+            crate::instruction::expire_insurance_contract(
+                &program_id,
+                &keeper_key,
+                &other_insurance_contract_data_key,
+            )
+            .unwrap(),
+            vec![
+                &mut other_keeper_acc,
+                &mut other_insurance_contract_data_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+            ],
+        )
+        .unwrap();
+        assert_eq!(other_keeper_acc.lamports, 0);
+        assert_eq!(
+            other_insurance_contract_data_acc.lamports,
+            insurance_contract_minimum_balance()
+        );
     }
 
     #[test]
-    fn test_save_insurance_contract() {
+    fn test_renew_insurance_contract() {
         let program_id = crate::id();
         let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut clock_acc = create_account_for_test(&test_clock());
+        let mut system_acc = SolanaAccount::default();
+        let mut token_program_acc = SolanaAccount::default();
+        let mut premium_mint_acc = SolanaAccount::default();
+        let terms = PolicyTerms {
+            premium_amount: 0,
+            ..test_terms()
+        };
 
         let insurance_contract_owner_key = Pubkey::new_unique();
         let mut insurance_contract_owner_acc = SolanaAccount::default();
-        let insurance_contract_data_key = Pubkey::new_unique();
-        let mut insurance_contract_data_acc = SolanaAccount::new(
-            insurance_contract_minimum_balance(),
-            state::INSURANCE_CONTRACT_DATA_LEN,
-            &program_id,
+        let premium_payer_key = Pubkey::new_unique();
+        let mut premium_payer_acc = SolanaAccount::default();
+        let mut payer_token_acc = SolanaAccount::default();
+        let (predecessor_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut predecessor_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, terms);
+        let mut predecessor_policy_token_acc =
+            policy_token_account(&predecessor_key, &insurance_contract_owner_key);
+        let year = 365 * 24 * 60 * 60;
+        let renew = |predecessor_key: &Pubkey,
+                     premium_mint_key: &Pubkey,
+                     id: u32,
+                     product_key: Option<&Pubkey>| {
+            crate::instruction::renew_insurance_contract(
+                &program_id,
+                &insurance_contract_owner_key,
+                &premium_payer_key,
+                &Pubkey::new_unique(),
+                predecessor_key,
+                premium_mint_key,
+                &Pubkey::new_unique(),
+                id,
+                terms.coverage_end + (id as i64 - 1) * year,
+                10_000,
+                &Pubkey::new_unique(),
+                product_key,
+            )
+            .unwrap()
+        };
+        let new_insurance_contract = |id: u32| {
+            let (insurance_contract_data_key, _) =
+                find_insurance_contract_address(&program_id, &insurance_contract_owner_key, id);
+            let (vault_key, _) = find_vault_address(&program_id, &insurance_contract_data_key);
+            (
+                SolanaAccount::new(
+                    insurance_contract_minimum_balance(),
+                    state::INSURANCE_CONTRACT_DATA_LEN,
+                    &program_id,
+                ),
+                token_account(&terms.premium_mint, &vault_key, 0),
+            )
+        };
+
+        // BadCase: Renewal premium paid in another currency
+        let (mut successor_acc, mut vault_acc) = new_insurance_contract(2);
+        assert_eq!(
+            Err(InsuranceContractError::InvalidMint.into()),
+            do_process(
+                renew(&predecessor_key, &Pubkey::new_unique(), 2, None),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut premium_payer_acc,
+                    &mut payer_token_acc,
+                    &mut predecessor_acc,
+                    &mut successor_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut predecessor_policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
         );
-        let insurance_contract_id = 11223344;
 
-        insurance_contract_data_acc.lamports -= 100;
+        do_process(
+            renew(&predecessor_key, &terms.premium_mint, 2, None),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut premium_payer_acc,
+                &mut payer_token_acc,
+                &mut predecessor_acc,
+                &mut successor_acc,
+                &mut vault_acc,
+                &mut premium_mint_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut predecessor_policy_token_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        let (successor_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 2);
+        let predecessor_data = InsuranceContractData::unpack(&predecessor_acc.data).unwrap();
+        assert_eq!(predecessor_data.successor, successor_key);
+        let successor_data = InsuranceContractData::unpack(&successor_acc.data).unwrap();
+        assert_eq!(successor_data.status, InsuranceContractStatus::Active);
+        assert_eq!(successor_data.predecessor, predecessor_key);
+        assert_eq!(successor_data.coverage_start, terms.coverage_end);
+        assert_eq!(successor_data.sum_insured, terms.sum_insured);
+        assert_eq!(successor_data.claim_free_terms, 1);
+        // One claim-free term earns 10% discount
+        assert_eq!(successor_data.premium_amount, 9_000);
+        assert_eq!(successor_data.premium_paid, 9_000);
+        assert_eq!(
+            successor_data.policy_mint,
+            find_policy_mint_address(&program_id, &successor_key).0
+        );
 
-        // BadCase: Rent exempt
+        // BadCase: Contract is already renewed
+        let (mut other_successor_acc, mut other_vault_acc) = new_insurance_contract(3);
         assert_eq!(
-            Err(ProgramError::AccountNotRentExempt),
+            Err(InsuranceContractError::AlreadyRenewed.into()),
             do_process(
-                crate::instruction::save_insurance_contract(
-                    &program_id,
-                    &insurance_contract_owner_key,
-                    &insurance_contract_data_key,
-                    insurance_contract_id,
-                )
-                .unwrap(),
+                renew(&predecessor_key, &terms.premium_mint, 3, None),
                 vec![
                     &mut insurance_contract_owner_acc,
-                    &mut insurance_contract_data_acc,
+                    &mut premium_payer_acc,
+                    &mut payer_token_acc,
+                    &mut predecessor_acc,
+                    &mut other_successor_acc,
+                    &mut other_vault_acc,
+                    &mut premium_mint_acc,
                     &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut predecessor_policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
                 ],
             )
         );
 
-        insurance_contract_data_acc.lamports += 100;
-
+        // A paid claim resets the no-claims discount
+        let mut successor_data = successor_data;
+        successor_data.claims_paid = 1;
+        successor_data.pack(&mut successor_acc.data).unwrap();
         do_process(
-            crate::instruction::save_insurance_contract(
-                &program_id,
-                &insurance_contract_owner_key,
-                &insurance_contract_data_key,
-                insurance_contract_id,
-            )
-            .unwrap(),
+            renew(&successor_key, &terms.premium_mint, 3, None),
             vec![
                 &mut insurance_contract_owner_acc,
-                &mut insurance_contract_data_acc,
+                &mut premium_payer_acc,
+                &mut payer_token_acc,
+                &mut successor_acc,
+                &mut other_successor_acc,
+                &mut other_vault_acc,
+                &mut premium_mint_acc,
                 &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut policy_token_account(&successor_key, &insurance_contract_owner_key),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
             ],
         )
         .unwrap();
+        let renewal_data = InsuranceContractData::unpack(&other_successor_acc.data).unwrap();
+        assert_eq!(renewal_data.claim_free_terms, 0);
+        assert_eq!(renewal_data.premium_amount, 10_000);
 
-        let insurance_contract_data =
-            InsuranceContractData::try_from_slice(&insurance_contract_data_acc.data).unwrap();
-        assert_eq!(insurance_contract_data.is_initialized, true);
-        assert_eq!(insurance_contract_data.is_closed, false);
+        // Successor of a contract issued from a product is a new policy of the product
+        let (renewal_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 3);
+        let mut renewal_data = renewal_data;
+        let product_key = Pubkey::new_unique();
+        renewal_data.product = product_key;
+        renewal_data.pack(&mut other_successor_acc.data).unwrap();
+        let mut renewal_policy_token_acc =
+            policy_token_account(&renewal_key, &insurance_contract_owner_key);
+        let mut product_data = InsuranceProductData {
+            account_type: AccountType::Product,
+            version: state::INSURANCE_PRODUCT_VERSION,
+            status: ProductStatus::Retired,
+            product_id: 1,
+            authority: insurance_contract_owner_key,
+            terms: test_product_terms(),
+            ..InsuranceProductData::default()
+        };
+        let mut product_acc = SolanaAccount::new(
+            Rent::default().minimum_balance(state::INSURANCE_PRODUCT_DATA_LEN),
+            state::INSURANCE_PRODUCT_DATA_LEN,
+            &program_id,
+        );
+        product_data.pack(&mut product_acc.data).unwrap();
+        let (mut product_successor_acc, mut product_vault_acc) = new_insurance_contract(4);
+
+        // BadCase: Product account is missing
         assert_eq!(
-            insurance_contract_data.insurance_contract_id,
-            insurance_contract_id
+            Err(ProgramError::NotEnoughAccountKeys),
+            do_process(
+                renew(&renewal_key, &terms.premium_mint, 4, None),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut premium_payer_acc,
+                    &mut payer_token_acc,
+                    &mut other_successor_acc,
+                    &mut product_successor_acc,
+                    &mut product_vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut renewal_policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
         );
 
-        // BadCase: account already initialized
+        // BadCase: Product account is another product
         assert_eq!(
-            Err(InsuranceContractError::AlreadyInitialized.into()),
+            Err(ProgramError::InvalidArgument),
             do_process(
-                crate::instruction::save_insurance_contract(
-                    &program_id,
-                    &insurance_contract_owner_key,
-                    &insurance_contract_data_key,
-                    insurance_contract_id,
-                )
-                .unwrap(),
+                renew(
+                    &renewal_key,
+                    &terms.premium_mint,
+                    4,
+                    Some(&Pubkey::new_unique())
+                ),
                 vec![
                     &mut insurance_contract_owner_acc,
-                    &mut insurance_contract_data_acc,
+                    &mut premium_payer_acc,
+                    &mut payer_token_acc,
+                    &mut other_successor_acc,
+                    &mut product_successor_acc,
+                    &mut product_vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut renewal_policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut product_acc,
+                ],
+            )
+        );
+
+        // BadCase: Product is retired
+        assert_eq!(
+            Err(InsuranceContractError::ProductRetired.into()),
+            do_process(
+                renew(&renewal_key, &terms.premium_mint, 4, Some(&product_key)),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut premium_payer_acc,
+                    &mut payer_token_acc,
+                    &mut other_successor_acc,
+                    &mut product_successor_acc,
+                    &mut product_vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut renewal_policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut product_acc,
+                ],
+            )
+        );
+
+        // BadCase: Product is paused
+        product_data.status = ProductStatus::Active;
+        product_data.paused = true;
+        product_data.pack(&mut product_acc.data).unwrap();
+        assert_eq!(
+            Err(InsuranceContractError::ProgramPaused.into()),
+            do_process(
+                renew(&renewal_key, &terms.premium_mint, 4, Some(&product_key)),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut premium_payer_acc,
+                    &mut payer_token_acc,
+                    &mut other_successor_acc,
+                    &mut product_successor_acc,
+                    &mut product_vault_acc,
+                    &mut premium_mint_acc,
                     &mut rent_acc,
+                    &mut clock_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut renewal_policy_token_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut product_acc,
                 ],
             )
         );
+
+        product_data.paused = false;
+        product_data.pack(&mut product_acc.data).unwrap();
+        take_events();
+        do_process(
+            renew(&renewal_key, &terms.premium_mint, 4, Some(&product_key)),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut premium_payer_acc,
+                &mut payer_token_acc,
+                &mut other_successor_acc,
+                &mut product_successor_acc,
+                &mut product_vault_acc,
+                &mut premium_mint_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut renewal_policy_token_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut product_acc,
+            ],
+        )
+        .unwrap();
+        let product_successor_data =
+            InsuranceContractData::unpack(&product_successor_acc.data).unwrap();
+        assert_eq!(product_successor_data.product, product_key);
+        assert_eq!(
+            InsuranceProductData::unpack(&product_acc.data)
+                .unwrap()
+                .policy_count,
+            1
+        );
+        assert!(take_events().iter().any(|event| matches!(
+            event,
+            InsuranceContractEvent::PremiumPaid(PremiumPaid { payer, .. })
+                if *payer == premium_payer_key
+        )));
+
+        // Late renewal of an expired contract starts at the renewal time
+        let (product_successor_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 4);
+        let mut expired_data = product_successor_data;
+        expired_data.status = InsuranceContractStatus::Expired;
+        expired_data.coverage_end = test_clock().unix_timestamp - 1_000;
+        expired_data.product = Pubkey::default();
+        expired_data.pack(&mut product_successor_acc.data).unwrap();
+        let (mut late_successor_acc, mut late_vault_acc) = new_insurance_contract(5);
+        do_process(
+            renew(&product_successor_key, &terms.premium_mint, 5, None),
+            vec![
+                &mut insurance_contract_owner_acc,
+                &mut premium_payer_acc,
+                &mut payer_token_acc,
+                &mut product_successor_acc,
+                &mut late_successor_acc,
+                &mut late_vault_acc,
+                &mut premium_mint_acc,
+                &mut rent_acc,
+                &mut clock_acc,
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut policy_token_account(&product_successor_key, &insurance_contract_owner_key),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            InsuranceContractData::unpack(&late_successor_acc.data)
+                .unwrap()
+                .coverage_start,
+            test_clock().unix_timestamp
+        );
     }
 
     #[test]
-    fn test_close_insurance_contract() {
+    fn test_pay_installment_and_lapse() {
         let program_id = crate::id();
         let mut rent_acc = create_account_for_test(&Rent::default());
+        let mut system_acc = SolanaAccount::default();
+        let mut token_program_acc = SolanaAccount::default();
+        let mut premium_mint_acc = SolanaAccount::default();
+        let month = 30 * 24 * 60 * 60;
+        let terms = PolicyTerms {
+            installment_count: 4,
+            installment_amount: 2_500,
+            installment_interval: month,
+            grace_period: 7 * 24 * 60 * 60,
+            ..test_terms()
+        };
+        let clock_at = |unix_timestamp: UnixTimestamp| {
+            create_account_for_test(&Clock {
+                unix_timestamp,
+                ..Clock::default()
+            })
+        };
 
         let insurance_contract_owner_key = Pubkey::new_unique();
         let mut insurance_contract_owner_acc = SolanaAccount::default();
-        let insurance_contract_data_key = Pubkey::new_unique();
+        let mut owner_token_acc = SolanaAccount::default();
+
+        // BadCase: Installments don't add up to the premium
         let mut insurance_contract_data_acc = SolanaAccount::new(
             insurance_contract_minimum_balance(),
             state::INSURANCE_CONTRACT_DATA_LEN,
             &program_id,
         );
-        let insurance_contract_id = 11223344;
-
-        // BadCase: Not initialized
         assert_eq!(
-            Err(InsuranceContractError::NotInitialized.into()),
+            Err(InsuranceContractError::InvalidPremiumSchedule.into()),
             do_process(
-                crate::instruction::close_insurance_contract(
+                crate::instruction::save_insurance_contract(
                     &program_id,
                     &insurance_contract_owner_key,
-                    &insurance_contract_data_key,
+                    1,
+                    PolicyTerms {
+                        installment_amount: 2_000,
+                        ..terms
+                    },
                 )
                 .unwrap(),
                 vec![
                     &mut insurance_contract_owner_acc,
                     &mut insurance_contract_data_acc,
+                    &mut rent_acc,
+                    &mut create_account_for_test(&Clock::default()),
+                    &mut system_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
                 ],
             )
         );
 
-        do_process(
-            crate::instruction::save_insurance_contract(
+        let (insurance_contract_data_key, _) =
+            find_insurance_contract_address(&program_id, &insurance_contract_owner_key, 1);
+        let mut insurance_contract_data_acc =
+            do_save_insurance_contract(&insurance_contract_owner_key, 1, terms);
+        let (vault_key, _) = find_vault_address(&program_id, &insurance_contract_data_key);
+        let mut vault_acc = token_account(&terms.premium_mint, &vault_key, 0);
+        let pay_installment = || {
+            crate::instruction::pay_installment(
                 &program_id,
                 &insurance_contract_owner_key,
+                &Pubkey::new_unique(),
                 &insurance_contract_data_key,
-                insurance_contract_id,
+                &terms.premium_mint,
+                &Pubkey::new_unique(),
             )
-            .unwrap(),
+            .unwrap()
+        };
+        let lapse = || {
+            crate::instruction::lapse_insurance_contract(&program_id, &insurance_contract_data_key)
+                .unwrap()
+        };
+        let insurance_contract_data = |insurance_contract_data_acc: &SolanaAccount| {
+            InsuranceContractData::unpack(&insurance_contract_data_acc.data).unwrap()
+        };
+
+        // BadCase: Scheduled premium can't be paid at once
+        assert_eq!(
+            Err(InsuranceContractError::InvalidPremiumSchedule.into()),
+            do_process(
+                crate::instruction::pay_premium(
+                    &program_id,
+                    &insurance_contract_owner_key,
+                    &Pubkey::new_unique(),
+                    &insurance_contract_data_key,
+                    &terms.premium_mint,
+                    terms.premium_amount,
+                    &Pubkey::new_unique(),
+                )
+                .unwrap(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut owner_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+        );
+
+        // First installment activates the contract
+        do_process(
+            pay_installment(),
             vec![
                 &mut insurance_contract_owner_acc,
+                &mut owner_token_acc,
                 &mut insurance_contract_data_acc,
+                &mut vault_acc,
+                &mut premium_mint_acc,
                 &mut rent_acc,
+                &mut clock_at(terms.coverage_start),
+                &mut system_acc,
+                &mut token_program_acc,
+                &mut SolanaAccount::default(),
+                &mut SolanaAccount::default(),
             ],
         )
         .unwrap();
+        let data = insurance_contract_data(&insurance_contract_data_acc);
+        assert_eq!(data.status, InsuranceContractStatus::Active);
+        assert_eq!(data.installments_paid, 1);
+        assert_eq!(data.premium_paid, 2_500);
+        assert_eq!(
+            data.next_installment_due(),
+            Some(terms.coverage_start + month)
+        );
 
-        do_process(
-            crate::instruction::close_insurance_contract(
-                &program_id,
-                &insurance_contract_owner_key,
-                &insurance_contract_data_key,
+        // BadCase: Second installment is still in the grace period
+        let grace_period_end = terms.coverage_start + month + terms.grace_period;
+        assert_eq!(
+            Err(InsuranceContractError::GracePeriodNotOver.into()),
+            do_process(
+                lapse(),
+                vec![
+                    &mut insurance_contract_data_acc,
+                    &mut clock_at(grace_period_end - 1),
+                ],
             )
-            .unwrap(),
+        );
+
+        do_process(
+            lapse(),
             vec![
-                &mut insurance_contract_owner_acc,
                 &mut insurance_contract_data_acc,
+                &mut clock_at(grace_period_end),
             ],
         )
         .unwrap();
+        assert_eq!(
+            insurance_contract_data(&insurance_contract_data_acc).status,
+            InsuranceContractStatus::Lapsed
+        );
 
-        let insurance_contract_data =
-            InsuranceContractData::try_from_slice(&insurance_contract_data_acc.data).unwrap();
-        assert_eq!(insurance_contract_data.is_initialized, true);
-        assert_eq!(insurance_contract_data.is_closed, true);
+        // BadCase: Contract already lapsed
         assert_eq!(
-            insurance_contract_data.insurance_contract_id,
-            insurance_contract_id
+            Err(InsuranceContractError::InvalidContractStatus.into()),
+            do_process(
+                lapse(),
+                vec![
+                    &mut insurance_contract_data_acc,
+                    &mut clock_at(grace_period_end),
+                ],
+            )
         );
 
-        // BadCase: account already closed
+        // Paying the overdue installment reinstates the contract
+        for _ in 0..3 {
+            do_process(
+                pay_installment(),
+                vec![
+                    &mut insurance_contract_owner_acc,
+                    &mut owner_token_acc,
+                    &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut clock_at(grace_period_end),
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
+                ],
+            )
+            .unwrap();
+            assert_eq!(
+                insurance_contract_data(&insurance_contract_data_acc).status,
+                InsuranceContractStatus::Active
+            );
+        }
+        let data = insurance_contract_data(&insurance_contract_data_acc);
+        assert_eq!(data.installments_paid, 4);
+        assert_eq!(data.premium_paid, terms.premium_amount);
+        assert_eq!(data.next_installment_due(), None);
+
+        // BadCase: All installments are paid
         assert_eq!(
-            Err(InsuranceContractError::AlreadyClosed.into()),
+            Err(InsuranceContractError::InvalidPremiumAmount.into()),
             do_process(
-                crate::instruction::close_insurance_contract(
-                    &program_id,
-                    &insurance_contract_owner_key,
-                    &insurance_contract_data_key,
-                )
-                .unwrap(),
+                pay_installment(),
                 vec![
                     &mut insurance_contract_owner_acc,
+                    &mut owner_token_acc,
                     &mut insurance_contract_data_acc,
+                    &mut vault_acc,
+                    &mut premium_mint_acc,
+                    &mut rent_acc,
+                    &mut clock_at(grace_period_end),
+                    &mut system_acc,
+                    &mut token_program_acc,
+                    &mut SolanaAccount::default(),
+                    &mut SolanaAccount::default(),
                 ],
             )
         );
+        assert_eq!(
+            Err(InsuranceContractError::GracePeriodNotOver.into()),
+            do_process(
+                lapse(),
+                vec![&mut insurance_contract_data_acc, &mut clock_at(i64::MAX)],
+            )
+        );
     }
 }
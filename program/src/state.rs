@@ -1,12 +1,1903 @@
 //! State transition types
+use crate::error::InsuranceContractError;
 use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{clock::UnixTimestamp, program_error::ProgramError, pubkey::Pubkey};
 
-pub const INSURANCE_CONTRACT_DATA_LEN: usize = 1 + 1 + 4;
+/// Size of an InsuranceContract account.
+///
+/// The serialized InsuranceContractData is smaller than this, the rest of the account is
+/// kept zeroed so fields appended to the end of the layout deserialize to their defaults
+/// on existing accounts.
+pub const INSURANCE_CONTRACT_DATA_LEN: usize = 1024;
+
+/// Version of the InsuranceContractData layout written by this program. Fields appended
+/// into the zeroed reserved tail keep the version, older accounts decode them as defaults.
+pub const INSURANCE_CONTRACT_VERSION: u8 = 3;
+
+/// Size of a Claim account, the serialized ClaimData is followed by a zeroed reserved tail
+pub const CLAIM_DATA_LEN: usize = 256;
+
+/// Version of the ClaimData layout written by this program
+pub const CLAIM_VERSION: u8 = 1;
+
+/// Size of an InsuranceProduct account, the serialized InsuranceProductData is followed
+/// by a zeroed reserved tail
+pub const INSURANCE_PRODUCT_DATA_LEN: usize = 512;
+
+/// Version of the InsuranceProductData layout written by this program
+pub const INSURANCE_PRODUCT_VERSION: u8 = 1;
+
+/// Size of the program config account, the serialized ConfigData is followed by a zeroed
+/// reserved tail
+pub const CONFIG_DATA_LEN: usize = 512;
+
+/// Version of the ConfigData layout written by this program
+pub const CONFIG_VERSION: u8 = 1;
+
+/// Number of premium mints the program config can restrict premiums to
+pub const MAX_ALLOWED_MINTS: usize = 8;
+
+/// Maximum protocol fee charged on top of premiums, in basis points
+pub const MAX_PROTOCOL_FEE_BPS: u16 = 1_000;
+
+/// Basis points in a whole, the cancellation penalty is expressed in basis points
+pub const BASIS_POINTS: u16 = 10_000;
+
+/// Maximum no-claims discount of a renewal premium, in basis points
+pub const MAX_NO_CLAIMS_DISCOUNT_BPS: u16 = 5_000;
+
+/// Number of assignments kept in the history of an InsuranceContract
+pub const MAX_ASSIGNMENT_HISTORY: usize = 4;
+
+/// Maximum length of the policy document URI, in bytes
+pub const MAX_DOCUMENT_URI_LEN: usize = 128;
+
+/// Number of replaced policy document hashes kept in the history of an InsuranceContract
+pub const MAX_DOCUMENT_HISTORY: usize = 2;
+
+/// Number of coverage gaps recorded on an InsuranceContract, later gaps are merged into
+/// the last one
+pub const MAX_COVERAGE_GAPS: usize = 4;
+
+/// Number of perils an InsuranceContract can list as covered
+pub const MAX_PERILS: usize = 6;
+
+/// Code of an unused peril entry, also filed by claims against all-risk contracts
+pub const NO_PERIL: u16 = 0;
+
+/// Length of a policy year the annual aggregate limit applies to, counted from the
+/// coverage start
+pub const SECONDS_PER_POLICY_YEAR: UnixTimestamp = 365 * 24 * 60 * 60;
+
+/// Number of policy years the claims paid against the annual aggregate limit are kept for,
+/// the coverage period of a contract with an annual aggregate limit can't be longer
+pub const MAX_AGGREGATE_YEARS: usize = 3;
+
+/// Size of the unversioned v0 layout
+pub const INSURANCE_CONTRACT_DATA_V0_LEN: usize = 1 + 1 + 4;
+
+/// Size of the unversioned v1 layout
+pub const INSURANCE_CONTRACT_DATA_V1_LEN: usize = 1 + 1 + 4 + 32;
+
+/// Size of the serialized InsuranceContractData, all of its fields have a fixed size:
+/// 9 one byte, 5 u16, 2 u32, 16 u64 and 10 Pubkey sized fields followed by the arrays
+pub const INSURANCE_CONTRACT_SERIALIZED_LEN: usize = 9
+    + 5 * 2
+    + 2 * 4
+    + 16 * 8
+    + 10 * 32
+    + MAX_ASSIGNMENT_HISTORY * (32 + 8)
+    + MAX_AGGREGATE_YEARS * 8
+    + MAX_PERILS * (2 + 8 + 8)
+    + MAX_DOCUMENT_URI_LEN
+    + MAX_DOCUMENT_HISTORY * 32
+    + MAX_COVERAGE_GAPS * (8 + 8);
+
+// Fails to compile once the appended fields outgrow the InsuranceContract account
+const _: [(); 0] =
+    [(); INSURANCE_CONTRACT_SERIALIZED_LEN.saturating_sub(INSURANCE_CONTRACT_DATA_LEN)];
+
+/// Discriminator stored in the first byte of every account owned by the program
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug)]
+pub enum AccountType {
+    /// Account is allocated but not initialized yet
+    Uninitialized,
+    /// InsuranceContractData account
+    InsuranceContract,
+    /// ClaimData account
+    Claim,
+    /// InsuranceContract account whose rent was reclaimed, it can't be initialized again
+    Reclaimed,
+    /// InsuranceProductData account
+    Product,
+    /// ConfigData account
+    Config,
+}
+
+impl Default for AccountType {
+    fn default() -> Self {
+        AccountType::Uninitialized
+    }
+}
+
+/// Lifecycle status of an InsuranceContract
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug)]
+pub enum InsuranceContractStatus {
+    /// Issued, coverage has not started
+    Draft,
+    /// Coverage is in force
+    Active,
+    /// Coverage is temporarily suspended by the authority
+    Suspended,
+    /// Coverage stopped because the premium was not paid
+    Lapsed,
+    /// Coverage period is over
+    Expired,
+    /// Coverage was cancelled before the end of the period
+    Cancelled,
+    /// InsuranceContract is archived, no further changes are possible
+    Closed,
+}
+
+impl Default for InsuranceContractStatus {
+    fn default() -> Self {
+        InsuranceContractStatus::Draft
+    }
+}
 
 #[repr(C)]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
 pub struct InsuranceContractData {
+    pub account_type: AccountType,
+    /// Layout version, see INSURANCE_CONTRACT_VERSION
+    pub version: u8,
+    pub status: InsuranceContractStatus,
+    pub insurance_contract_id: u32,
+    /// Authority allowed to modify the InsuranceContract
+    pub authority: Pubkey,
+    /// Beginning of the coverage period
+    pub coverage_start: UnixTimestamp,
+    /// End of the coverage period
+    pub coverage_end: UnixTimestamp,
+    /// Maximum amount covered, in premium_mint tokens
+    pub sum_insured: u64,
+    /// Premium amount, in premium_mint tokens
+    pub premium_amount: u64,
+    /// Mint of the currency the premium and the sum insured are denominated in
+    pub premium_mint: Pubkey,
+    /// Premium paid to date, in premium_mint tokens
+    pub premium_paid: u64,
+    /// Authority allowed to approve or reject claims
+    pub claims_adjuster: Pubkey,
+    /// Number of claims submitted, the index of the next claim
+    pub claim_count: u32,
+    /// Claims paid to date, in premium_mint tokens
+    pub claims_paid: u64,
+    /// Lamports paid to the keeper expiring the contract, reserved on the account
+    pub keeper_reward: u64,
+    /// Short-rate penalty kept from the unearned premium on cancellation, in basis points
+    pub cancellation_penalty_bps: u16,
+    /// Application defined code of the cancellation reason
+    pub cancellation_reason: u8,
+    /// Premium refunded on cancellation, in premium_mint tokens
+    pub premium_refunded: u64,
+    /// No-claims discount per consecutive claim-free term, in basis points
+    pub no_claims_bonus_bps: u16,
+    /// Number of consecutive claim-free terms before this one
+    pub claim_free_terms: u8,
+    /// InsuranceContract renewed by this one
+    pub predecessor: Pubkey,
+    /// InsuranceContract renewing this one
+    pub successor: Pubkey,
+    /// Number of premium installments, zero when the premium is paid at once
+    pub installment_count: u8,
+    /// Amount of each installment, in premium_mint tokens
+    pub installment_amount: u64,
+    /// Time between installment due dates, the first one is due at the coverage start
+    pub installment_interval: UnixTimestamp,
+    /// Time an installment can stay unpaid after its due date before the contract lapses
+    pub grace_period: UnixTimestamp,
+    /// Number of installments paid to date
+    pub installments_paid: u8,
+    /// Authority proposed to take over the InsuranceContract, default when none
+    pub pending_authority: Pubkey,
+    /// Policyholder filing claims and receiving refunds, the authority when default.
+    /// The holder of a tokenized policy is the holder of its policy token.
+    pub holder: Pubkey,
+    /// Assignment of the policy needs the consent of the authority
+    pub assignment_consent_required: bool,
+    /// Number of assignments of the policy to date
+    pub assignment_count: u16,
+    /// Latest assignments of the policy, a ring buffer indexed by assignment_count
+    pub assignments: [Assignment; MAX_ASSIGNMENT_HISTORY],
+    /// Mint of the token whose holder is the policyholder, default for contracts saved
+    /// before policies were tokenized
+    pub policy_mint: Pubkey,
+    /// Part of each claim borne by the policyholder, in premium_mint tokens
+    pub deductible: u64,
+    /// Maximum payout of a single claim, in premium_mint tokens, zero when unlimited
+    pub per_occurrence_limit: u64,
+    /// Maximum payouts within a policy year, in premium_mint tokens, zero when unlimited
+    pub annual_aggregate_limit: u64,
+    /// Claims paid for incidents within each policy year, in premium_mint tokens
+    pub aggregate_paid: [u64; MAX_AGGREGATE_YEARS],
+    /// Covered perils, see PolicyTerms::perils
+    pub perils: [Peril; MAX_PERILS],
+    /// Claims paid to date for each of the perils, in premium_mint tokens
+    pub peril_claims_paid: [u64; MAX_PERILS],
+    /// Time after the coverage start incidents are not covered in
+    pub waiting_period: UnixTimestamp,
+    /// InsuranceProduct the contract was issued from, default when saved with custom terms
+    pub product: Pubkey,
+    /// Protocol fees paid to the treasury on top of the premium, in premium_mint tokens
+    pub protocol_fees_paid: u64,
+    /// SHA-256 hash of the off-chain policy document, zero when none is anchored
+    pub document_hash: [u8; 32],
+    /// URI the policy document is published at
+    pub document_uri: DocumentUri,
+    /// Number of times the policy document hash was replaced
+    pub document_amendment_count: u16,
+    /// Latest replaced policy document hashes, a ring buffer indexed by
+    /// document_amendment_count
+    pub previous_document_hashes: [[u8; 32]; MAX_DOCUMENT_HISTORY],
+    /// Number of coverage gaps recorded
+    pub coverage_gap_count: u8,
+    /// Periods the coverage was Suspended or Lapsed, oldest first
+    pub coverage_gaps: [CoverageGap; MAX_COVERAGE_GAPS],
+    /// Number of Approved claims not paid yet
+    pub unpaid_claim_count: u16,
+}
+
+impl InsuranceContractData {
+    /// Checks if the account stores an InsuranceContract
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::InsuranceContract
+    }
+
+    /// Deserializes an InsuranceContract account stored with the current layout
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        match input.len() {
+            INSURANCE_CONTRACT_DATA_LEN => {}
+            INSURANCE_CONTRACT_DATA_V0_LEN | INSURANCE_CONTRACT_DATA_V1_LEN => {
+                return Err(InsuranceContractError::MigrationRequired.into())
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+
+        match (AccountType::deserialize(&mut &*input)?, input[1]) {
+            (AccountType::Uninitialized, _) => {}
+            (AccountType::InsuranceContract, INSURANCE_CONTRACT_VERSION) => {}
+            (AccountType::InsuranceContract, version) if version < INSURANCE_CONTRACT_VERSION => {
+                return Err(InsuranceContractError::MigrationRequired.into())
+            }
+            (AccountType::Reclaimed, _) => return Err(InsuranceContractError::AlreadyClosed.into()),
+            _ => return Err(InsuranceContractError::InvalidAccountVersion.into()),
+        }
+        Ok(Self::deserialize(&mut &*input)?)
+    }
+
+    /// Deserializes an InsuranceContract account stored with any historical layout
+    pub fn unpack_from_any_version(input: &[u8]) -> Result<Self, ProgramError> {
+        match input.len() {
+            INSURANCE_CONTRACT_DATA_V0_LEN => {
+                Ok(InsuranceContractDataV0::try_from_slice(input)?.into())
+            }
+            INSURANCE_CONTRACT_DATA_V1_LEN => {
+                Ok(InsuranceContractDataV1::try_from_slice(input)?.into())
+            }
+            _ => match Self::unpack(input) {
+                Err(error) if error == InsuranceContractError::MigrationRequired.into() => {
+                    Ok(InsuranceContractDataV2::deserialize(&mut &*input)?.into())
+                }
+                result => result,
+            },
+        }
+    }
+
+    /// Serializes into the account data and zeroes the reserved tail
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        pack_with_reserved_tail(self, dst)
+    }
+
+    /// Zeroes the account data and marks it reclaimed
+    pub fn pack_reclaimed(dst: &mut [u8]) {
+        dst.iter_mut().for_each(|byte| *byte = 0);
+        if let Some(account_type) = dst.first_mut() {
+            *account_type = AccountType::Reclaimed as u8;
+        }
+    }
+
+    /// Sets the terms the InsuranceContract was issued with
+    pub fn set_terms(&mut self, terms: &PolicyTerms) {
+        self.coverage_start = terms.coverage_start;
+        self.coverage_end = terms.coverage_end;
+        self.sum_insured = terms.sum_insured;
+        self.premium_amount = terms.premium_amount;
+        self.premium_mint = terms.premium_mint;
+        self.claims_adjuster = terms.claims_adjuster;
+        self.keeper_reward = terms.keeper_reward;
+        self.cancellation_penalty_bps = terms.cancellation_penalty_bps;
+        self.no_claims_bonus_bps = terms.no_claims_bonus_bps;
+        self.installment_count = terms.installment_count;
+        self.installment_amount = terms.installment_amount;
+        self.installment_interval = terms.installment_interval;
+        self.grace_period = terms.grace_period;
+        self.assignment_consent_required = terms.assignment_consent_required;
+        self.deductible = terms.deductible;
+        self.per_occurrence_limit = terms.per_occurrence_limit;
+        self.annual_aggregate_limit = terms.annual_aggregate_limit;
+        self.perils = terms.perils;
+        self.waiting_period = terms.waiting_period;
+    }
+
+    /// Terms the InsuranceContract was issued with
+    pub fn terms(&self) -> PolicyTerms {
+        PolicyTerms {
+            coverage_start: self.coverage_start,
+            coverage_end: self.coverage_end,
+            sum_insured: self.sum_insured,
+            premium_amount: self.premium_amount,
+            premium_mint: self.premium_mint,
+            claims_adjuster: self.claims_adjuster,
+            keeper_reward: self.keeper_reward,
+            cancellation_penalty_bps: self.cancellation_penalty_bps,
+            no_claims_bonus_bps: self.no_claims_bonus_bps,
+            installment_count: self.installment_count,
+            installment_amount: self.installment_amount,
+            installment_interval: self.installment_interval,
+            grace_period: self.grace_period,
+            assignment_consent_required: self.assignment_consent_required,
+            deductible: self.deductible,
+            per_occurrence_limit: self.per_occurrence_limit,
+            annual_aggregate_limit: self.annual_aggregate_limit,
+            perils: self.perils,
+            waiting_period: self.waiting_period,
+        }
+    }
+
+    /// Checks if the policy is represented by a token
+    pub fn is_tokenized(&self) -> bool {
+        self.policy_mint != Pubkey::default()
+    }
+
+    /// Policyholder stored on an InsuranceContract which is not tokenized, contracts saved
+    /// before the holder was stored are held by their authority
+    pub fn policyholder(&self) -> Pubkey {
+        if self.holder == Pubkey::default() {
+            self.authority
+        } else {
+            self.holder
+        }
+    }
+
+    /// Appends an assignment of the policy to the history, the oldest entry is overwritten
+    /// once the history is full
+    pub fn record_assignment(
+        &mut self,
+        previous_holder: Pubkey,
+        now: UnixTimestamp,
+    ) -> Result<(), InsuranceContractError> {
+        let index = self.assignment_count as usize % MAX_ASSIGNMENT_HISTORY;
+        self.assignments[index] = Assignment {
+            previous_holder,
+            assigned_at: now,
+        };
+        self.assignment_count = self
+            .assignment_count
+            .checked_add(1)
+            .ok_or(InsuranceContractError::Overflow)?;
+        Ok(())
+    }
+
+    /// Assignments kept in the history, oldest first
+    pub fn assignment_history(&self) -> Vec<Assignment> {
+        let count = self.assignment_count as usize;
+        let kept = count.min(MAX_ASSIGNMENT_HISTORY);
+        (count - kept..count)
+            .map(|index| self.assignments[index % MAX_ASSIGNMENT_HISTORY])
+            .collect()
+    }
+
+    /// Anchors a new policy document, a replaced hash is appended to the history and
+    /// the oldest entry is overwritten once the history is full
+    pub fn amend_document(
+        &mut self,
+        document_hash: [u8; 32],
+        document_uri: DocumentUri,
+    ) -> Result<(), InsuranceContractError> {
+        if self.document_hash != [0; 32] && self.document_hash != document_hash {
+            let index = self.document_amendment_count as usize % MAX_DOCUMENT_HISTORY;
+            self.previous_document_hashes[index] = self.document_hash;
+            self.document_amendment_count = self
+                .document_amendment_count
+                .checked_add(1)
+                .ok_or(InsuranceContractError::Overflow)?;
+        }
+        self.document_hash = document_hash;
+        self.document_uri = document_uri;
+        Ok(())
+    }
+
+    /// Replaced policy document hashes kept in the history, oldest first
+    pub fn document_history(&self) -> Vec<[u8; 32]> {
+        let count = self.document_amendment_count as usize;
+        let kept = count.min(MAX_DOCUMENT_HISTORY);
+        (count - kept..count)
+            .map(|index| self.previous_document_hashes[index % MAX_DOCUMENT_HISTORY])
+            .collect()
+    }
+
+    /// Records the start of a coverage gap. Once the gaps are all used the last one is
+    /// reopened, so it spans every later gap and the covered time in between.
+    pub fn open_coverage_gap(&mut self, now: UnixTimestamp) {
+        let count = self.coverage_gap_count as usize;
+        if count < MAX_COVERAGE_GAPS {
+            self.coverage_gaps[count] = CoverageGap { start: now, end: 0 };
+            self.coverage_gap_count += 1;
+        } else {
+            self.coverage_gaps[MAX_COVERAGE_GAPS - 1].end = 0;
+        }
+    }
+
+    /// Records the reinstatement of the coverage ending the open coverage gap, if any
+    pub fn close_coverage_gap(&mut self, now: UnixTimestamp) {
+        if let Some(gap) = self.coverage_gaps[..self.coverage_gap_count as usize]
+            .last_mut()
+            .filter(|gap| gap.is_open())
+        {
+            gap.end = now;
+        }
+    }
+
+    /// Coverage gaps recorded, oldest first
+    pub fn coverage_gap_history(&self) -> &[CoverageGap] {
+        &self.coverage_gaps[..(self.coverage_gap_count as usize).min(MAX_COVERAGE_GAPS)]
+    }
+
+    /// Checks if the coverage was Suspended or Lapsed at the given time
+    pub fn is_in_coverage_gap(&self, time: UnixTimestamp) -> bool {
+        self.coverage_gap_history()
+            .iter()
+            .any(|gap| gap.contains(time))
+    }
+
+    /// Earliest time of an incident covered by the InsuranceContract
+    pub fn waiting_period_end(&self) -> UnixTimestamp {
+        self.coverage_start.saturating_add(self.waiting_period)
+    }
+
+    /// Coverage left for new claims, in premium_mint tokens
+    pub fn remaining_coverage(&self) -> u64 {
+        self.sum_insured.saturating_sub(self.claims_paid)
+    }
+
+    /// Policy year of the given time, times after the coverage period fall in its last year
+    pub fn policy_year(&self, now: UnixTimestamp) -> u16 {
+        let elapsed = now
+            .min(self.coverage_end.saturating_sub(1))
+            .saturating_sub(self.coverage_start)
+            .max(0);
+        (elapsed / SECONDS_PER_POLICY_YEAR).min(u16::MAX as UnixTimestamp) as u16
+    }
+
+    /// Position in aggregate_paid of the policy year of the given incident time, years
+    /// past the kept ones are counted in the last one
+    fn aggregate_index(&self, incident_time: UnixTimestamp) -> usize {
+        (self.policy_year(incident_time) as usize).min(MAX_AGGREGATE_YEARS - 1)
+    }
+
+    /// Payouts left within the annual aggregate limit of the policy year of the given
+    /// incident time, in premium_mint tokens
+    pub fn remaining_aggregate(&self, incident_time: UnixTimestamp) -> u64 {
+        if self.annual_aggregate_limit == 0 {
+            return u64::MAX;
+        }
+        self.annual_aggregate_limit
+            .saturating_sub(self.aggregate_paid[self.aggregate_index(incident_time)])
+    }
+
+    /// Checks if the InsuranceContract covers all perils rather than a list of them
+    pub fn is_all_risk(&self) -> bool {
+        self.perils.iter().all(|peril| peril.code == NO_PERIL)
+    }
+
+    /// Position of a covered peril in the list of perils
+    pub fn peril_index(&self, code: u16) -> Option<usize> {
+        if code == NO_PERIL {
+            return None;
+        }
+        self.perils.iter().position(|peril| peril.code == code)
+    }
+
+    /// Checks if claims for the given peril are covered
+    pub fn covers_peril(&self, code: u16) -> bool {
+        self.is_all_risk() || self.peril_index(code).is_some()
+    }
+
+    /// Payouts left within the sub-limit of the given peril, in premium_mint tokens
+    pub fn remaining_peril_limit(&self, code: u16) -> u64 {
+        match self.peril_index(code) {
+            Some(index) if self.perils[index].sub_limit > 0 => self.perils[index]
+                .sub_limit
+                .saturating_sub(self.peril_claims_paid[index]),
+            Some(_) => u64::MAX,
+            None if self.is_all_risk() => u64::MAX,
+            None => 0,
+        }
+    }
+
+    /// Amount paid out for a claim for the given peril and incident time: the claimed amount
+    /// less the deductible, capped by the per-occurrence limit, the peril sub-limit,
+    /// the annual aggregate limit and the remaining coverage, in premium_mint tokens
+    pub fn claim_payout(&self, claim_amount: u64, peril: u16, incident_time: UnixTimestamp) -> u64 {
+        let mut payout = claim_amount.saturating_sub(self.deductible);
+        if self.per_occurrence_limit > 0 {
+            payout = payout.min(self.per_occurrence_limit);
+        }
+        payout
+            .min(self.remaining_peril_limit(peril))
+            .min(self.remaining_aggregate(incident_time))
+            .min(self.remaining_coverage())
+    }
+
+    /// Adds a claim payout for the given peril to the claims paid, to the peril and to the
+    /// annual aggregate of the policy year of the incident, whenever the claim is paid
+    pub fn record_claim_payout(
+        &mut self,
+        payout: u64,
+        peril: u16,
+        incident_time: UnixTimestamp,
+    ) -> Result<(), InsuranceContractError> {
+        if let Some(index) = self.peril_index(peril) {
+            self.peril_claims_paid[index] = self.peril_claims_paid[index]
+                .checked_add(payout)
+                .ok_or(InsuranceContractError::Overflow)?;
+        }
+        let index = self.aggregate_index(incident_time);
+        self.aggregate_paid[index] = self.aggregate_paid[index]
+            .checked_add(payout)
+            .ok_or(InsuranceContractError::Overflow)?;
+        self.claims_paid = self
+            .claims_paid
+            .checked_add(payout)
+            .ok_or(InsuranceContractError::Overflow)?;
+        Ok(())
+    }
+
+    /// Paid premium not earned at the given time, in premium_mint tokens.
+    /// Premium is earned evenly over the coverage period.
+    pub fn unearned_premium(&self, now: UnixTimestamp) -> u64 {
+        let period = self.coverage_end.saturating_sub(self.coverage_start);
+        if period <= 0 {
+            return 0;
+        }
+        let unearned_period = self
+            .coverage_end
+            .saturating_sub(now.max(self.coverage_start))
+            .clamp(0, period);
+        (self.premium_paid as u128 * unearned_period as u128 / period as u128) as u64
+    }
+
+    /// Premium refunded on cancellation at the given time: the unearned premium less
+    /// the short-rate penalty, in premium_mint tokens
+    pub fn cancellation_refund(&self, now: UnixTimestamp) -> u64 {
+        let unearned_premium = self.unearned_premium(now);
+        let penalty = unearned_premium as u128
+            * self.cancellation_penalty_bps.min(BASIS_POINTS) as u128
+            / BASIS_POINTS as u128;
+        unearned_premium - penalty as u64
+    }
+
+    /// Due date of the next unpaid installment, if any
+    pub fn next_installment_due(&self) -> Option<UnixTimestamp> {
+        if self.installments_paid >= self.installment_count {
+            return None;
+        }
+        self.installment_interval
+            .checked_mul(self.installments_paid as UnixTimestamp)
+            .and_then(|offset| self.coverage_start.checked_add(offset))
+    }
+
+    /// Checks if an installment is still unpaid at the given time after its grace period
+    pub fn is_installment_overdue(&self, now: UnixTimestamp) -> bool {
+        match self.next_installment_due() {
+            Some(due) => now >= due.saturating_add(self.grace_period),
+            None => false,
+        }
+    }
+
+    /// Consecutive claim-free terms credited to a renewal of the InsuranceContract,
+    /// any paid claim resets the count
+    pub fn renewal_claim_free_terms(&self) -> u8 {
+        if self.claims_paid == 0 {
+            self.claim_free_terms.saturating_add(1)
+        } else {
+            0
+        }
+    }
+
+    /// No-claims discount earned by the claim-free terms, in basis points
+    pub fn no_claims_discount_bps(&self) -> u16 {
+        (self.claim_free_terms as u32 * self.no_claims_bonus_bps as u32)
+            .min(MAX_NO_CLAIMS_DISCOUNT_BPS as u32) as u16
+    }
+
+    /// Applies the no-claims discount to a premium amount, in premium_mint tokens
+    pub fn discounted_premium(&self, premium_amount: u64) -> u64 {
+        let multiplier_bps = BASIS_POINTS - self.no_claims_discount_bps();
+        (premium_amount as u128 * multiplier_bps as u128 / BASIS_POINTS as u128) as u64
+    }
+}
+
+/// Terms an InsuranceContract is issued with
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct PolicyTerms {
+    /// Beginning of the coverage period
+    pub coverage_start: UnixTimestamp,
+    /// End of the coverage period
+    pub coverage_end: UnixTimestamp,
+    /// Maximum amount covered, in premium_mint tokens
+    pub sum_insured: u64,
+    /// Premium amount, in premium_mint tokens
+    pub premium_amount: u64,
+    /// Mint of the currency the premium and the sum insured are denominated in
+    pub premium_mint: Pubkey,
+    /// Authority allowed to approve or reject claims
+    pub claims_adjuster: Pubkey,
+    /// Lamports paid to the keeper expiring the contract, deposited by the authority
+    pub keeper_reward: u64,
+    /// Short-rate penalty kept from the unearned premium on cancellation, in basis points
+    pub cancellation_penalty_bps: u16,
+    /// No-claims discount of the renewal premium per consecutive claim-free term,
+    /// in basis points
+    pub no_claims_bonus_bps: u16,
+    /// Number of premium installments, zero when the premium is paid at once
+    pub installment_count: u8,
+    /// Amount of each installment, in premium_mint tokens
+    pub installment_amount: u64,
+    /// Time between installment due dates, the first one is due at the coverage start
+    pub installment_interval: UnixTimestamp,
+    /// Time an installment can stay unpaid after its due date before the contract lapses
+    pub grace_period: UnixTimestamp,
+    /// Assignment of the policy needs the consent of the authority
+    pub assignment_consent_required: bool,
+    /// Part of each claim borne by the policyholder, in premium_mint tokens
+    pub deductible: u64,
+    /// Maximum payout of a single claim, in premium_mint tokens, zero when unlimited
+    pub per_occurrence_limit: u64,
+    /// Maximum payouts within a policy year, in premium_mint tokens, zero when unlimited
+    pub annual_aggregate_limit: u64,
+    /// Perils claims can be filed for, unused entries have the NO_PERIL code.
+    /// All perils are covered when none is listed.
+    pub perils: [Peril; MAX_PERILS],
+    /// Time after the coverage start incidents are not covered in
+    pub waiting_period: UnixTimestamp,
+}
+
+/// Peril covered by an InsuranceContract
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct Peril {
+    /// Application defined code of the peril
+    pub code: u16,
+    /// Maximum payouts for the peril over the coverage, in premium_mint tokens,
+    /// zero when only the contract limits apply
+    pub sub_limit: u64,
+}
+
+/// Lifecycle status of an InsuranceProduct
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug)]
+pub enum ProductStatus {
+    /// InsuranceContracts can be issued from the product
+    Active,
+    /// Product is withdrawn, issued InsuranceContracts stay in force
+    Retired,
+}
+
+impl Default for ProductStatus {
+    fn default() -> Self {
+        ProductStatus::Active
+    }
+}
+
+/// Template of the terms InsuranceContracts are issued with, everything but the
+/// coverage dates
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct ProductTerms {
+    /// Length of the coverage period
+    pub coverage_period: UnixTimestamp,
+    /// Time after the coverage start incidents are not covered in
+    pub waiting_period: UnixTimestamp,
+    /// Maximum amount covered, in premium_mint tokens
+    pub sum_insured: u64,
+    /// Premium amount, in premium_mint tokens
+    pub premium_amount: u64,
+    /// Mint of the currency the premium and the sum insured are denominated in
+    pub premium_mint: Pubkey,
+    /// Authority allowed to approve or reject claims
+    pub claims_adjuster: Pubkey,
+    /// Lamports paid to the keeper expiring the contract, deposited by the authority
+    pub keeper_reward: u64,
+    /// Short-rate penalty kept from the unearned premium on cancellation, in basis points
+    pub cancellation_penalty_bps: u16,
+    /// No-claims discount of the renewal premium per consecutive claim-free term,
+    /// in basis points
+    pub no_claims_bonus_bps: u16,
+    /// Number of premium installments, zero when the premium is paid at once
+    pub installment_count: u8,
+    /// Amount of each installment, in premium_mint tokens
+    pub installment_amount: u64,
+    /// Time between installment due dates, the first one is due at the coverage start
+    pub installment_interval: UnixTimestamp,
+    /// Time an installment can stay unpaid after its due date before the contract lapses
+    pub grace_period: UnixTimestamp,
+    /// Assignment of the policy needs the consent of the authority
+    pub assignment_consent_required: bool,
+    /// Part of each claim borne by the policyholder, in premium_mint tokens
+    pub deductible: u64,
+    /// Maximum payout of a single claim, in premium_mint tokens, zero when unlimited
+    pub per_occurrence_limit: u64,
+    /// Maximum payouts within a policy year, in premium_mint tokens, zero when unlimited
+    pub annual_aggregate_limit: u64,
+    /// Covered perils, see PolicyTerms::perils
+    pub perils: [Peril; MAX_PERILS],
+}
+
+impl ProductTerms {
+    /// Terms of an InsuranceContract issued from the product with the given coverage start
+    pub fn policy_terms(&self, coverage_start: UnixTimestamp) -> PolicyTerms {
+        PolicyTerms {
+            coverage_start,
+            coverage_end: coverage_start.saturating_add(self.coverage_period),
+            sum_insured: self.sum_insured,
+            premium_amount: self.premium_amount,
+            premium_mint: self.premium_mint,
+            claims_adjuster: self.claims_adjuster,
+            keeper_reward: self.keeper_reward,
+            cancellation_penalty_bps: self.cancellation_penalty_bps,
+            no_claims_bonus_bps: self.no_claims_bonus_bps,
+            installment_count: self.installment_count,
+            installment_amount: self.installment_amount,
+            installment_interval: self.installment_interval,
+            grace_period: self.grace_period,
+            assignment_consent_required: self.assignment_consent_required,
+            deductible: self.deductible,
+            per_occurrence_limit: self.per_occurrence_limit,
+            annual_aggregate_limit: self.annual_aggregate_limit,
+            perils: self.perils,
+            waiting_period: self.waiting_period,
+        }
+    }
+}
+
+/// Product InsuranceContracts are issued from by its authority
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct InsuranceProductData {
+    pub account_type: AccountType,
+    /// Layout version, see INSURANCE_PRODUCT_VERSION
+    pub version: u8,
+    pub status: ProductStatus,
+    pub product_id: u32,
+    /// Authority allowed to modify the product and to issue InsuranceContracts from it
+    pub authority: Pubkey,
+    /// Terms of the InsuranceContracts issued from now on
+    pub terms: ProductTerms,
+    /// Number of InsuranceContracts issued from the product
+    pub policy_count: u32,
+    /// No InsuranceContracts can be issued from the product until the guardian unpauses it
+    pub paused: bool,
+}
+
+impl InsuranceProductData {
+    /// Checks if the account stores an InsuranceProduct
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::Product
+    }
+
+    /// Deserializes an InsuranceProduct account
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() != INSURANCE_PRODUCT_DATA_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        match (AccountType::deserialize(&mut &*input)?, input[1]) {
+            (AccountType::Uninitialized, _) => {}
+            (AccountType::Product, INSURANCE_PRODUCT_VERSION) => {}
+            (AccountType::Product, _) => {
+                return Err(InsuranceContractError::InvalidAccountVersion.into())
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+        Ok(Self::deserialize(&mut &*input)?)
+    }
+
+    /// Serializes into the account data and zeroes the reserved tail
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        pack_with_reserved_tail(self, dst)
+    }
+}
+
+/// Program-wide settings changed by the config admin
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct ConfigSettings {
+    /// Fee charged on top of every premium payment, in basis points
+    pub protocol_fee_bps: u16,
+    /// Owner of the token accounts the protocol fees are paid to
+    pub treasury: Pubkey,
+    /// Mints premiums can be paid in, unused entries are default.
+    /// Any mint is allowed when none is listed.
+    pub allowed_premium_mints: [Pubkey; MAX_ALLOWED_MINTS],
+    /// Authority allowed to pause and unpause the program and the products
+    pub guardian: Pubkey,
+}
+
+impl ConfigSettings {
+    /// Checks if premiums can be paid in the given mint
+    pub fn allows_premium_mint(&self, mint: &Pubkey) -> bool {
+        let default = Pubkey::default();
+        self.allowed_premium_mints
+            .iter()
+            .all(|allowed| *allowed == default)
+            || (*mint != default && self.allowed_premium_mints.contains(mint))
+    }
+
+    /// Protocol fee charged on top of a premium payment, in premium_mint tokens
+    pub fn protocol_fee(&self, premium_amount: u64) -> u64 {
+        (premium_amount as u128 * self.protocol_fee_bps as u128 / BASIS_POINTS as u128) as u64
+    }
+}
+
+/// Singleton account storing the program-wide configuration, see `find_config_address`
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct ConfigData {
+    pub account_type: AccountType,
+    /// Layout version, see CONFIG_VERSION
+    pub version: u8,
+    /// Authority allowed to update the settings
+    pub admin: Pubkey,
+    pub settings: ConfigSettings,
+    /// New business is stopped, see `InsuranceContractInstruction::is_pausable`
+    pub paused: bool,
+}
+
+impl ConfigData {
+    /// Checks if the account stores the program config
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::Config
+    }
+
+    /// Deserializes the program config account
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() != CONFIG_DATA_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        match (AccountType::deserialize(&mut &*input)?, input[1]) {
+            (AccountType::Uninitialized, _) => {}
+            (AccountType::Config, CONFIG_VERSION) => {}
+            (AccountType::Config, _) => {
+                return Err(InsuranceContractError::InvalidAccountVersion.into())
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+        Ok(Self::deserialize(&mut &*input)?)
+    }
+
+    /// Serializes into the account data and zeroes the reserved tail
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        pack_with_reserved_tail(self, dst)
+    }
+}
+
+/// Transfer of an InsuranceContract to another policyholder. The policy was assigned to
+/// the previous holder of the next assignment, or to the current holder for the latest one.
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct Assignment {
+    /// Policyholder the policy was assigned from
+    pub previous_holder: Pubkey,
+    /// Time of the assignment
+    pub assigned_at: UnixTimestamp,
+}
+
+/// Period the coverage of an InsuranceContract was Suspended or Lapsed
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct CoverageGap {
+    /// Time the coverage stopped
+    pub start: UnixTimestamp,
+    /// Time the coverage was reinstated, zero while it is not
+    pub end: UnixTimestamp,
+}
+
+impl CoverageGap {
+    /// Checks if the coverage is not reinstated yet
+    pub fn is_open(&self) -> bool {
+        self.end == 0
+    }
+
+    /// Checks if the given time falls within the gap
+    pub fn contains(&self, time: UnixTimestamp) -> bool {
+        time >= self.start && (self.is_open() || time < self.end)
+    }
+}
+
+/// URI of an off-chain document, UTF-8 encoded and zero padded to MAX_DOCUMENT_URI_LEN bytes
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy)]
+pub struct DocumentUri(pub [u8; MAX_DOCUMENT_URI_LEN]);
+
+impl DocumentUri {
+    /// Pads the URI, `None` when it is too long or contains a zero byte
+    pub fn new(uri: &str) -> Option<Self> {
+        if uri.len() > MAX_DOCUMENT_URI_LEN || uri.bytes().any(|byte| byte == 0) {
+            return None;
+        }
+        let mut padded = [0; MAX_DOCUMENT_URI_LEN];
+        padded[..uri.len()].copy_from_slice(uri.as_bytes());
+        Some(Self(padded))
+    }
+
+    /// Length of the URI without the padding
+    fn len(&self) -> usize {
+        self.0
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(MAX_DOCUMENT_URI_LEN)
+    }
+
+    /// Checks that the URI is UTF-8 encoded and followed by the zero padding only
+    pub fn is_valid(&self) -> bool {
+        let len = self.len();
+        std::str::from_utf8(&self.0[..len]).is_ok() && self.0[len..].iter().all(|byte| *byte == 0)
+    }
+
+    /// URI without the padding, empty when it is not valid UTF-8
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0[..self.len()]).unwrap_or_default()
+    }
+}
+
+impl Default for DocumentUri {
+    fn default() -> Self {
+        Self([0; MAX_DOCUMENT_URI_LEN])
+    }
+}
+
+impl std::fmt::Debug for DocumentUri {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("DocumentUri").field(&self.as_str()).finish()
+    }
+}
+
+/// Lifecycle status of a Claim
+#[repr(u8)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug)]
+pub enum ClaimStatus {
+    /// Filed by the policyholder, waiting for the adjuster decision
+    Submitted,
+    /// Accepted by the adjuster
+    Approved,
+    /// Declined by the adjuster
+    Rejected,
+    /// Approved amount was paid out
+    Paid,
+}
+
+impl Default for ClaimStatus {
+    fn default() -> Self {
+        ClaimStatus::Submitted
+    }
+}
+
+/// Claim filed against an InsuranceContract
+#[repr(C)]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct ClaimData {
+    pub account_type: AccountType,
+    /// Layout version, see CLAIM_VERSION
+    pub version: u8,
+    pub status: ClaimStatus,
+    /// InsuranceContract the claim is filed against
+    pub insurance_contract: Pubkey,
+    /// Index of the claim within the InsuranceContract
+    pub claim_index: u32,
+    /// Policyholder who filed the claim
+    pub claimant: Pubkey,
+    /// Claimed amount, in premium_mint tokens
+    pub amount: u64,
+    /// Time the insured event happened
+    pub incident_time: UnixTimestamp,
+    /// Time the claim was filed
+    pub submitted_at: UnixTimestamp,
+    /// Hash of the off-chain evidence documents
+    pub evidence_hash: [u8; 32],
+    /// Amount paid out after the deductible and the limits, in premium_mint tokens
+    pub payout: u64,
+    /// Code of the peril the claim is filed for
+    pub peril: u16,
+}
+
+impl ClaimData {
+    /// Checks if the account stores a Claim
+    pub fn is_initialized(&self) -> bool {
+        self.account_type == AccountType::Claim
+    }
+
+    /// Deserializes a Claim account
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        if input.len() != CLAIM_DATA_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        match (AccountType::deserialize(&mut &*input)?, input[1]) {
+            (AccountType::Uninitialized, _) => {}
+            (AccountType::Claim, CLAIM_VERSION) => {}
+            (AccountType::Claim, _) => {
+                return Err(InsuranceContractError::InvalidAccountVersion.into())
+            }
+            _ => return Err(ProgramError::InvalidAccountData),
+        }
+        Ok(Self::deserialize(&mut &*input)?)
+    }
+
+    /// Serializes into the account data and zeroes the reserved tail
+    pub fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        pack_with_reserved_tail(self, dst)
+    }
+}
+
+/// Serializes the account state at the start of the account data and zeroes the rest,
+/// so fields appended later deserialize to their defaults
+fn pack_with_reserved_tail<T: BorshSerialize>(
+    state: &T,
+    dst: &mut [u8],
+) -> Result<(), ProgramError> {
+    let data = state.try_to_vec()?;
+    if data.len() > dst.len() {
+        return Err(ProgramError::AccountDataTooSmall);
+    }
+    let (used, reserved) = dst.split_at_mut(data.len());
+    used.copy_from_slice(&data);
+    reserved.iter_mut().for_each(|byte| *byte = 0);
+    Ok(())
+}
+
+/// Unversioned layout used before the authority was stored on the account
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct InsuranceContractDataV0 {
     pub is_initialized: bool,
     pub is_closed: bool,
     pub insurance_contract_id: u32,
 }
+
+impl From<InsuranceContractDataV0> for InsuranceContractData {
+    fn from(legacy: InsuranceContractDataV0) -> Self {
+        Self {
+            account_type: legacy_account_type(legacy.is_initialized),
+            version: INSURANCE_CONTRACT_VERSION,
+            status: legacy_status(legacy.is_closed),
+            insurance_contract_id: legacy.insurance_contract_id,
+            authority: Pubkey::default(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Unversioned layout with the authority appended
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct InsuranceContractDataV1 {
+    pub is_initialized: bool,
+    pub is_closed: bool,
+    pub insurance_contract_id: u32,
+    pub authority: Pubkey,
+}
+
+impl From<InsuranceContractDataV1> for InsuranceContractData {
+    fn from(legacy: InsuranceContractDataV1) -> Self {
+        Self {
+            account_type: legacy_account_type(legacy.is_initialized),
+            version: INSURANCE_CONTRACT_VERSION,
+            status: legacy_status(legacy.is_closed),
+            insurance_contract_id: legacy.insurance_contract_id,
+            authority: legacy.authority,
+            ..Self::default()
+        }
+    }
+}
+
+/// Versioned layout with the is_initialized and is_closed flags
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Clone, Copy, Debug, Default)]
+pub struct InsuranceContractDataV2 {
+    pub account_type: AccountType,
+    pub version: u8,
+    pub is_initialized: bool,
+    pub is_closed: bool,
+    pub insurance_contract_id: u32,
+    pub authority: Pubkey,
+}
+
+impl From<InsuranceContractDataV2> for InsuranceContractData {
+    fn from(legacy: InsuranceContractDataV2) -> Self {
+        Self {
+            account_type: legacy_account_type(legacy.is_initialized),
+            version: INSURANCE_CONTRACT_VERSION,
+            status: legacy_status(legacy.is_closed),
+            insurance_contract_id: legacy.insurance_contract_id,
+            authority: legacy.authority,
+            ..Self::default()
+        }
+    }
+}
+
+fn legacy_account_type(is_initialized: bool) -> AccountType {
+    if is_initialized {
+        AccountType::InsuranceContract
+    } else {
+        AccountType::Uninitialized
+    }
+}
+
+fn legacy_status(is_closed: bool) -> InsuranceContractStatus {
+    if is_closed {
+        InsuranceContractStatus::Closed
+    } else {
+        InsuranceContractStatus::Active
+    }
+}
+
+// Layout compatibility tests
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INSURANCE_CONTRACT_ID: u32 = 11223344;
+    const AUTHORITY: [u8; 32] = [7; 32];
+
+    fn v0_fixture(is_closed: bool) -> Vec<u8> {
+        let mut data = vec![1, is_closed as u8];
+        data.extend_from_slice(&INSURANCE_CONTRACT_ID.to_le_bytes());
+        data
+    }
+
+    fn v1_fixture(is_closed: bool) -> Vec<u8> {
+        let mut data = v0_fixture(is_closed);
+        data.extend_from_slice(&AUTHORITY);
+        data
+    }
+
+    fn v2_fixture(is_closed: bool) -> Vec<u8> {
+        let mut data = vec![AccountType::InsuranceContract as u8, 2];
+        data.extend_from_slice(&v1_fixture(is_closed));
+        data.resize(INSURANCE_CONTRACT_DATA_LEN, 0);
+        data
+    }
+
+    fn v3_fixture(status: InsuranceContractStatus) -> Vec<u8> {
+        let mut data = vec![AccountType::InsuranceContract as u8, 3, status as u8];
+        data.extend_from_slice(&INSURANCE_CONTRACT_ID.to_le_bytes());
+        data.extend_from_slice(&AUTHORITY);
+        data.resize(INSURANCE_CONTRACT_DATA_LEN, 0);
+        data
+    }
+
+    fn expected_status(is_closed: bool) -> InsuranceContractStatus {
+        if is_closed {
+            InsuranceContractStatus::Closed
+        } else {
+            InsuranceContractStatus::Active
+        }
+    }
+
+    #[test]
+    fn test_unpack_v0() {
+        for is_closed in [false, true].iter() {
+            let data = v0_fixture(*is_closed);
+            assert_eq!(data.len(), INSURANCE_CONTRACT_DATA_V0_LEN);
+            assert_eq!(
+                InsuranceContractData::unpack(&data),
+                Err(InsuranceContractError::MigrationRequired.into())
+            );
+
+            let insurance_contract_data =
+                InsuranceContractData::unpack_from_any_version(&data).unwrap();
+            assert_eq!(
+                insurance_contract_data.account_type,
+                AccountType::InsuranceContract
+            );
+            assert_eq!(insurance_contract_data.version, INSURANCE_CONTRACT_VERSION);
+            assert_eq!(insurance_contract_data.status, expected_status(*is_closed));
+            assert_eq!(
+                insurance_contract_data.insurance_contract_id,
+                INSURANCE_CONTRACT_ID
+            );
+            assert_eq!(insurance_contract_data.authority, Pubkey::default());
+        }
+    }
+
+    #[test]
+    fn test_unpack_v1() {
+        for is_closed in [false, true].iter() {
+            let data = v1_fixture(*is_closed);
+            assert_eq!(data.len(), INSURANCE_CONTRACT_DATA_V1_LEN);
+            assert_eq!(
+                InsuranceContractData::unpack(&data),
+                Err(InsuranceContractError::MigrationRequired.into())
+            );
+
+            let insurance_contract_data =
+                InsuranceContractData::unpack_from_any_version(&data).unwrap();
+            assert!(insurance_contract_data.is_initialized());
+            assert_eq!(insurance_contract_data.status, expected_status(*is_closed));
+            assert_eq!(
+                insurance_contract_data.insurance_contract_id,
+                INSURANCE_CONTRACT_ID
+            );
+            assert_eq!(
+                insurance_contract_data.authority,
+                Pubkey::new_from_array(AUTHORITY)
+            );
+        }
+    }
+
+    #[test]
+    fn test_unpack_v2() {
+        for is_closed in [false, true].iter() {
+            let data = v2_fixture(*is_closed);
+            assert_eq!(
+                InsuranceContractData::unpack(&data),
+                Err(InsuranceContractError::MigrationRequired.into())
+            );
+
+            let insurance_contract_data =
+                InsuranceContractData::unpack_from_any_version(&data).unwrap();
+            assert_eq!(
+                insurance_contract_data,
+                InsuranceContractDataV1::try_from_slice(&v1_fixture(*is_closed))
+                    .unwrap()
+                    .into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_unpack_v3() {
+        for is_closed in [false, true].iter() {
+            let data = v3_fixture(expected_status(*is_closed));
+            let insurance_contract_data = InsuranceContractData::unpack(&data).unwrap();
+            // Accounts saved before documents were anchored decode as having none
+            assert_eq!(insurance_contract_data.document_hash, [0; 32]);
+            assert!(insurance_contract_data.document_history().is_empty());
+            assert_eq!(
+                insurance_contract_data,
+                InsuranceContractData::unpack_from_any_version(&data).unwrap()
+            );
+            assert_eq!(
+                insurance_contract_data,
+                InsuranceContractData::unpack_from_any_version(&v2_fixture(*is_closed)).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_serialized_len() {
+        assert_eq!(
+            InsuranceContractData::default().try_to_vec().unwrap().len(),
+            INSURANCE_CONTRACT_SERIALIZED_LEN
+        );
+    }
+
+    #[test]
+    fn test_unpack_uninitialized() {
+        let insurance_contract_data =
+            InsuranceContractData::unpack(&[0; INSURANCE_CONTRACT_DATA_LEN]).unwrap();
+        assert_eq!(insurance_contract_data, InsuranceContractData::default());
+        assert!(!insurance_contract_data.is_initialized());
+
+        let mut data = v1_fixture(false);
+        data[0] = 0;
+        assert!(!InsuranceContractData::unpack_from_any_version(&data)
+            .unwrap()
+            .is_initialized());
+    }
+
+    #[test]
+    fn test_unpack_invalid() {
+        let mut data = v3_fixture(InsuranceContractStatus::Active);
+        data[1] = INSURANCE_CONTRACT_VERSION + 1;
+        assert_eq!(
+            InsuranceContractData::unpack(&data),
+            Err(InsuranceContractError::InvalidAccountVersion.into())
+        );
+
+        assert_eq!(
+            InsuranceContractData::unpack_from_any_version(&[1; 7]),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_pack_zeroes_reserved_tail() {
+        let data = v3_fixture(InsuranceContractStatus::Closed);
+        let insurance_contract_data = InsuranceContractData::unpack(&data).unwrap();
+        let mut packed = vec![0xff; INSURANCE_CONTRACT_DATA_LEN];
+        insurance_contract_data.pack(&mut packed).unwrap();
+        assert_eq!(packed, data);
+
+        assert_eq!(
+            insurance_contract_data.pack(&mut [0; INSURANCE_CONTRACT_DATA_V1_LEN]),
+            Err(ProgramError::AccountDataTooSmall)
+        );
+    }
+
+    #[test]
+    fn test_pack_reclaimed() {
+        let mut data = v3_fixture(InsuranceContractStatus::Closed);
+        InsuranceContractData::pack_reclaimed(&mut data);
+        assert_eq!(data[0], AccountType::Reclaimed as u8);
+        assert!(data[1..].iter().all(|byte| *byte == 0));
+        assert_eq!(
+            InsuranceContractData::unpack(&data),
+            Err(InsuranceContractError::AlreadyClosed.into())
+        );
+    }
+
+    #[test]
+    fn test_cancellation_refund() {
+        let insurance_contract_data = InsuranceContractData {
+            coverage_start: 1_000,
+            coverage_end: 2_000,
+            premium_paid: 10_000,
+            ..InsuranceContractData::default()
+        };
+        assert_eq!(insurance_contract_data.unearned_premium(0), 10_000);
+        assert_eq!(insurance_contract_data.unearned_premium(1_000), 10_000);
+        assert_eq!(insurance_contract_data.unearned_premium(1_250), 7_500);
+        assert_eq!(insurance_contract_data.unearned_premium(1_999), 10);
+        assert_eq!(insurance_contract_data.unearned_premium(2_000), 0);
+        assert_eq!(insurance_contract_data.unearned_premium(i64::MAX), 0);
+        assert_eq!(insurance_contract_data.cancellation_refund(1_250), 7_500);
+
+        let insurance_contract_data = InsuranceContractData {
+            cancellation_penalty_bps: 1_000,
+            ..insurance_contract_data
+        };
+        assert_eq!(insurance_contract_data.cancellation_refund(1_250), 6_750);
+        assert_eq!(insurance_contract_data.cancellation_refund(2_000), 0);
+
+        let insurance_contract_data = InsuranceContractData {
+            cancellation_penalty_bps: BASIS_POINTS,
+            ..insurance_contract_data
+        };
+        assert_eq!(insurance_contract_data.cancellation_refund(1_250), 0);
+
+        // Premium can't be refunded for an empty coverage period
+        let insurance_contract_data = InsuranceContractData {
+            coverage_start: i64::MIN,
+            coverage_end: i64::MIN,
+            ..insurance_contract_data
+        };
+        assert_eq!(insurance_contract_data.unearned_premium(i64::MIN), 0);
+    }
+
+    #[test]
+    fn test_no_claims_discount() {
+        let insurance_contract_data = InsuranceContractData {
+            no_claims_bonus_bps: 1_000,
+            ..InsuranceContractData::default()
+        };
+        assert_eq!(insurance_contract_data.renewal_claim_free_terms(), 1);
+        assert_eq!(insurance_contract_data.no_claims_discount_bps(), 0);
+        assert_eq!(insurance_contract_data.discounted_premium(10_000), 10_000);
+
+        let insurance_contract_data = InsuranceContractData {
+            claim_free_terms: 2,
+            ..insurance_contract_data
+        };
+        assert_eq!(insurance_contract_data.renewal_claim_free_terms(), 3);
+        assert_eq!(insurance_contract_data.no_claims_discount_bps(), 2_000);
+        assert_eq!(insurance_contract_data.discounted_premium(10_000), 8_000);
+
+        // The discount is capped
+        let insurance_contract_data = InsuranceContractData {
+            claim_free_terms: u8::MAX,
+            no_claims_bonus_bps: u16::MAX,
+            ..insurance_contract_data
+        };
+        assert_eq!(insurance_contract_data.renewal_claim_free_terms(), u8::MAX);
+        assert_eq!(
+            insurance_contract_data.no_claims_discount_bps(),
+            MAX_NO_CLAIMS_DISCOUNT_BPS
+        );
+        assert_eq!(
+            insurance_contract_data.discounted_premium(u64::MAX),
+            u64::MAX / 2
+        );
+
+        // A paid claim resets the claim-free terms
+        let insurance_contract_data = InsuranceContractData {
+            claims_paid: 1,
+            ..insurance_contract_data
+        };
+        assert_eq!(insurance_contract_data.renewal_claim_free_terms(), 0);
+    }
+
+    #[test]
+    fn test_installment_schedule() {
+        let mut insurance_contract_data = InsuranceContractData {
+            coverage_start: 1_000,
+            installment_count: 3,
+            installment_amount: 100,
+            installment_interval: 1_000,
+            grace_period: 100,
+            ..InsuranceContractData::default()
+        };
+        assert_eq!(insurance_contract_data.next_installment_due(), Some(1_000));
+        assert!(!insurance_contract_data.is_installment_overdue(1_099));
+        assert!(insurance_contract_data.is_installment_overdue(1_100));
+
+        insurance_contract_data.installments_paid = 2;
+        assert_eq!(insurance_contract_data.next_installment_due(), Some(3_000));
+        assert!(!insurance_contract_data.is_installment_overdue(3_099));
+        assert!(insurance_contract_data.is_installment_overdue(3_100));
+
+        insurance_contract_data.installments_paid = 3;
+        assert_eq!(insurance_contract_data.next_installment_due(), None);
+        assert!(!insurance_contract_data.is_installment_overdue(i64::MAX));
+
+        // Premium paid at once has no schedule
+        assert_eq!(
+            InsuranceContractData::default().next_installment_due(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_assignment_history() {
+        let authority = Pubkey::new_from_array([1; 32]);
+        let mut insurance_contract_data = InsuranceContractData {
+            authority,
+            ..InsuranceContractData::default()
+        };
+        assert_eq!(insurance_contract_data.policyholder(), authority);
+        assert_eq!(insurance_contract_data.assignment_history(), vec![]);
+
+        let holders: Vec<Pubkey> = (2..8).map(|i| Pubkey::new_from_array([i; 32])).collect();
+        for (i, holder) in holders.iter().enumerate() {
+            let previous_holder = insurance_contract_data.policyholder();
+            insurance_contract_data
+                .record_assignment(previous_holder, 100 * i as UnixTimestamp)
+                .unwrap();
+            insurance_contract_data.holder = *holder;
+        }
+        assert_eq!(insurance_contract_data.policyholder(), holders[5]);
+        assert_eq!(insurance_contract_data.assignment_count, 6);
+
+        // Only the latest assignments are kept
+        let history = insurance_contract_data.assignment_history();
+        assert_eq!(history.len(), MAX_ASSIGNMENT_HISTORY);
+        assert_eq!(
+            history[0],
+            Assignment {
+                previous_holder: holders[1],
+                assigned_at: 200,
+            }
+        );
+        assert_eq!(
+            history[3],
+            Assignment {
+                previous_holder: holders[4],
+                assigned_at: 500,
+            }
+        );
+    }
+
+    #[test]
+    fn test_coverage_gaps() {
+        let mut insurance_contract_data = InsuranceContractData::default();
+        assert!(!insurance_contract_data.is_in_coverage_gap(100));
+
+        // Suspended from 100 to 200, lapsed since 300
+        insurance_contract_data.open_coverage_gap(100);
+        insurance_contract_data.close_coverage_gap(200);
+        insurance_contract_data.open_coverage_gap(300);
+        assert!(!insurance_contract_data.is_in_coverage_gap(99));
+        assert!(insurance_contract_data.is_in_coverage_gap(100));
+        assert!(insurance_contract_data.is_in_coverage_gap(199));
+        assert!(!insurance_contract_data.is_in_coverage_gap(200));
+        assert!(insurance_contract_data.is_in_coverage_gap(1_000_000));
+
+        // Reinstating twice doesn't move the end of the gap
+        insurance_contract_data.close_coverage_gap(400);
+        insurance_contract_data.close_coverage_gap(500);
+        assert_eq!(
+            insurance_contract_data.coverage_gap_history(),
+            &[
+                CoverageGap {
+                    start: 100,
+                    end: 200
+                },
+                CoverageGap {
+                    start: 300,
+                    end: 400
+                },
+            ]
+        );
+
+        // Gaps past the last one are merged into it
+        for start in (600..1_000).step_by(100) {
+            insurance_contract_data.open_coverage_gap(start);
+            insurance_contract_data.close_coverage_gap(start + 50);
+        }
+        assert_eq!(
+            insurance_contract_data.coverage_gap_count as usize,
+            MAX_COVERAGE_GAPS
+        );
+        let history = insurance_contract_data.coverage_gap_history();
+        assert_eq!(
+            history[2],
+            CoverageGap {
+                start: 600,
+                end: 650
+            }
+        );
+        assert_eq!(
+            history[3],
+            CoverageGap {
+                start: 700,
+                end: 950
+            }
+        );
+        assert!(insurance_contract_data.is_in_coverage_gap(860));
+        assert!(!insurance_contract_data.is_in_coverage_gap(950));
+    }
+
+    #[test]
+    fn test_document_history() {
+        let mut insurance_contract_data = InsuranceContractData::default();
+        assert_eq!(
+            insurance_contract_data.document_history(),
+            Vec::<[u8; 32]>::new()
+        );
+
+        // The first document replaces nothing, a new URI of the same document neither
+        let uri = DocumentUri::new("ipfs://wording-v1").unwrap();
+        insurance_contract_data
+            .amend_document([1; 32], uri)
+            .unwrap();
+        let uri = DocumentUri::new("https://example.com/wording-v1.pdf").unwrap();
+        insurance_contract_data
+            .amend_document([1; 32], uri)
+            .unwrap();
+        assert_eq!(insurance_contract_data.document_amendment_count, 0);
+        assert_eq!(
+            insurance_contract_data.document_uri.as_str(),
+            "https://example.com/wording-v1.pdf"
+        );
+
+        for i in 2..8 {
+            insurance_contract_data
+                .amend_document([i; 32], DocumentUri::default())
+                .unwrap();
+        }
+        assert_eq!(insurance_contract_data.document_hash, [7; 32]);
+        assert_eq!(insurance_contract_data.document_amendment_count, 6);
+        assert_eq!(insurance_contract_data.document_uri.as_str(), "");
+
+        // Only the latest replaced hashes are kept
+        assert_eq!(
+            insurance_contract_data.document_history(),
+            vec![[5; 32], [6; 32]]
+        );
+
+        // The whole layout fits the account
+        let mut data = vec![0; INSURANCE_CONTRACT_DATA_LEN];
+        InsuranceContractData {
+            document_uri: DocumentUri([b'a'; MAX_DOCUMENT_URI_LEN]),
+            ..insurance_contract_data
+        }
+        .pack(&mut data)
+        .unwrap();
+    }
+
+    #[test]
+    fn test_document_uri() {
+        let uri = "a".repeat(MAX_DOCUMENT_URI_LEN);
+        assert_eq!(DocumentUri::new(&uri).unwrap().as_str(), uri);
+        assert!(DocumentUri::new(&uri).unwrap().is_valid());
+        assert_eq!(DocumentUri::new(&format!("{}a", uri)), None);
+        assert_eq!(DocumentUri::new("ipfs://\0"), None);
+        assert!(DocumentUri::default().is_valid());
+
+        let mut padded = DocumentUri::new("ar://wording").unwrap();
+        padded.0[20] = b'x';
+        assert!(!padded.is_valid());
+        padded.0[20] = 0;
+        padded.0[0] = 0xff;
+        assert!(!padded.is_valid());
+        assert_eq!(padded.as_str(), "");
+    }
+
+    #[test]
+    fn test_claim_payout() {
+        let year = SECONDS_PER_POLICY_YEAR;
+        let mut insurance_contract_data = InsuranceContractData {
+            coverage_start: 1_000,
+            coverage_end: 1_000 + 2 * year,
+            sum_insured: 100_000,
+            ..InsuranceContractData::default()
+        };
+        assert_eq!(
+            insurance_contract_data.claim_payout(5_000, NO_PERIL, 2_000),
+            5_000
+        );
+
+        // Deductible is borne by the policyholder
+        insurance_contract_data.deductible = 1_000;
+        assert_eq!(
+            insurance_contract_data.claim_payout(5_000, NO_PERIL, 2_000),
+            4_000
+        );
+        assert_eq!(
+            insurance_contract_data.claim_payout(500, NO_PERIL, 2_000),
+            0
+        );
+
+        // A single claim is capped by the per-occurrence limit
+        insurance_contract_data.per_occurrence_limit = 30_000;
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 2_000),
+            30_000
+        );
+
+        // Payouts within a policy year are capped by the annual aggregate limit
+        insurance_contract_data.annual_aggregate_limit = 50_000;
+        insurance_contract_data
+            .record_claim_payout(30_000, NO_PERIL, 2_000)
+            .unwrap();
+        insurance_contract_data
+            .record_claim_payout(15_000, NO_PERIL, 3_000)
+            .unwrap();
+        assert_eq!(insurance_contract_data.remaining_aggregate(4_000), 5_000);
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 4_000),
+            5_000
+        );
+
+        // The aggregate is restored in the next policy year, the sum insured still caps it
+        assert_eq!(insurance_contract_data.policy_year(1_000 + year), 1);
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 1_000 + year),
+            30_000
+        );
+        insurance_contract_data
+            .record_claim_payout(30_000, NO_PERIL, 1_000 + year)
+            .unwrap();
+        insurance_contract_data
+            .record_claim_payout(20_000, NO_PERIL, 2_000 + year)
+            .unwrap();
+        assert_eq!(insurance_contract_data.aggregate_paid, [45_000, 50_000, 0]);
+        assert_eq!(insurance_contract_data.claims_paid, 95_000);
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 1_000 + 5 * year),
+            0
+        );
+        assert_eq!(insurance_contract_data.policy_year(1_000 + 5 * year), 1);
+
+        // Without an aggregate limit the remaining coverage caps the payout
+        insurance_contract_data.annual_aggregate_limit = 0;
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 1_000 + year),
+            5_000
+        );
+    }
+
+    #[test]
+    fn test_aggregate_by_incident_year() {
+        let year = SECONDS_PER_POLICY_YEAR;
+        let mut insurance_contract_data = InsuranceContractData {
+            coverage_start: 1_000,
+            coverage_end: 1_000 + 2 * year,
+            sum_insured: 100_000,
+            annual_aggregate_limit: 40_000,
+            ..InsuranceContractData::default()
+        };
+
+        // A claim of the second year is paid before a late claim of the first year
+        insurance_contract_data
+            .record_claim_payout(30_000, NO_PERIL, 1_000 + year)
+            .unwrap();
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 2_000),
+            40_000
+        );
+        insurance_contract_data
+            .record_claim_payout(40_000, NO_PERIL, 2_000)
+            .unwrap();
+        assert_eq!(insurance_contract_data.aggregate_paid, [40_000, 30_000, 0]);
+
+        // Each claim counts against the year of its incident only
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 3_000),
+            0
+        );
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, NO_PERIL, 2_000 + year),
+            10_000
+        );
+    }
+
+    #[test]
+    fn test_peril_sub_limits() {
+        let mut insurance_contract_data = InsuranceContractData {
+            coverage_start: 1_000,
+            coverage_end: 1_000 + SECONDS_PER_POLICY_YEAR,
+            sum_insured: 100_000,
+            ..InsuranceContractData::default()
+        };
+        assert!(insurance_contract_data.is_all_risk());
+        assert!(insurance_contract_data.covers_peril(NO_PERIL));
+        assert!(insurance_contract_data.covers_peril(7));
+
+        insurance_contract_data.perils[0] = Peril {
+            code: 7,
+            sub_limit: 20_000,
+        };
+        insurance_contract_data.perils[3] = Peril {
+            code: 9,
+            sub_limit: 0,
+        };
+        assert!(!insurance_contract_data.is_all_risk());
+        assert!(!insurance_contract_data.covers_peril(NO_PERIL));
+        assert!(!insurance_contract_data.covers_peril(8));
+        assert!(insurance_contract_data.covers_peril(9));
+        assert_eq!(insurance_contract_data.claim_payout(5_000, 8, 2_000), 0);
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, 9, 2_000),
+            50_000
+        );
+
+        // Payouts for a peril are capped by its sub-limit
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, 7, 2_000),
+            20_000
+        );
+        insurance_contract_data
+            .record_claim_payout(15_000, 7, 2_000)
+            .unwrap();
+        assert_eq!(insurance_contract_data.peril_claims_paid[0], 15_000);
+        assert_eq!(insurance_contract_data.remaining_peril_limit(7), 5_000);
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, 7, 3_000),
+            5_000
+        );
+        assert_eq!(
+            insurance_contract_data.claim_payout(50_000, 9, 3_000),
+            50_000
+        );
+        assert_eq!(insurance_contract_data.claims_paid, 15_000);
+    }
+
+    #[test]
+    fn test_pack_unpack_claim() {
+        let claim_data = ClaimData {
+            account_type: AccountType::Claim,
+            version: CLAIM_VERSION,
+            status: ClaimStatus::Approved,
+            insurance_contract: Pubkey::new_from_array([3; 32]),
+            claim_index: 2,
+            claimant: Pubkey::new_from_array(AUTHORITY),
+            amount: 5_000,
+            incident_time: 1_000,
+            submitted_at: 2_000,
+            evidence_hash: [9; 32],
+            payout: 4_000,
+            peril: 7,
+        };
+        let mut data = [1; CLAIM_DATA_LEN];
+        claim_data.pack(&mut data).unwrap();
+        assert_eq!(ClaimData::unpack(&data).unwrap(), claim_data);
+
+        assert!(!ClaimData::unpack(&[0; CLAIM_DATA_LEN])
+            .unwrap()
+            .is_initialized());
+        assert_eq!(
+            ClaimData::unpack(&data[..CLAIM_DATA_LEN - 1]),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        data[0] = AccountType::InsuranceContract as u8;
+        assert_eq!(
+            ClaimData::unpack(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+    }
+
+    #[test]
+    fn test_pack_unpack_product() {
+        let mut perils = [Peril::default(); MAX_PERILS];
+        perils[0] = Peril {
+            code: 7,
+            sub_limit: 10_000,
+        };
+        let product_data = InsuranceProductData {
+            account_type: AccountType::Product,
+            version: INSURANCE_PRODUCT_VERSION,
+            status: ProductStatus::Retired,
+            product_id: 5,
+            authority: Pubkey::new_from_array(AUTHORITY),
+            terms: ProductTerms {
+                coverage_period: SECONDS_PER_POLICY_YEAR,
+                waiting_period: 30 * 24 * 60 * 60,
+                sum_insured: 100_000,
+                premium_mint: Pubkey::new_from_array([3; 32]),
+                perils,
+                ..ProductTerms::default()
+            },
+            policy_count: 1_000,
+            paused: true,
+        };
+        let mut data = [1; INSURANCE_PRODUCT_DATA_LEN];
+        product_data.pack(&mut data).unwrap();
+        assert_eq!(InsuranceProductData::unpack(&data).unwrap(), product_data);
+
+        assert!(
+            !InsuranceProductData::unpack(&[0; INSURANCE_PRODUCT_DATA_LEN])
+                .unwrap()
+                .is_initialized()
+        );
+        data[0] = AccountType::Claim as u8;
+        assert_eq!(
+            InsuranceProductData::unpack(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        // Issued terms start at the given time and keep the product terms
+        let terms = product_data.terms.policy_terms(1_000);
+        assert_eq!(terms.coverage_start, 1_000);
+        assert_eq!(terms.coverage_end, 1_000 + SECONDS_PER_POLICY_YEAR);
+        assert_eq!(terms.waiting_period, product_data.terms.waiting_period);
+        assert_eq!(terms.premium_mint, product_data.terms.premium_mint);
+        assert_eq!(terms.perils, perils);
+    }
+
+    #[test]
+    fn test_pack_unpack_config() {
+        let mut allowed_premium_mints = [Pubkey::default(); MAX_ALLOWED_MINTS];
+        allowed_premium_mints[0] = Pubkey::new_from_array([3; 32]);
+        let config_data = ConfigData {
+            account_type: AccountType::Config,
+            version: CONFIG_VERSION,
+            admin: Pubkey::new_from_array(AUTHORITY),
+            settings: ConfigSettings {
+                protocol_fee_bps: 250,
+                treasury: Pubkey::new_from_array([4; 32]),
+                allowed_premium_mints,
+                guardian: Pubkey::new_from_array([5; 32]),
+            },
+            paused: true,
+        };
+        let mut data = [1; CONFIG_DATA_LEN];
+        config_data.pack(&mut data).unwrap();
+        assert_eq!(ConfigData::unpack(&data).unwrap(), config_data);
+        assert!(!ConfigData::unpack(&[0; CONFIG_DATA_LEN])
+            .unwrap()
+            .is_initialized());
+        data[0] = AccountType::Product as u8;
+        assert_eq!(
+            ConfigData::unpack(&data),
+            Err(ProgramError::InvalidAccountData)
+        );
+
+        let settings = config_data.settings;
+        assert_eq!(settings.protocol_fee(10_000), 250);
+        assert_eq!(settings.protocol_fee(39), 0);
+        assert_eq!(settings.protocol_fee(u64::MAX), u64::MAX / 40);
+        assert!(settings.allows_premium_mint(&Pubkey::new_from_array([3; 32])));
+        assert!(!settings.allows_premium_mint(&Pubkey::new_unique()));
+        assert!(!settings.allows_premium_mint(&Pubkey::default()));
+        assert!(ConfigSettings::default().allows_premium_mint(&Pubkey::new_unique()));
+    }
+}
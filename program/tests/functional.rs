@@ -0,0 +1,2353 @@
+use insurance_contract::{
+    error::InsuranceContractError,
+    find_claim_address, find_insurance_contract_address, find_policy_mint_address,
+    find_policy_token_address, find_vault_address, id,
+    processor::Processor,
+    state::{
+        ClaimData, ClaimStatus, InsuranceContractData, InsuranceContractStatus, Peril, PolicyTerms,
+        CLAIM_DATA_LEN, INSURANCE_CONTRACT_DATA_LEN, MAX_PERILS, NO_PERIL,
+    },
+};
+use solana_program::{
+    clock::{Clock, UnixTimestamp},
+    hash::Hash,
+    instruction::InstructionError,
+    native_token::sol_to_lamports,
+    program_pack::Pack,
+    rent::Rent,
+    system_instruction,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account, signature::Keypair, system_transaction, transaction::TransactionError,
+    transport::TransportError,
+};
+use spl_token::state::{Account as TokenAccount, AccountState, Mint};
+use {
+    solana_program::pubkey::Pubkey,
+    solana_sdk::{signature::Signer, transaction::Transaction},
+};
+
+// Helper functions
+fn test_terms(premium_mint: &Pubkey, claims_adjuster: &Pubkey) -> PolicyTerms {
+    PolicyTerms {
+        coverage_start: 1_600_000_000,
+        coverage_end: 4_000_000_000,
+        sum_insured: 1_000_000,
+        premium_amount: 10_000,
+        premium_mint: *premium_mint,
+        claims_adjuster: *claims_adjuster,
+        keeper_reward: 0,
+        cancellation_penalty_bps: 1_000,
+        no_claims_bonus_bps: 1_000,
+        installment_count: 0,
+        installment_amount: 0,
+        installment_interval: 0,
+        grace_period: 0,
+        assignment_consent_required: false,
+        deductible: 0,
+        per_occurrence_limit: 0,
+        annual_aggregate_limit: 0,
+        perils: [Peril::default(); MAX_PERILS],
+        waiting_period: 0,
+    }
+}
+
+// Claim accounts allocated for every InsuranceContract of the tests
+const TEST_CLAIM_COUNT: u32 = 2;
+
+/// The native processor of the test harness can't resize an account in a cross-program
+/// invocation, so the accounts the program creates for an InsuranceContract are allocated
+/// up front and the program takes them over as if it had created them
+fn add_insurance_contract_accounts(
+    program: &mut ProgramTest,
+    insurance_contract_owner: &Pubkey,
+    insurance_contract_id: u32,
+    premium_mint: Option<&Pubkey>,
+) {
+    let mut add_account = |address: Pubkey, owner: Pubkey, data: Vec<u8>| {
+        program.add_account(
+            address,
+            Account {
+                lamports: Rent::default().minimum_balance(data.len()),
+                data,
+                owner,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+    };
+
+    let (insurance_contract_account, _) =
+        find_insurance_contract_address(&id(), insurance_contract_owner, insurance_contract_id);
+    add_account(
+        insurance_contract_account,
+        id(),
+        vec![0; INSURANCE_CONTRACT_DATA_LEN],
+    );
+    add_account(
+        find_policy_mint_address(&id(), &insurance_contract_account).0,
+        spl_token::id(),
+        vec![0; Mint::LEN],
+    );
+    add_account(
+        find_policy_token_address(&id(), &insurance_contract_account).0,
+        spl_token::id(),
+        vec![0; TokenAccount::LEN],
+    );
+    for claim_index in 0..TEST_CLAIM_COUNT {
+        add_account(
+            find_claim_address(&id(), &insurance_contract_account, claim_index).0,
+            id(),
+            vec![0; CLAIM_DATA_LEN],
+        );
+    }
+
+    // The vault is initialized along with its creation, it's added ready to use
+    if let Some(premium_mint) = premium_mint {
+        let (vault_account, _) = find_vault_address(&id(), &insurance_contract_account);
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount {
+            mint: *premium_mint,
+            owner: vault_account,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        }
+        .pack_into_slice(&mut data);
+        add_account(vault_account, spl_token::id(), data);
+    }
+}
+
+async fn save_insurance_contract(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    insurance_contract_id: u32,
+    terms: PolicyTerms,
+    insurance_contract_owner: &Keypair,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::save_insurance_contract(
+            &id(),
+            &insurance_contract_owner.pubkey(),
+            insurance_contract_id,
+            terms,
+        )
+        .unwrap()],
+        Some(&insurance_contract_owner.pubkey()),
+    );
+    transaction.sign(&[insurance_contract_owner], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn close_insurance_contract(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    insurance_contract_owner: &Keypair,
+    policyholder: &Keypair,
+    insurance_contract_account: &Pubkey,
+    policy_token_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::close_insurance_contract(
+            &id(),
+            &insurance_contract_owner.pubkey(),
+            insurance_contract_account,
+            &policyholder.pubkey(),
+            policy_token_account,
+        )
+        .unwrap()],
+        Some(&insurance_contract_owner.pubkey()),
+    );
+    transaction.sign(&[insurance_contract_owner, policyholder], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn pay_premium(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    premium_payer: &Keypair,
+    premium_payer_token_account: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    premium_mint: &Pubkey,
+    amount: u64,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::pay_premium(
+            &id(),
+            &premium_payer.pubkey(),
+            premium_payer_token_account,
+            insurance_contract_account,
+            premium_mint,
+            amount,
+            // The program config is not initialized, no protocol fee is charged
+            premium_payer_token_account,
+        )
+        .unwrap()],
+        Some(&premium_payer.pubkey()),
+    );
+    transaction.sign(&[premium_payer], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn submit_claim(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    policyholder: &Keypair,
+    insurance_contract_account: &Pubkey,
+    policy_token_account: &Pubkey,
+    claim_index: u32,
+    amount: u64,
+    incident_time: UnixTimestamp,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::submit_claim(
+            &id(),
+            &policyholder.pubkey(),
+            insurance_contract_account,
+            claim_index,
+            amount,
+            incident_time,
+            [5; 32],
+            NO_PERIL,
+            policy_token_account,
+        )
+        .unwrap()],
+        Some(&policyholder.pubkey()),
+    );
+    transaction.sign(&[policyholder], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn approve_claim(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    claims_adjuster: &Keypair,
+    insurance_contract_account: &Pubkey,
+    claim_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::approve_claim(
+            &id(),
+            &claims_adjuster.pubkey(),
+            insurance_contract_account,
+            claim_account,
+        )
+        .unwrap()],
+        Some(&claims_adjuster.pubkey()),
+    );
+    transaction.sign(&[claims_adjuster], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn pay_claim(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    claims_adjuster: &Keypair,
+    insurance_contract_account: &Pubkey,
+    claim_account: &Pubkey,
+    beneficiary_token_account: &Pubkey,
+    policy_token_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::pay_claim(
+            &id(),
+            &claims_adjuster.pubkey(),
+            insurance_contract_account,
+            claim_account,
+            beneficiary_token_account,
+            policy_token_account,
+        )
+        .unwrap()],
+        Some(&claims_adjuster.pubkey()),
+    );
+    transaction.sign(&[claims_adjuster], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn create_mint(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    payer: &Keypair,
+    mint: &Keypair,
+) -> Result<(), TransportError> {
+    let rent = banks_client.get_rent().await.unwrap();
+    let mut transaction = Transaction::new_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(Mint::LEN),
+                Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &payer.pubkey(),
+                None,
+                0,
+            )
+            .unwrap(),
+        ],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[payer, mint], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn create_token_account(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    payer: &Keypair,
+    account: &Keypair,
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+) -> Result<(), TransportError> {
+    let rent = banks_client.get_rent().await.unwrap();
+    let mut instructions = vec![
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &account.pubkey(),
+            rent.minimum_balance(TokenAccount::LEN),
+            TokenAccount::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap(),
+    ];
+    if amount > 0 {
+        instructions.push(
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                mint,
+                &account.pubkey(),
+                &payer.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        );
+    }
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    transaction.sign(&[payer, account], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn get_token_balance(banks_client: &mut BanksClient, token_account: &Pubkey) -> u64 {
+    let token_account = banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    TokenAccount::unpack(&token_account.data).unwrap().amount
+}
+
+async fn reclaim_insurance_contract(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    insurance_contract_owner: &Keypair,
+    insurance_contract_account: &Pubkey,
+    destination: &Pubkey,
+    premium_token_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::reclaim_insurance_contract(
+            &id(),
+            &insurance_contract_owner.pubkey(),
+            insurance_contract_account,
+            destination,
+            premium_token_account,
+        )
+        .unwrap()],
+        Some(&insurance_contract_owner.pubkey()),
+    );
+    transaction.sign(&[insurance_contract_owner], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn expire_insurance_contract(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    payer: &Keypair,
+    keeper: &Keypair,
+    insurance_contract_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::expire_insurance_contract(
+            &id(),
+            &keeper.pubkey(),
+            insurance_contract_account,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[payer, keeper], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn cancel_insurance_contract(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    insurance_contract_owner: &Keypair,
+    insurance_contract_account: &Pubkey,
+    refund_token_account: &Pubkey,
+    policy_token_account: &Pubkey,
+    reason: u8,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::cancel_insurance_contract(
+            &id(),
+            &insurance_contract_owner.pubkey(),
+            insurance_contract_account,
+            refund_token_account,
+            policy_token_account,
+            reason,
+        )
+        .unwrap()],
+        Some(&insurance_contract_owner.pubkey()),
+    );
+    transaction.sign(&[insurance_contract_owner], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn renew_insurance_contract(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    insurance_contract_owner: &Keypair,
+    premium_payer: &Keypair,
+    premium_payer_token_account: &Pubkey,
+    predecessor_insurance_contract_account: &Pubkey,
+    premium_mint: &Pubkey,
+    predecessor_policy_token_account: &Pubkey,
+    insurance_contract_id: u32,
+    coverage_end: UnixTimestamp,
+    premium_amount: u64,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::renew_insurance_contract(
+            &id(),
+            &insurance_contract_owner.pubkey(),
+            &premium_payer.pubkey(),
+            premium_payer_token_account,
+            predecessor_insurance_contract_account,
+            premium_mint,
+            predecessor_policy_token_account,
+            insurance_contract_id,
+            coverage_end,
+            premium_amount,
+            premium_payer_token_account,
+            None,
+        )
+        .unwrap()],
+        Some(&insurance_contract_owner.pubkey()),
+    );
+    transaction.sign(
+        &[insurance_contract_owner, premium_payer],
+        *recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn pay_installment(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    premium_payer: &Keypair,
+    premium_payer_token_account: &Pubkey,
+    insurance_contract_account: &Pubkey,
+    premium_mint: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::pay_installment(
+            &id(),
+            &premium_payer.pubkey(),
+            premium_payer_token_account,
+            insurance_contract_account,
+            premium_mint,
+            premium_payer_token_account,
+        )
+        .unwrap()],
+        Some(&premium_payer.pubkey()),
+    );
+    transaction.sign(&[premium_payer], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn lapse_insurance_contract(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    payer: &Keypair,
+    insurance_contract_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::lapse_insurance_contract(
+            &id(),
+            insurance_contract_account,
+        )
+        .unwrap()],
+        Some(&payer.pubkey()),
+    );
+    transaction.sign(&[payer], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn propose_authority(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    insurance_contract_owner: &Keypair,
+    insurance_contract_account: &Pubkey,
+    new_authority: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::propose_authority(
+            &id(),
+            &insurance_contract_owner.pubkey(),
+            insurance_contract_account,
+            new_authority,
+        )
+        .unwrap()],
+        Some(&insurance_contract_owner.pubkey()),
+    );
+    transaction.sign(&[insurance_contract_owner], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn accept_authority(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    new_authority: &Keypair,
+    insurance_contract_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::accept_authority(
+            &id(),
+            &new_authority.pubkey(),
+            insurance_contract_account,
+        )
+        .unwrap()],
+        Some(&new_authority.pubkey()),
+    );
+    transaction.sign(&[new_authority], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn cancel_authority_proposal(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    insurance_contract_owner: &Keypair,
+    insurance_contract_account: &Pubkey,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::cancel_authority_proposal(
+            &id(),
+            &insurance_contract_owner.pubkey(),
+            insurance_contract_account,
+        )
+        .unwrap()],
+        Some(&insurance_contract_owner.pubkey()),
+    );
+    transaction.sign(&[insurance_contract_owner], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn assign_policy(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    policyholder: &Keypair,
+    insurance_contract_account: &Pubkey,
+    policy_token_account: &Pubkey,
+    new_holder: &Pubkey,
+    new_holder_policy_token_account: &Pubkey,
+    insurance_contract_authority: Option<&Keypair>,
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(
+        &[insurance_contract::instruction::assign_policy(
+            &id(),
+            &policyholder.pubkey(),
+            insurance_contract_account,
+            new_holder,
+            Some((policy_token_account, new_holder_policy_token_account)),
+            insurance_contract_authority
+                .map(|authority| authority.pubkey())
+                .as_ref(),
+        )
+        .unwrap()],
+        Some(&policyholder.pubkey()),
+    );
+    let mut signers = vec![policyholder];
+    signers.extend(insurance_contract_authority);
+    transaction.sign(&signers, *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+async fn transfer_sol(
+    banks_client: &mut BanksClient,
+    recent_blockhash: &Hash,
+    from: &Keypair,
+    to: &Keypair,
+    amount_sol: f64,
+) -> Result<(), TransportError> {
+    let mut transaction = system_transaction::transfer(
+        from,
+        &to.pubkey(),
+        sol_to_lamports(amount_sol),
+        *recent_blockhash,
+    );
+    transaction.sign(&[from], *recent_blockhash);
+    banks_client.process_transaction(transaction).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_insurance_contract() {
+    let insurance_contract_owner = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        None,
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+
+    // SOL balance for insurance_contract_owner
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+
+    // Save InsuranceContract on-chain
+    let terms = test_terms(&Pubkey::new_unique(), &Pubkey::new_unique());
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(
+        insurance_contract_data.status,
+        InsuranceContractStatus::Draft
+    );
+    assert_eq!(
+        insurance_contract_data.insurance_contract_id,
+        insurance_contract_id
+    );
+    assert_eq!(
+        insurance_contract_data.authority,
+        insurance_contract_owner.pubkey()
+    );
+    assert_eq!(insurance_contract_data.coverage_end, terms.coverage_end);
+    assert_eq!(insurance_contract_data.premium_mint, terms.premium_mint);
+
+    // A single policy token is minted to the authority
+    let (policy_mint, _) = find_policy_mint_address(&id(), &insurance_contract_account);
+    assert_eq!(insurance_contract_data.policy_mint, policy_mint);
+    let policy_mint_acc = banks_client
+        .get_account(policy_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    let policy_mint_data = Mint::unpack(&policy_mint_acc.data).unwrap();
+    assert_eq!(policy_mint_data.supply, 1);
+    assert_eq!(policy_mint_data.decimals, 0);
+    assert!(policy_mint_data.mint_authority.is_none());
+    let policy_token_acc = banks_client
+        .get_account(policy_token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let policy_token = TokenAccount::unpack(&policy_token_acc.data).unwrap();
+    assert_eq!(policy_token.owner, insurance_contract_owner.pubkey());
+    assert_eq!(policy_token.amount, 1);
+
+    // BadCase: somebody else tries to close the InsuranceContract
+    let attacker = Keypair::new();
+    transfer_sol(&mut banks_client, &recent_blockhash, &payer, &attacker, 1.0)
+        .await
+        .unwrap();
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::AuthorityMismatch as u32)
+        ),
+        close_insurance_contract(
+            &mut banks_client,
+            &recent_blockhash,
+            &attacker,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &policy_token_account,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    close_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &policy_token_account,
+    )
+    .await
+    .unwrap();
+
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(
+        insurance_contract_data.status,
+        InsuranceContractStatus::Closed
+    );
+    assert_eq!(
+        insurance_contract_data.insurance_contract_id,
+        insurance_contract_id
+    );
+
+    // The policy token is burned
+    let policy_mint_acc = banks_client
+        .get_account(policy_mint)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Mint::unpack(&policy_mint_acc.data).unwrap().supply, 0);
+    assert!(banks_client
+        .get_account(policy_token_account)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_pay_premium() {
+    let insurance_contract_owner = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let premium_mint = Keypair::new();
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        Some(&premium_mint.pubkey()),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (vault_account, _) = find_vault_address(&id(), &insurance_contract_account);
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+
+    // Premium currency and the policyholder funds
+    create_mint(&mut banks_client, &recent_blockhash, &payer, &premium_mint)
+        .await
+        .unwrap();
+    let policyholder_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &policyholder_token_account,
+        &premium_mint.pubkey(),
+        &insurance_contract_owner.pubkey(),
+        20_000,
+    )
+    .await
+    .unwrap();
+
+    let terms = test_terms(&premium_mint.pubkey(), &Pubkey::new_unique());
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+
+    // BadCase: premium paid in another currency
+    let other_mint = Keypair::new();
+    create_mint(&mut banks_client, &recent_blockhash, &payer, &other_mint)
+        .await
+        .unwrap();
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::InvalidMint as u32)
+        ),
+        pay_premium(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &policyholder_token_account.pubkey(),
+            &insurance_contract_account,
+            &other_mint.pubkey(),
+            4_000,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    // First installment goes into the vault
+    pay_premium(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &policyholder_token_account.pubkey(),
+        &insurance_contract_account,
+        &premium_mint.pubkey(),
+        4_000,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut banks_client, &vault_account).await,
+        4_000
+    );
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(insurance_contract_data.premium_paid, 4_000);
+    assert_eq!(
+        insurance_contract_data.status,
+        InsuranceContractStatus::Draft
+    );
+
+    // BadCase: premium overpaid
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::InvalidPremiumAmount as u32)
+        ),
+        pay_premium(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &policyholder_token_account.pubkey(),
+            &insurance_contract_account,
+            &premium_mint.pubkey(),
+            terms.premium_amount,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    pay_premium(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &policyholder_token_account.pubkey(),
+        &insurance_contract_account,
+        &premium_mint.pubkey(),
+        6_000,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut banks_client, &vault_account).await,
+        terms.premium_amount
+    );
+    assert_eq!(
+        get_token_balance(&mut banks_client, &policyholder_token_account.pubkey()).await,
+        10_000
+    );
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(insurance_contract_data.premium_paid, terms.premium_amount);
+    assert_eq!(
+        insurance_contract_data.status,
+        InsuranceContractStatus::Active
+    );
+}
+
+#[tokio::test]
+async fn test_claim() {
+    let insurance_contract_owner = Keypair::new();
+    let claims_adjuster = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        None,
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+    let (claim_account, _) = find_claim_address(&id(), &insurance_contract_account, 0);
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &claims_adjuster,
+        1.0,
+    )
+    .await
+    .unwrap();
+
+    let terms = PolicyTerms {
+        premium_amount: 0,
+        ..test_terms(&Pubkey::new_unique(), &claims_adjuster.pubkey())
+    };
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+
+    // BadCase: incident happened before the coverage started
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::InvalidIncidentTime as u32)
+        ),
+        submit_claim(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &policy_token_account,
+            0,
+            5_000,
+            terms.coverage_start - 1,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    submit_claim(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &policy_token_account,
+        0,
+        5_000,
+        terms.coverage_start + 1,
+    )
+    .await
+    .unwrap();
+
+    // BadCase: the policyholder approves its own claim
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::AuthorityMismatch as u32)
+        ),
+        approve_claim(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &claim_account,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    approve_claim(
+        &mut banks_client,
+        &recent_blockhash,
+        &claims_adjuster,
+        &insurance_contract_account,
+        &claim_account,
+    )
+    .await
+    .unwrap();
+
+    let claim_acc = banks_client
+        .get_account(claim_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let claim_data = ClaimData::unpack(&claim_acc.data).unwrap();
+    assert_eq!(claim_data.status, ClaimStatus::Approved);
+    assert_eq!(claim_data.insurance_contract, insurance_contract_account);
+    assert_eq!(claim_data.amount, 5_000);
+}
+
+#[tokio::test]
+async fn test_pay_claim() {
+    let insurance_contract_owner = Keypair::new();
+    let claims_adjuster = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let premium_mint = Keypair::new();
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        Some(&premium_mint.pubkey()),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+    let (vault_account, _) = find_vault_address(&id(), &insurance_contract_account);
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &claims_adjuster,
+        1.0,
+    )
+    .await
+    .unwrap();
+
+    create_mint(&mut banks_client, &recent_blockhash, &payer, &premium_mint)
+        .await
+        .unwrap();
+    let policyholder_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &policyholder_token_account,
+        &premium_mint.pubkey(),
+        &insurance_contract_owner.pubkey(),
+        10_000,
+    )
+    .await
+    .unwrap();
+
+    // Fully paid contract, the vault holds the premium
+    let terms = test_terms(&premium_mint.pubkey(), &claims_adjuster.pubkey());
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+    pay_premium(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &policyholder_token_account.pubkey(),
+        &insurance_contract_account,
+        &premium_mint.pubkey(),
+        terms.premium_amount,
+    )
+    .await
+    .unwrap();
+
+    for (claim_index, amount) in [(0, 15_000), (1, 4_000)].iter() {
+        submit_claim(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &policy_token_account,
+            *claim_index,
+            *amount,
+            terms.coverage_start + 1,
+        )
+        .await
+        .unwrap();
+        approve_claim(
+            &mut banks_client,
+            &recent_blockhash,
+            &claims_adjuster,
+            &insurance_contract_account,
+            &find_claim_address(&id(), &insurance_contract_account, *claim_index).0,
+        )
+        .await
+        .unwrap();
+    }
+
+    // BadCase: vault can't cover the claim
+    let (first_claim_account, _) = find_claim_address(&id(), &insurance_contract_account, 0);
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::InsufficientVaultFunds as u32)
+        ),
+        pay_claim(
+            &mut banks_client,
+            &recent_blockhash,
+            &claims_adjuster,
+            &insurance_contract_account,
+            &first_claim_account,
+            &policyholder_token_account.pubkey(),
+            &policy_token_account,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    let (second_claim_account, _) = find_claim_address(&id(), &insurance_contract_account, 1);
+    pay_claim(
+        &mut banks_client,
+        &recent_blockhash,
+        &claims_adjuster,
+        &insurance_contract_account,
+        &second_claim_account,
+        &policyholder_token_account.pubkey(),
+        &policy_token_account,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut banks_client, &vault_account).await,
+        6_000
+    );
+    assert_eq!(
+        get_token_balance(&mut banks_client, &policyholder_token_account.pubkey()).await,
+        4_000
+    );
+    let claim_acc = banks_client
+        .get_account(second_claim_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        ClaimData::unpack(&claim_acc.data).unwrap().status,
+        ClaimStatus::Paid
+    );
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(insurance_contract_data.claims_paid, 4_000);
+}
+
+#[tokio::test]
+async fn test_reclaim_insurance_contract() {
+    let insurance_contract_owner = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        None,
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        test_terms(&Pubkey::new_unique(), &Pubkey::new_unique()),
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+    let insurance_contract_lamports = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap()
+        .lamports;
+
+    // BadCase: contract is not closed yet
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::InvalidContractStatus as u32)
+        ),
+        reclaim_insurance_contract(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    close_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &policy_token_account,
+    )
+    .await
+    .unwrap();
+    let destination = Pubkey::new_unique();
+    reclaim_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &destination,
+        &Pubkey::new_unique(),
+    )
+    .await
+    .unwrap();
+
+    assert!(banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        banks_client.get_balance(destination).await.unwrap(),
+        insurance_contract_lamports
+    );
+}
+
+#[tokio::test]
+async fn test_reclaim_expired_insurance_contract() {
+    let insurance_contract_owner = Keypair::new();
+    let premium_mint = Keypair::new();
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        1,
+        Some(&premium_mint.pubkey()),
+    );
+    let mut context = program.start_with_context().await;
+
+    let (insurance_contract_account, _) =
+        find_insurance_contract_address(&id(), &insurance_contract_owner.pubkey(), 1);
+    let (vault_account, _) = find_vault_address(&id(), &insurance_contract_account);
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+    transfer_sol(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        &context.payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+
+    create_mint(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        &context.payer,
+        &premium_mint,
+    )
+    .await
+    .unwrap();
+    let owner_token_account = Keypair::new();
+    create_token_account(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        &context.payer,
+        &owner_token_account,
+        &premium_mint.pubkey(),
+        &insurance_contract_owner.pubkey(),
+        10_000,
+    )
+    .await
+    .unwrap();
+
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let terms = PolicyTerms {
+        coverage_end: clock.unix_timestamp + 1,
+        ..test_terms(&premium_mint.pubkey(), &Pubkey::new_unique())
+    };
+    save_insurance_contract(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        1,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+    pay_premium(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        &insurance_contract_owner,
+        &owner_token_account.pubkey(),
+        &insurance_contract_account,
+        &premium_mint.pubkey(),
+        10_000,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &vault_account).await,
+        10_000
+    );
+
+    // Warp the clock past the end of the contract
+    context.warp_to_slot(2 * 60 * 60 * 1000 / 400).unwrap();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert!(clock.unix_timestamp >= terms.coverage_end);
+    let recent_blockhash = context.banks_client.get_recent_blockhash().await.unwrap();
+    expire_insurance_contract(
+        &mut context.banks_client,
+        &recent_blockhash,
+        &context.payer,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+    )
+    .await
+    .unwrap();
+    close_insurance_contract(
+        &mut context.banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &policy_token_account,
+    )
+    .await
+    .unwrap();
+
+    // The earned premium goes to the authority token account, the rent to the destination
+    let destination = Pubkey::new_unique();
+    reclaim_insurance_contract(
+        &mut context.banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &destination,
+        &owner_token_account.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        get_token_balance(&mut context.banks_client, &owner_token_account.pubkey()).await,
+        10_000
+    );
+    assert!(context
+        .banks_client
+        .get_account(vault_account)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(context
+        .banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(context.banks_client.get_balance(destination).await.unwrap() > 0);
+}
+
+#[tokio::test]
+async fn test_expire_insurance_contract() {
+    let insurance_contract_owner = Keypair::new();
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(&mut program, &insurance_contract_owner.pubkey(), 1, None);
+    add_insurance_contract_accounts(&mut program, &insurance_contract_owner.pubkey(), 2, None);
+    let mut context = program.start_with_context().await;
+
+    let keeper = Keypair::new();
+    transfer_sol(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        &context.payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+    transfer_sol(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        &context.payer,
+        &keeper,
+        1.0,
+    )
+    .await
+    .unwrap();
+
+    // One contract ends right after the current time, another one in the far future
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    let terms = PolicyTerms {
+        coverage_end: clock.unix_timestamp + 1,
+        premium_amount: 0,
+        keeper_reward: sol_to_lamports(0.01),
+        ..test_terms(&Pubkey::new_unique(), &Pubkey::new_unique())
+    };
+    let (insurance_contract_account, _) =
+        find_insurance_contract_address(&id(), &insurance_contract_owner.pubkey(), 1);
+    save_insurance_contract(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        1,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+    let (long_insurance_contract_account, _) =
+        find_insurance_contract_address(&id(), &insurance_contract_owner.pubkey(), 2);
+    save_insurance_contract(
+        &mut context.banks_client,
+        &context.last_blockhash,
+        2,
+        PolicyTerms {
+            coverage_end: 4_000_000_000,
+            ..terms
+        },
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+
+    // Warp the clock past the end of the first contract
+    context.warp_to_slot(2 * 60 * 60 * 1000 / 400).unwrap();
+    let clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+    assert!(clock.unix_timestamp >= terms.coverage_end);
+    let recent_blockhash = context.banks_client.get_recent_blockhash().await.unwrap();
+
+    // BadCase: coverage period is not over
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::CoverageNotEnded as u32)
+        ),
+        expire_insurance_contract(
+            &mut context.banks_client,
+            &recent_blockhash,
+            &context.payer,
+            &keeper,
+            &long_insurance_contract_account,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    let keeper_lamports = context
+        .banks_client
+        .get_balance(keeper.pubkey())
+        .await
+        .unwrap();
+    expire_insurance_contract(
+        &mut context.banks_client,
+        &recent_blockhash,
+        &context.payer,
+        &keeper,
+        &insurance_contract_account,
+    )
+    .await
+    .unwrap();
+
+    let insurance_contract_acc = context
+        .banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(
+        insurance_contract_data.status,
+        InsuranceContractStatus::Expired
+    );
+    let rent = context.banks_client.get_rent().await.unwrap();
+    assert_eq!(
+        insurance_contract_acc.lamports,
+        rent.minimum_balance(insurance_contract_acc.data.len())
+    );
+    assert_eq!(
+        context
+            .banks_client
+            .get_balance(keeper.pubkey())
+            .await
+            .unwrap(),
+        keeper_lamports + terms.keeper_reward
+    );
+}
+
+#[tokio::test]
+async fn test_cancel_insurance_contract() {
+    let insurance_contract_owner = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let premium_mint = Keypair::new();
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        Some(&premium_mint.pubkey()),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+    let (vault_account, _) = find_vault_address(&id(), &insurance_contract_account);
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+
+    create_mint(&mut banks_client, &recent_blockhash, &payer, &premium_mint)
+        .await
+        .unwrap();
+    let policyholder_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &policyholder_token_account,
+        &premium_mint.pubkey(),
+        &insurance_contract_owner.pubkey(),
+        10_000,
+    )
+    .await
+    .unwrap();
+
+    let terms = test_terms(&premium_mint.pubkey(), &Pubkey::new_unique());
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+    pay_premium(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &policyholder_token_account.pubkey(),
+        &insurance_contract_account,
+        &premium_mint.pubkey(),
+        terms.premium_amount,
+    )
+    .await
+    .unwrap();
+
+    // BadCase: refund is sent to someone else
+    let other_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &other_token_account,
+        &premium_mint.pubkey(),
+        &payer.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::BeneficiaryMismatch as u32)
+        ),
+        cancel_insurance_contract(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &other_token_account.pubkey(),
+            &policy_token_account,
+            3,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let clock = banks_client.get_sysvar::<Clock>().await.unwrap();
+    let refund = InsuranceContractData::unpack(&insurance_contract_acc.data)
+        .unwrap()
+        .cancellation_refund(clock.unix_timestamp);
+    assert!(refund > 0 && refund < terms.premium_amount);
+
+    cancel_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &policyholder_token_account.pubkey(),
+        &policy_token_account,
+        3,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut banks_client, &policyholder_token_account.pubkey()).await,
+        refund
+    );
+    assert_eq!(
+        get_token_balance(&mut banks_client, &vault_account).await,
+        terms.premium_amount - refund
+    );
+
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(
+        insurance_contract_data.status,
+        InsuranceContractStatus::Cancelled
+    );
+    assert_eq!(insurance_contract_data.cancellation_reason, 3);
+    assert_eq!(insurance_contract_data.premium_refunded, refund);
+}
+
+#[tokio::test]
+async fn test_renew_insurance_contract() {
+    let insurance_contract_owner = Keypair::new();
+    let premium_mint = Keypair::new();
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        1,
+        Some(&premium_mint.pubkey()),
+    );
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        2,
+        Some(&premium_mint.pubkey()),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (predecessor_account, _) =
+        find_insurance_contract_address(&id(), &insurance_contract_owner.pubkey(), 1);
+    let (successor_account, _) =
+        find_insurance_contract_address(&id(), &insurance_contract_owner.pubkey(), 2);
+    let (successor_vault_account, _) = find_vault_address(&id(), &successor_account);
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+    let policyholder = Keypair::new();
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &policyholder,
+        10.0,
+    )
+    .await
+    .unwrap();
+
+    create_mint(&mut banks_client, &recent_blockhash, &payer, &premium_mint)
+        .await
+        .unwrap();
+    let policyholder_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &policyholder_token_account,
+        &premium_mint.pubkey(),
+        &policyholder.pubkey(),
+        20_000,
+    )
+    .await
+    .unwrap();
+
+    let terms = test_terms(&premium_mint.pubkey(), &Pubkey::new_unique());
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        1,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+
+    // BadCase: Draft contract can't be renewed
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::InvalidContractStatus as u32)
+        ),
+        renew_insurance_contract(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &policyholder,
+            &policyholder_token_account.pubkey(),
+            &predecessor_account,
+            &premium_mint.pubkey(),
+            &find_policy_token_address(&id(), &predecessor_account).0,
+            2,
+            terms.coverage_end + 1,
+            terms.premium_amount,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    pay_premium(
+        &mut banks_client,
+        &recent_blockhash,
+        &policyholder,
+        &policyholder_token_account.pubkey(),
+        &predecessor_account,
+        &premium_mint.pubkey(),
+        terms.premium_amount,
+    )
+    .await
+    .unwrap();
+    renew_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &policyholder,
+        &policyholder_token_account.pubkey(),
+        &predecessor_account,
+        &premium_mint.pubkey(),
+        &find_policy_token_address(&id(), &predecessor_account).0,
+        2,
+        terms.coverage_end + 100_000_000,
+        terms.premium_amount,
+    )
+    .await
+    .unwrap();
+
+    // No claims were paid, the renewal premium paid by the policyholder is discounted
+    // by the no-claims bonus
+    assert_eq!(
+        get_token_balance(&mut banks_client, &successor_vault_account).await,
+        9_000
+    );
+    assert_eq!(
+        get_token_balance(&mut banks_client, &policyholder_token_account.pubkey()).await,
+        1_000
+    );
+
+    let predecessor_acc = banks_client
+        .get_account(predecessor_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let predecessor_data = InsuranceContractData::unpack(&predecessor_acc.data).unwrap();
+    assert_eq!(predecessor_data.successor, successor_account);
+
+    let successor_acc = banks_client
+        .get_account(successor_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let successor_data = InsuranceContractData::unpack(&successor_acc.data).unwrap();
+    assert_eq!(successor_data.status, InsuranceContractStatus::Active);
+    assert_eq!(successor_data.predecessor, predecessor_account);
+    assert_eq!(successor_data.coverage_start, terms.coverage_end);
+    assert_eq!(
+        successor_data.coverage_end,
+        terms.coverage_end + 100_000_000
+    );
+    assert_eq!(successor_data.claim_free_terms, 1);
+    assert_eq!(successor_data.premium_paid, 9_000);
+}
+
+#[tokio::test]
+async fn test_pay_installment_and_lapse() {
+    let insurance_contract_owner = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let premium_mint = Keypair::new();
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        Some(&premium_mint.pubkey()),
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (vault_account, _) = find_vault_address(&id(), &insurance_contract_account);
+    transfer_sol(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &insurance_contract_owner,
+        10.0,
+    )
+    .await
+    .unwrap();
+
+    create_mint(&mut banks_client, &recent_blockhash, &payer, &premium_mint)
+        .await
+        .unwrap();
+    let policyholder_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &policyholder_token_account,
+        &premium_mint.pubkey(),
+        &insurance_contract_owner.pubkey(),
+        10_000,
+    )
+    .await
+    .unwrap();
+
+    // The second installment is already overdue after the grace period
+    let clock = banks_client.get_sysvar::<Clock>().await.unwrap();
+    let terms = PolicyTerms {
+        coverage_start: clock.unix_timestamp - 100,
+        installment_count: 4,
+        installment_amount: 2_500,
+        installment_interval: 60,
+        grace_period: 10,
+        ..test_terms(&premium_mint.pubkey(), &Pubkey::new_unique())
+    };
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+    let status = |insurance_contract_acc: Option<solana_sdk::account::Account>| {
+        InsuranceContractData::unpack(&insurance_contract_acc.unwrap().data)
+            .unwrap()
+            .status
+    };
+
+    // BadCase: Draft contract can't lapse
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::InvalidContractStatus as u32)
+        ),
+        lapse_insurance_contract(
+            &mut banks_client,
+            &recent_blockhash,
+            &payer,
+            &insurance_contract_account,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    pay_installment(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &policyholder_token_account.pubkey(),
+        &insurance_contract_account,
+        &premium_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut banks_client, &vault_account).await,
+        2_500
+    );
+    assert_eq!(
+        status(
+            banks_client
+                .get_account(insurance_contract_account)
+                .await
+                .unwrap()
+        ),
+        InsuranceContractStatus::Active
+    );
+
+    // Anyone can lapse the contract
+    let keeper = Keypair::new();
+    transfer_sol(&mut banks_client, &recent_blockhash, &payer, &keeper, 1.0)
+        .await
+        .unwrap();
+    lapse_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        &keeper,
+        &insurance_contract_account,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        status(
+            banks_client
+                .get_account(insurance_contract_account)
+                .await
+                .unwrap()
+        ),
+        InsuranceContractStatus::Lapsed
+    );
+
+    // Paying the overdue installment reinstates the contract, anyone can pay it
+    let keeper_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &keeper_token_account,
+        &premium_mint.pubkey(),
+        &keeper.pubkey(),
+        2_500,
+    )
+    .await
+    .unwrap();
+    pay_installment(
+        &mut banks_client,
+        &recent_blockhash,
+        &keeper,
+        &keeper_token_account.pubkey(),
+        &insurance_contract_account,
+        &premium_mint.pubkey(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut banks_client, &vault_account).await,
+        5_000
+    );
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(
+        insurance_contract_data.status,
+        InsuranceContractStatus::Active
+    );
+    assert_eq!(insurance_contract_data.installments_paid, 2);
+    assert_eq!(insurance_contract_data.premium_paid, 5_000);
+}
+
+#[tokio::test]
+async fn test_authority_handover() {
+    let insurance_contract_owner = Keypair::new();
+    let new_authority = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        None,
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+    for keypair in &[&insurance_contract_owner, &new_authority] {
+        transfer_sol(&mut banks_client, &recent_blockhash, &payer, keypair, 10.0)
+            .await
+            .unwrap();
+    }
+
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        test_terms(&Pubkey::new_unique(), &Pubkey::new_unique()),
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+
+    propose_authority(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &Pubkey::new_unique(),
+    )
+    .await
+    .unwrap();
+    cancel_authority_proposal(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+    )
+    .await
+    .unwrap();
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(insurance_contract_data.pending_authority, Pubkey::default());
+
+    propose_authority(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &new_authority.pubkey(),
+    )
+    .await
+    .unwrap();
+
+    // BadCase: only the proposed authority can accept
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::AuthorityMismatch as u32)
+        ),
+        accept_authority(
+            &mut banks_client,
+            &recent_blockhash,
+            &payer,
+            &insurance_contract_account,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+    accept_authority(
+        &mut banks_client,
+        &recent_blockhash,
+        &new_authority,
+        &insurance_contract_account,
+    )
+    .await
+    .unwrap();
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(insurance_contract_data.authority, new_authority.pubkey());
+    assert_eq!(insurance_contract_data.pending_authority, Pubkey::default());
+
+    // BadCase: previous authority can't close the contract anymore
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::AuthorityMismatch as u32)
+        ),
+        close_insurance_contract(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &policy_token_account,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+    close_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        &new_authority,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &policy_token_account,
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_assign_policy() {
+    let insurance_contract_owner = Keypair::new();
+    let lender = Keypair::new();
+    let insurance_contract_id = 11223344;
+    let mut program = ProgramTest::new("insurance", id(), processor!(Processor::process));
+    add_insurance_contract_accounts(
+        &mut program,
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+        None,
+    );
+    let (mut banks_client, payer, recent_blockhash) = program.start().await;
+
+    let (insurance_contract_account, _) = find_insurance_contract_address(
+        &id(),
+        &insurance_contract_owner.pubkey(),
+        insurance_contract_id,
+    );
+    let (policy_token_account, _) = find_policy_token_address(&id(), &insurance_contract_account);
+    for keypair in &[&insurance_contract_owner, &lender] {
+        transfer_sol(&mut banks_client, &recent_blockhash, &payer, keypair, 10.0)
+            .await
+            .unwrap();
+    }
+
+    let terms = PolicyTerms {
+        premium_amount: 0,
+        assignment_consent_required: true,
+        ..test_terms(&Pubkey::new_unique(), &Pubkey::new_unique())
+    };
+    save_insurance_contract(
+        &mut banks_client,
+        &recent_blockhash,
+        insurance_contract_id,
+        terms,
+        &insurance_contract_owner,
+    )
+    .await
+    .unwrap();
+    let (policy_mint, _) = find_policy_mint_address(&id(), &insurance_contract_account);
+    let lender_policy_token_account = Keypair::new();
+    create_token_account(
+        &mut banks_client,
+        &recent_blockhash,
+        &payer,
+        &lender_policy_token_account,
+        &policy_mint,
+        &lender.pubkey(),
+        0,
+    )
+    .await
+    .unwrap();
+    assign_policy(
+        &mut banks_client,
+        &recent_blockhash,
+        &insurance_contract_owner,
+        &insurance_contract_account,
+        &policy_token_account,
+        &lender.pubkey(),
+        &lender_policy_token_account.pubkey(),
+        Some(&insurance_contract_owner),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_token_balance(&mut banks_client, &policy_token_account).await,
+        0
+    );
+    // The policy token stays frozen while assignments need the authority consent
+    let lender_policy_token_acc = banks_client
+        .get_account(lender_policy_token_account.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let lender_policy_token = TokenAccount::unpack(&lender_policy_token_acc.data).unwrap();
+    assert_eq!(lender_policy_token.amount, 1);
+    assert!(lender_policy_token.is_frozen());
+
+    // BadCase: the previous holder files a claim
+    assert_eq!(
+        TransactionError::InstructionError(
+            0,
+            InstructionError::Custom(InsuranceContractError::HolderMismatch as u32)
+        ),
+        submit_claim(
+            &mut banks_client,
+            &recent_blockhash,
+            &insurance_contract_owner,
+            &insurance_contract_account,
+            &policy_token_account,
+            0,
+            5_000,
+            terms.coverage_start + 1,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+    submit_claim(
+        &mut banks_client,
+        &recent_blockhash,
+        &lender,
+        &insurance_contract_account,
+        &lender_policy_token_account.pubkey(),
+        0,
+        5_000,
+        terms.coverage_start + 1,
+    )
+    .await
+    .unwrap();
+
+    // BadCase: the authority doesn't consent to the assignment
+    assert_eq!(
+        TransactionError::InstructionError(0, InstructionError::NotEnoughAccountKeys),
+        assign_policy(
+            &mut banks_client,
+            &recent_blockhash,
+            &lender,
+            &insurance_contract_account,
+            &lender_policy_token_account.pubkey(),
+            &insurance_contract_owner.pubkey(),
+            &policy_token_account,
+            None,
+        )
+        .await
+        .unwrap_err()
+        .unwrap()
+    );
+
+    let insurance_contract_acc = banks_client
+        .get_account(insurance_contract_account)
+        .await
+        .unwrap()
+        .unwrap();
+    let insurance_contract_data =
+        InsuranceContractData::unpack(&insurance_contract_acc.data).unwrap();
+    assert_eq!(insurance_contract_data.assignment_count, 1);
+    assert_eq!(
+        insurance_contract_data.assignment_history()[0].previous_holder,
+        insurance_contract_owner.pubkey()
+    );
+}